@@ -16,14 +16,15 @@
 //! # }
 //! ```
 
-use crate::{FetchError, Id, ToJsonString};
+use crate::{empty_as_none, FetchError, Id, ToJsonString};
 use log::debug;
 use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
 use std::collections::BTreeMap;
 use std::hash::{Hash, Hasher};
+use std::io::Read;
 
-const AIRPORT_FREQUENCIES_CSV_URL: &str =
+pub(crate) const AIRPORT_FREQUENCIES_CSV_URL: &str =
     "https://davidmegginson.github.io/ourairports-data/airport-frequencies.csv";
 
 /// Represents a single airport radio frequency for voice communication.
@@ -40,7 +41,8 @@ pub struct AirportFrequency {
     airport_ident: String,
     #[serde(rename = "type")]
     frequency_type: String, // TODO: make this an enum once the vocab is controlled
-    description: String,
+    #[serde(with = "empty_as_none")]
+    description: Option<String>,
     frequency_mhz: String,
 }
 
@@ -72,8 +74,13 @@ impl AirportFrequency {
         &self.frequency_type
     }
     /// A description of the frequency, typically the way a pilot would open a call on it.
+    pub fn description_opt(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+    /// Same as [`Self::description_opt()`], but returns an empty string if not available.
+    #[deprecated(since = "0.2.0", note = "use `description_opt()` instead")]
     pub fn description(&self) -> &str {
-        &self.description
+        self.description.as_deref().unwrap_or_default()
     }
     /// Radio voice frequency in megahertz.
     ///
@@ -121,9 +128,21 @@ pub fn get_airport_frequencies_csv() -> Result<BTreeMap<Id, AirportFrequency>, F
     // get data
     debug!("getting data");
     let content = crate::web_request_blocking(AIRPORT_FREQUENCIES_CSV_URL)?;
+    Ok(read_airport_frequencies_csv(content.as_bytes())?)
+}
+
+/// Reads [`AirportFrequency`] records in the OurAirports `airport_frequencies.csv` format from `reader`, returning a
+/// [`BTreeMap`] with its ID as the key, sorted according to its keys.
+///
+/// # Errors
+/// Returns [`csv::Error`] if the data cannot be read or there's something wrong with the
+/// de serialization process.
+pub fn read_airport_frequencies_csv<R: Read>(
+    reader: R,
+) -> Result<BTreeMap<Id, AirportFrequency>, csv::Error> {
     // initialise csv reader & return value
     debug!("initialising reader");
-    let mut rdr = csv::Reader::from_reader(content.as_bytes());
+    let mut rdr = csv::Reader::from_reader(reader);
     debug!("parsing and deserializing data");
     let mut map = BTreeMap::new();
     for result in rdr.deserialize() {
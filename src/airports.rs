@@ -13,7 +13,7 @@
 //!     let heathrow_airport = airports.get(&2434).unwrap();
 //!     assert_eq!(2434, heathrow_airport.id());
 //!     assert_eq!("EGLL", heathrow_airport.ident());
-//!     assert_eq!("LHR", heathrow_airport.iata_code());
+//!     assert_eq!(Some("LHR"), heathrow_airport.iata_code_opt());
 //!     assert_eq!(&AirportType::LargeAirport, heathrow_airport.airport_type());
 //!
 //! #    Ok(())
@@ -24,14 +24,27 @@ use log::debug;
 use std::cmp::Ordering;
 use std::collections::BTreeMap;
 use std::hash::{Hash, Hasher};
+use std::io::{Read, Write};
 
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
+use url::Url;
 
+use crate::codes::{AirportCodes, CountryCode, OurAirportsIdent, RegionCode};
+use crate::geo::{geojson_feature, geojson_point, Coordinate, ToGeoJson};
+use crate::magnetic::MagneticModel;
+use crate::runways::Runway;
+use crate::sun::{is_night, night_operations_possible, sun_times, SunTimes};
+#[cfg(feature = "tz")]
+use crate::time_zone::{time_zone, TimeZoneMatch};
+use crate::units::Elevation;
 use crate::{
-    bool_from_str, vec_string_from_string, Continent, FetchError, Id, ToJsonString,
+    bool_from_str, empty_as_none, parse_link, vec_string_from_string, Continent, FetchError, Id,
+    ToJsonString,
 };
 
-const AIRPORTS_CSV_URL: &str = "https://davidmegginson.github.io/ourairports-data/airports.csv";
+pub(crate) const AIRPORTS_CSV_URL: &str =
+    "https://davidmegginson.github.io/ourairports-data/airports.csv";
 
 /// Represents a single airport in the OurAirports data.
 ///
@@ -50,14 +63,20 @@ pub struct Airport {
     continent: Continent,
     iso_country: String,
     iso_region: String,
-    municipality: String,
+    #[serde(with = "empty_as_none")]
+    municipality: Option<String>,
     #[serde(deserialize_with = "bool_from_str")]
     scheduled_service: bool,
-    gps_code: String,
-    iata_code: String,
-    local_code: String,
-    home_link: String,
-    wikipedia_link: String,
+    #[serde(with = "empty_as_none")]
+    gps_code: Option<String>,
+    #[serde(with = "empty_as_none")]
+    iata_code: Option<String>,
+    #[serde(with = "empty_as_none")]
+    local_code: Option<String>,
+    #[serde(with = "empty_as_none")]
+    home_link: Option<String>,
+    #[serde(with = "empty_as_none")]
+    wikipedia_link: Option<String>,
     #[serde(deserialize_with = "vec_string_from_string")]
     keywords: Vec<String>,
 }
@@ -77,6 +96,13 @@ impl Airport {
     pub fn ident(&self) -> &str {
         &self.ident
     }
+    /// `true` if [`Self::ident()`] was generated by OurAirports (e.g. "GB-0001") because the
+    /// airport has no ICAO or local code, so it is not used outside OurAirports.
+    pub fn has_generated_ident(&self) -> bool {
+        self.ident
+            .parse::<OurAirportsIdent>()
+            .is_ok_and(|ident| ident.is_generated())
+    }
     /// The type of the airport. See [`AirportType`] for available values.
     pub fn airport_type(&self) -> &AirportType {
         &self.airport_type
@@ -93,10 +119,47 @@ impl Airport {
     pub fn longitude_deg(&self) -> f64 {
         self.longitude_deg
     }
+    /// The airport location as a [`Coordinate`].
+    pub fn coordinate(&self) -> Coordinate {
+        Coordinate::new(self.latitude_deg, self.longitude_deg)
+    }
     /// The airport elevation above MSL in feet (negative for altitude below MSL).
     pub fn elevation_ft(&self) -> Option<i32> {
         self.elevation_ft
     }
+    /// [`Self::elevation_ft()`] as an [`Elevation`]. Returns `None` if not available.
+    pub fn elevation(&self) -> Option<Elevation> {
+        self.elevation_ft.map(|ft| Elevation::feet(ft.into()))
+    }
+    /// Magnetic variation at the airport on `date` in degrees (positive east), from the embedded
+    /// [World Magnetic Model](crate::magnetic).
+    pub fn magnetic_variation(&self, date: NaiveDate) -> f64 {
+        let elevation_ft = f64::from(self.elevation_ft.unwrap_or(0));
+        MagneticModel::WMM2025
+            .field(&self.coordinate(), elevation_ft, date)
+            .declination_deg()
+    }
+    /// Sunrise, sunset and twilight times at the airport on `date`, see [`crate::sun`].
+    pub fn sun_times(&self, date: NaiveDate) -> SunTimes {
+        sun_times(&self.coordinate(), date)
+    }
+    /// `true` if it is night at the airport at `time`, i.e. between the end of evening and the
+    /// beginning of morning civil twilight.
+    pub fn is_night(&self, time: DateTime<Utc>) -> bool {
+        is_night(&self.coordinate(), time)
+    }
+    /// `true` if the airport can be used at night: it is not closed and one of its `runways`
+    /// (e.g. from [`AirportIndex::runways()`](crate::query::AirportIndex::runways())) is lighted
+    /// and not closed.
+    pub fn night_operations_possible(&self, runways: &[&Runway]) -> bool {
+        self.airport_type != AirportType::ClosedAirport && night_operations_possible(runways)
+    }
+    /// The IANA time zone of the airport, resolved offline from its coordinates and country, see
+    /// [`crate::time_zone`]. Requires the `tz` feature.
+    #[cfg(feature = "tz")]
+    pub fn time_zone(&self) -> Option<TimeZoneMatch> {
+        time_zone(&self.coordinate(), &self.iso_country)
+    }
     /// The continent where the airport is located. See [`Continent`] for possible values.
     pub fn continent(&self) -> &Continent {
         &self.continent
@@ -116,10 +179,32 @@ impl Airport {
     pub fn iso_region(&self) -> &str {
         &self.iso_region
     }
+    /// Parsed [`Self::iso_country()`]. Returns `None` if it is not a valid country code.
+    pub fn country_code(&self) -> Option<CountryCode> {
+        self.iso_country.parse().ok()
+    }
+    /// Parsed [`Self::iso_region()`]. Returns `None` if it is not a valid region code.
+    pub fn region_code(&self) -> Option<RegionCode> {
+        self.iso_region.parse().ok()
+    }
+    /// `false` if [`Self::iso_region()`] is in a different country than [`Self::iso_country()`].
+    ///
+    /// Invalid codes are always consistent.
+    pub fn has_consistent_region(&self) -> bool {
+        match (self.country_code(), self.region_code()) {
+            (Some(country), Some(region)) => region.country() == country,
+            _ => true,
+        }
+    }
     /// The primary municipality that the airport serves (when available).
     /// Note that this is not necessarily the municipality where the airport is physically located.
+    pub fn municipality_opt(&self) -> Option<&str> {
+        self.municipality.as_deref()
+    }
+    /// Same as [`Self::municipality_opt()`], but returns an empty string if not available.
+    #[deprecated(since = "0.2.0", note = "use `municipality_opt()` instead")]
     pub fn municipality(&self) -> &str {
-        &self.municipality
+        self.municipality.as_deref().unwrap_or_default()
     }
     /// `true` if the airport currently has scheduled airline service; `false` otherwise.
     pub fn scheduled_service(&self) -> bool {
@@ -128,26 +213,67 @@ impl Airport {
     /// The code that an aviation GPS database (such as Jeppesen's or Garmin's) would normally use
     /// for the airport. This will always be the [ICAO code](https://en.wikipedia.org/wiki/ICAO_airport_code)
     /// if one exists.
+    pub fn gps_code_opt(&self) -> Option<&str> {
+        self.gps_code.as_deref()
+    }
+    /// Same as [`Self::gps_code_opt()`], but returns an empty string if not available.
+    #[deprecated(since = "0.2.0", note = "use `gps_code_opt()` instead")]
     pub fn gps_code(&self) -> &str {
-        &self.gps_code
+        self.gps_code.as_deref().unwrap_or_default()
     }
     /// The three-letter [IATA code](https://en.wikipedia.org/wiki/International_Air_Transport_Association_code)
     /// for the airport (if it has one).
+    pub fn iata_code_opt(&self) -> Option<&str> {
+        self.iata_code.as_deref()
+    }
+    /// Same as [`Self::iata_code_opt()`], but returns an empty string if not available.
+    #[deprecated(since = "0.2.0", note = "use `iata_code_opt()` instead")]
     pub fn iata_code(&self) -> &str {
-        &self.iata_code
+        self.iata_code.as_deref().unwrap_or_default()
     }
-    /// The local country code for the airport, if different from the [`gps_code`](self.gps_code()) and
-    /// [`iata_code`](self.iata_code()) fields (used mainly for US airports).
+    /// The local country code for the airport, if different from the
+    /// [`gps_code`](Self::gps_code_opt()) and [`iata_code`](Self::iata_code_opt()) fields (used mainly for US airports).
+    pub fn local_code_opt(&self) -> Option<&str> {
+        self.local_code.as_deref()
+    }
+    /// Same as [`Self::local_code_opt()`], but returns an empty string if not available.
+    #[deprecated(since = "0.2.0", note = "use `local_code_opt()` instead")]
     pub fn local_code(&self) -> &str {
-        &self.local_code
+        self.local_code.as_deref().unwrap_or_default()
+    }
+    /// The typed identifier, ICAO, IATA and local codes of the airport.
+    pub fn codes(&self) -> AirportCodes {
+        AirportCodes::new(self)
     }
-    /// URL of the airport's official home page on the web, if one exists.
+    /// URL of the airport's official home page on the web, if one exists, as it appears in the
+    /// data. See [`Self::home_url()`] for the parsed URL.
+    pub fn home_link_opt(&self) -> Option<&str> {
+        self.home_link.as_deref()
+    }
+    /// Same as [`Self::home_link_opt()`], but returns an empty string if not available.
+    #[deprecated(since = "0.2.0", note = "use `home_link_opt()` instead")]
     pub fn home_link(&self) -> &str {
-        &self.home_link
+        self.home_link.as_deref().unwrap_or_default()
+    }
+    /// The airport's official home page as an `http` or `https` URL. Returns `None` if there is no
+    /// home page or the link is not a valid URL.
+    pub fn home_url(&self) -> Option<Url> {
+        parse_link(self.home_link.as_deref())
     }
-    /// URL of the airport's page on Wikipedia, if one exists.
+    /// URL of the airport's page on Wikipedia, if one exists, as it appears in the data. See
+    /// [`Self::wikipedia_url()`] for the parsed URL.
+    pub fn wikipedia_link_opt(&self) -> Option<&str> {
+        self.wikipedia_link.as_deref()
+    }
+    /// Same as [`Self::wikipedia_link_opt()`], but returns an empty string if not available.
+    #[deprecated(since = "0.2.0", note = "use `wikipedia_link_opt()` instead")]
     pub fn wikipedia_link(&self) -> &str {
-        &self.wikipedia_link
+        self.wikipedia_link.as_deref().unwrap_or_default()
+    }
+    /// The airport's Wikipedia page as an `http` or `https` URL. Returns `None` if there is no
+    /// page or the link is not a valid URL.
+    pub fn wikipedia_url(&self) -> Option<Url> {
+        parse_link(self.wikipedia_link.as_deref())
     }
     /// Extra keywords/phrases to assist with search. May include former names for the airport,
     /// alternate codes, names in other languages, nearby tourist destinations, etc. Each item
@@ -185,6 +311,15 @@ impl Hash for Airport {
 
 impl ToJsonString for Airport {}
 
+impl ToGeoJson for Airport {
+    fn to_geojson_feature(&self) -> Option<serde_json::Value> {
+        Some(geojson_feature(
+            geojson_point(&self.coordinate()),
+            serde_json::to_value(self).unwrap_or_default(),
+        ))
+    }
+}
+
 /// Possible types of airports.
 ///
 /// See [OurAirports map legend](https://ourairports.com/help/data-dictionary.html#airports)
@@ -219,9 +354,19 @@ pub fn get_airports_csv() -> Result<BTreeMap<Id, Airport>, FetchError> {
     // get data
     debug!("getting data");
     let content = crate::web_request_blocking(AIRPORTS_CSV_URL)?;
+    Ok(read_airports_csv(content.as_bytes())?)
+}
+
+/// Reads [`Airport`] records in the OurAirports `airports.csv` format from `reader`, returning a
+/// [`BTreeMap`] with its ID as the key, sorted according to its keys.
+///
+/// # Errors
+/// Returns [`csv::Error`] if the data cannot be read or there's something wrong with the
+/// de serialization process.
+pub fn read_airports_csv<R: Read>(reader: R) -> Result<BTreeMap<Id, Airport>, csv::Error> {
     // initialise csv reader & return value
     debug!("initialising CSV reader");
-    let mut rdr = csv::Reader::from_reader(content.as_bytes());
+    let mut rdr = csv::Reader::from_reader(reader);
     debug!("parsing and deserializing data");
     let mut map = BTreeMap::new();
     for result in rdr.deserialize() {
@@ -230,3 +375,69 @@ pub fn get_airports_csv() -> Result<BTreeMap<Id, Airport>, FetchError> {
     }
     Ok(map)
 }
+
+/// Writes `airports` to `writer` in the OurAirports `airports.csv` format, so that they can be
+/// read back with [`read_airports_csv()`].
+///
+/// # Errors
+/// Returns [`csv::Error`] if the data cannot be written.
+pub fn write_airports_csv<'a, W: Write>(
+    writer: W,
+    airports: impl IntoIterator<Item = &'a Airport>,
+) -> Result<(), csv::Error> {
+    let mut wtr = csv::Writer::from_writer(writer);
+    wtr.write_record([
+        "id",
+        "ident",
+        "type",
+        "name",
+        "latitude_deg",
+        "longitude_deg",
+        "elevation_ft",
+        "continent",
+        "iso_country",
+        "iso_region",
+        "municipality",
+        "scheduled_service",
+        "gps_code",
+        "iata_code",
+        "local_code",
+        "home_link",
+        "wikipedia_link",
+        "keywords",
+    ])?;
+    for airport in airports {
+        // serialize enums through serde to get the same codes as the source data
+        let airport_type = serde_json::to_value(&airport.airport_type).unwrap_or_default();
+        let continent = serde_json::to_value(&airport.continent).unwrap_or_default();
+        wtr.write_record([
+            airport.id.to_string().as_str(),
+            &airport.ident,
+            airport_type.as_str().unwrap_or_default(),
+            &airport.name,
+            &airport.latitude_deg.to_string(),
+            &airport.longitude_deg.to_string(),
+            &airport
+                .elevation_ft
+                .map(|e| e.to_string())
+                .unwrap_or_default(),
+            continent.as_str().unwrap_or_default(),
+            &airport.iso_country,
+            &airport.iso_region,
+            airport.municipality.as_deref().unwrap_or_default(),
+            if airport.scheduled_service {
+                "yes"
+            } else {
+                "no"
+            },
+            airport.gps_code.as_deref().unwrap_or_default(),
+            airport.iata_code.as_deref().unwrap_or_default(),
+            airport.local_code.as_deref().unwrap_or_default(),
+            airport.home_link.as_deref().unwrap_or_default(),
+            airport.wikipedia_link.as_deref().unwrap_or_default(),
+            &airport.keywords.join(", "),
+        ])?;
+    }
+    wtr.flush()?;
+    Ok(())
+}
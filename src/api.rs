@@ -0,0 +1,566 @@
+//! Contains a read-only JSON HTTP API over a [`Dataset`]. Requires the `server` feature.
+//!
+//! [`Api`] only maps a request method and target to a JSON response, so it can be used with any
+//! HTTP server. The `ourairports-server` binary serves it with
+//! [`tiny_http`](https://docs.rs/tiny_http).
+//!
+//! | Endpoint | Response |
+//! |----------|----------|
+//! | `GET /airports?country=..&type=..` | page of airports, see [`AirportQuery::param()`] for all parameters |
+//! | `GET /airports/{ident}` | airport |
+//! | `GET /airports/{ident}/runways` | page of runways |
+//! | `GET /airports/{ident}/frequencies` | page of frequencies |
+//! | `GET /navaids/near?lat=..&lon=..&radius=..` | page of navaids within `radius` NM (default 25) |
+//! | `GET /regions/{code}` | region |
+//! | `GET /countries/{code}` | country |
+//! | `GET /openapi.json` | [OpenAPI](https://www.openapis.org/) document, see [`openapi_document()`] |
+//!
+//! Pages are objects with the `total` number of matching records and the `items` in the page,
+//! selected with the `limit` (default 100, at most 1000) and `offset` parameters.
+//!
+//! # Examples
+//! ```
+//! use ourairports::api::*;
+//! use ourairports::dataset::Dataset;
+//!
+//! let dataset = Dataset::default();
+//! let api = Api::new(&dataset);
+//! let response = api.handle("GET", "/airports/EGLL");
+//! assert_eq!(404, response.status());
+//! assert_eq!(200, api.handle("GET", "/airports?country=GB&limit=10").status());
+//! ```
+
+use std::collections::HashMap;
+
+use percent_encoding::percent_decode_str;
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::airport_frequencies::AirportFrequency;
+use crate::countries::Country;
+use crate::dataset::Dataset;
+use crate::geo::{Coordinate, SpatialIndex};
+use crate::navaids::Navaid;
+use crate::query::{AirportIndex, AirportQuery};
+use crate::regions::Region;
+use crate::Id;
+
+/// Default number of items in a page.
+const DEFAULT_LIMIT: usize = 100;
+/// Maximum number of items in a page.
+const MAX_LIMIT: usize = 1000;
+/// Default radius of `/navaids/near` in nautical miles.
+const DEFAULT_NAVAID_RADIUS_NM: f64 = 25.0;
+/// Maximum radius of `/navaids/near` in nautical miles.
+const MAX_NAVAID_RADIUS_NM: f64 = 500.0;
+
+/// A JSON response to an API request.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ApiResponse {
+    status: u16,
+    body: Value,
+}
+
+impl ApiResponse {
+    fn ok(body: impl Serialize) -> Self {
+        ApiResponse {
+            status: 200,
+            body: serde_json::to_value(body).unwrap_or_default(),
+        }
+    }
+    fn error(status: u16, message: impl Into<String>) -> Self {
+        ApiResponse {
+            status,
+            body: json!({ "error": message.into() }),
+        }
+    }
+    /// HTTP status code of the response.
+    pub fn status(&self) -> u16 {
+        self.status
+    }
+    /// JSON body of the response.
+    pub fn body(&self) -> &Value {
+        &self.body
+    }
+}
+
+/// The API over a [`Dataset`], with indexes for answering requests.
+#[derive(Debug, Clone)]
+pub struct Api<'a> {
+    airports: AirportIndex<'a>,
+    frequencies: HashMap<Id, Vec<&'a AirportFrequency>>,
+    navaids: SpatialIndex<&'a Navaid>,
+    regions: HashMap<&'a str, &'a Region>,
+    countries: HashMap<&'a str, &'a Country>,
+}
+
+impl<'a> Api<'a> {
+    /// Builds the indexes needed to serve `dataset`.
+    pub fn new(dataset: &'a Dataset) -> Self {
+        let mut frequencies: HashMap<Id, Vec<&AirportFrequency>> = HashMap::new();
+        for frequency in dataset.airport_frequencies().values() {
+            frequencies
+                .entry(frequency.airport_ref())
+                .or_default()
+                .push(frequency);
+        }
+        Api {
+            airports: AirportIndex::with_runways(dataset.airports(), dataset.runways()),
+            frequencies,
+            navaids: dataset
+                .navaids()
+                .values()
+                .filter_map(|n| Some((n.coordinate()?, n)))
+                .collect(),
+            regions: dataset.regions().values().map(|r| (r.code(), r)).collect(),
+            countries: dataset
+                .countries()
+                .values()
+                .map(|c| (c.code(), c))
+                .collect(),
+        }
+    }
+
+    /// Handles a request with the given HTTP `method` and `target` (path and query string, e.g.
+    /// `/airports?country=GB`). Path segments and query parameters are percent-decoded.
+    pub fn handle(&self, method: &str, target: &str) -> ApiResponse {
+        if method != "GET" {
+            return ApiResponse::error(405, format!("method {} not allowed", method));
+        }
+        let (path, query) = target.split_once('?').unwrap_or((target, ""));
+        let params = url::form_urlencoded::parse(query.as_bytes())
+            .into_owned()
+            .collect::<Vec<_>>();
+        let segments = path
+            .split('/')
+            .filter(|s| !s.is_empty())
+            .map(|s| percent_decode_str(s).decode_utf8_lossy().into_owned())
+            .collect::<Vec<_>>();
+        let segments = segments.iter().map(String::as_str).collect::<Vec<_>>();
+        match segments.as_slice() {
+            ["airports"] => self.airports(&params),
+            ["airports", ident] => match self.airport_id(ident) {
+                Some(id) => ApiResponse::ok(self.airports.get(id)),
+                None => not_found("airport", ident),
+            },
+            ["airports", ident, "runways"] => match self.airport_id(ident) {
+                Some(id) => page(self.airports.runways(id), &params),
+                None => not_found("airport", ident),
+            },
+            ["airports", ident, "frequencies"] => match self.airport_id(ident) {
+                Some(id) => page(
+                    self.frequencies.get(&id).map_or(&[][..], Vec::as_slice),
+                    &params,
+                ),
+                None => not_found("airport", ident),
+            },
+            ["navaids", "near"] => self.navaids_near(&params),
+            ["regions", code] => match self.regions.get(code.to_uppercase().as_str()) {
+                Some(region) => ApiResponse::ok(region),
+                None => not_found("region", code),
+            },
+            ["countries", code] => match self.countries.get(code.to_uppercase().as_str()) {
+                Some(country) => ApiResponse::ok(country),
+                None => not_found("country", code),
+            },
+            ["openapi.json"] => ApiResponse::ok(openapi_document()),
+            _ => ApiResponse::error(404, format!("no such endpoint {}", path)),
+        }
+    }
+
+    fn airport_id(&self, ident: &str) -> Option<Id> {
+        self.airports
+            .get_by_ident(ident)
+            .or_else(|| self.airports.get_by_ident(&ident.to_uppercase()))
+            .map(|a| a.id())
+    }
+
+    fn airports(&self, params: &[(String, String)]) -> ApiResponse {
+        let (limit, offset) = match pagination(params) {
+            Ok(pagination) => pagination,
+            Err(response) => return response,
+        };
+        let query = AirportQuery::new().params(
+            params
+                .iter()
+                .filter(|(k, _)| k != "limit" && k != "offset")
+                .map(|(k, v)| (k, v)),
+        );
+        let query = match query {
+            Ok(query) => query,
+            Err(e) => return ApiResponse::error(400, e.to_string()),
+        };
+        let results = query.offset(offset).limit(limit).run(&self.airports);
+        ApiResponse::ok(json!({
+            "total": results.total(),
+            "offset": offset,
+            "limit": limit,
+            "items": results.airports(),
+        }))
+    }
+
+    fn navaids_near(&self, params: &[(String, String)]) -> ApiResponse {
+        let param = |key: &str| {
+            params
+                .iter()
+                .find(|(k, _)| k == key)
+                .map(|(_, v)| v.parse::<f64>().map_err(|_| invalid_param(key, v)))
+                .transpose()
+        };
+        let (lat, lon, radius) = match (param("lat"), param("lon"), param("radius")) {
+            (Ok(Some(lat)), Ok(Some(lon)), Ok(radius)) => (lat, lon, radius),
+            (Err(e), _, _) | (_, Err(e), _) | (_, _, Err(e)) => return e,
+            _ => return ApiResponse::error(400, "parameters \"lat\" and \"lon\" are required"),
+        };
+        let radius = radius
+            .unwrap_or(DEFAULT_NAVAID_RADIUS_NM)
+            .clamp(0.0, MAX_NAVAID_RADIUS_NM);
+        let navaids = self
+            .navaids
+            .within_radius(&Coordinate::new(lat, lon), radius)
+            .into_iter()
+            .map(|(navaid, distance)| json!({ "distance_nm": distance, "navaid": navaid }))
+            .collect::<Vec<_>>();
+        page(&navaids, params)
+    }
+}
+
+fn not_found(kind: &str, key: &str) -> ApiResponse {
+    ApiResponse::error(404, format!("{} \"{}\" not found", kind, key))
+}
+
+fn invalid_param(key: &str, value: &str) -> ApiResponse {
+    ApiResponse::error(
+        400,
+        format!("Invalid value for parameter \"{}\": \"{}\"", key, value),
+    )
+}
+
+/// Reads the `limit` and `offset` parameters.
+fn pagination(params: &[(String, String)]) -> Result<(usize, usize), ApiResponse> {
+    let mut limit = DEFAULT_LIMIT;
+    let mut offset = 0;
+    for (key, value) in params {
+        match key.as_str() {
+            "limit" => limit = value.parse().map_err(|_| invalid_param(key, value))?,
+            "offset" => offset = value.parse().map_err(|_| invalid_param(key, value))?,
+            _ => {}
+        }
+    }
+    Ok((limit.min(MAX_LIMIT), offset))
+}
+
+/// Returns a page of `items` selected by the `limit` and `offset` parameters.
+fn page<T: Serialize>(items: &[T], params: &[(String, String)]) -> ApiResponse {
+    let (limit, offset) = match pagination(params) {
+        Ok(pagination) => pagination,
+        Err(response) => return response,
+    };
+    ApiResponse::ok(json!({
+        "total": items.len(),
+        "offset": offset,
+        "limit": limit,
+        "items": items.iter().skip(offset).take(limit).collect::<Vec<_>>(),
+    }))
+}
+
+/// Returns the [OpenAPI 3](https://spec.openapis.org/oas/v3.0.3) document describing [`Api`].
+pub fn openapi_document() -> Value {
+    let string = json!({ "type": "string" });
+    let integer = json!({ "type": "integer" });
+    let number = json!({ "type": "number" });
+    let boolean = json!({ "type": "boolean" });
+    let nullable = |schema: &Value| {
+        let mut schema = schema.clone();
+        schema["nullable"] = json!(true);
+        schema
+    };
+    let strings = json!({ "type": "array", "items": string });
+    let object = |properties: Vec<(&str, &Value)>| {
+        json!({
+            "type": "object",
+            "properties": properties
+                .into_iter()
+                .map(|(k, v)| (k.to_string(), v.clone()))
+                .collect::<serde_json::Map<_, _>>(),
+        })
+    };
+    let schema_ref = |name: &str| json!({ "$ref": format!("#/components/schemas/{}", name) });
+    let page_of = |name: &str| {
+        json!({
+            "allOf": [
+                schema_ref("Page"),
+                { "type": "object", "properties": { "items": { "type": "array", "items": schema_ref(name) } } },
+            ]
+        })
+    };
+    let query_param = |name: &str, description: &str, schema: &Value| json!({ "name": name, "in": "query", "description": description, "schema": schema });
+    let path_param = |name: &str, description: &str| json!({ "name": name, "in": "path", "required": true, "description": description, "schema": string });
+    let response = |description: &str, schema: Value| {
+        json!({
+            "200": { "description": description, "content": { "application/json": { "schema": schema } } },
+            "400": { "$ref": "#/components/responses/Error" },
+            "404": { "$ref": "#/components/responses/Error" },
+        })
+    };
+    let pagination = [
+        query_param(
+            "limit",
+            "Maximum number of items (default 100, at most 1000)",
+            &integer,
+        ),
+        query_param("offset", "Number of items to skip", &integer),
+    ];
+    let with_pagination = |mut params: Vec<Value>| {
+        params.extend(pagination.iter().cloned());
+        params
+    };
+    let ident = path_param("ident", "Airport ident, see Airport.ident");
+
+    json!({
+        "openapi": "3.0.3",
+        "info": {
+            "title": "OurAirports API",
+            "description": "Read-only access to OurAirports data (https://ourairports.com/data/).",
+            "version": env!("CARGO_PKG_VERSION"),
+        },
+        "paths": {
+            "/airports": { "get": {
+                "summary": "Search airports",
+                "parameters": with_pagination(vec![
+                    query_param("type", "Comma-separated airport types, e.g. large_airport,medium_airport", &string),
+                    query_param("continent", "Comma-separated continent codes, e.g. EU,AS", &string),
+                    query_param("country", "Comma-separated ISO country codes", &string),
+                    query_param("region", "Comma-separated ISO region codes", &string),
+                    query_param("scheduled", "Has scheduled airline service", &boolean),
+                    query_param("iata", "Has an IATA code", &boolean),
+                    query_param("paved", "Has a paved runway which is not closed", &boolean),
+                    query_param("lighted", "Has a lighted runway which is not closed", &boolean),
+                    query_param("min_runway", "Minimum runway length in feet", &integer),
+                    query_param("min_elevation", "Minimum elevation in feet", &integer),
+                    query_param("max_elevation", "Maximum elevation in feet", &integer),
+                    query_param("bbox", "Bounding box as south,west,north,east", &string),
+                    query_param("near", "Circle as lat,lon,radius_nm", &string),
+                    query_param("sort", "id, ident, name, elevation, longest_runway or distance (requires near)", &string),
+                    query_param("order", "asc or desc", &string),
+                ]),
+                "responses": response("Page of airports", page_of("Airport")),
+            }},
+            "/airports/{ident}": { "get": {
+                "summary": "Get an airport",
+                "parameters": [ident],
+                "responses": response("Airport", schema_ref("Airport")),
+            }},
+            "/airports/{ident}/runways": { "get": {
+                "summary": "List the runways of an airport",
+                "parameters": with_pagination(vec![ident.clone()]),
+                "responses": response("Page of runways", page_of("Runway")),
+            }},
+            "/airports/{ident}/frequencies": { "get": {
+                "summary": "List the radio frequencies of an airport",
+                "parameters": with_pagination(vec![ident.clone()]),
+                "responses": response("Page of frequencies", page_of("AirportFrequency")),
+            }},
+            "/navaids/near": { "get": {
+                "summary": "List navaids near a position, nearest first",
+                "parameters": with_pagination(vec![
+                    query_param("lat", "Latitude in decimal degrees (required)", &number),
+                    query_param("lon", "Longitude in decimal degrees (required)", &number),
+                    query_param("radius", "Radius in nautical miles (default 25, at most 500)", &number),
+                ]),
+                "responses": response("Page of navaids", page_of("NavaidDistance")),
+            }},
+            "/regions/{code}": { "get": {
+                "summary": "Get a region",
+                "parameters": [path_param("code", "ISO region code, e.g. GB-ENG")],
+                "responses": response("Region", schema_ref("Region")),
+            }},
+            "/countries/{code}": { "get": {
+                "summary": "Get a country",
+                "parameters": [path_param("code", "ISO country code, e.g. GB")],
+                "responses": response("Country", schema_ref("Country")),
+            }},
+            "/openapi.json": { "get": {
+                "summary": "This document",
+                "responses": { "200": { "description": "OpenAPI document" } },
+            }},
+        },
+        "components": {
+            "responses": {
+                "Error": {
+                    "description": "Error",
+                    "content": { "application/json": { "schema": schema_ref("Error") } },
+                },
+            },
+            "schemas": {
+                "Error": object(vec![("error", &string)]),
+                "Page": object(vec![("total", &integer), ("offset", &integer), ("limit", &integer)]),
+                "Airport": object(vec![
+                    ("id", &integer), ("ident", &string), ("type", &string), ("name", &string),
+                    ("latitude_deg", &number), ("longitude_deg", &number),
+                    ("elevation_ft", &nullable(&integer)), ("continent", &string),
+                    ("iso_country", &string), ("iso_region", &string), ("municipality", &string),
+                    ("scheduled_service", &boolean), ("gps_code", &string), ("iata_code", &string),
+                    ("local_code", &string), ("home_link", &string), ("wikipedia_link", &string),
+                    ("keywords", &strings),
+                ]),
+                "Runway": object(vec![
+                    ("id", &integer), ("airport_ref", &integer), ("airport_ident", &string),
+                    ("length_ft", &nullable(&integer)), ("width_ft", &nullable(&integer)),
+                    ("surface", &string), ("lighted", &boolean), ("closed", &boolean),
+                    ("le_ident", &string), ("le_latitude_deg", &nullable(&number)),
+                    ("le_longitude_deg", &nullable(&number)), ("le_elevation_ft", &nullable(&integer)),
+                    ("le_heading_degT", &nullable(&number)),
+                    ("le_displaced_threshold_ft", &nullable(&integer)),
+                    ("he_ident", &string), ("he_latitude_deg", &nullable(&number)),
+                    ("he_longitude_deg", &nullable(&number)), ("he_elevation_ft", &nullable(&integer)),
+                    ("he_heading_degT", &nullable(&number)),
+                    ("he_displaced_threshold_ft", &nullable(&integer)),
+                ]),
+                "AirportFrequency": object(vec![
+                    ("id", &integer), ("airport_ref", &integer), ("airport_ident", &string),
+                    ("type", &string), ("description", &string), ("frequency_mhz", &string),
+                ]),
+                "Navaid": object(vec![
+                    ("id", &integer), ("filename", &string), ("ident", &string), ("name", &string),
+                    ("type", &string), ("frequency_khz", &string),
+                    ("latitude_deg", &nullable(&number)), ("longitude_deg", &nullable(&number)),
+                    ("elevation_ft", &nullable(&integer)), ("iso_country", &string),
+                    ("dme_frequency_khz", &string), ("dme_channel", &string),
+                    ("dme_latitude_deg", &nullable(&number)), ("dme_longitude_deg", &nullable(&number)),
+                    ("dme_elevation_ft", &nullable(&integer)),
+                    ("slaved_variation_deg", &nullable(&number)),
+                    ("magnetic_variation_deg", &nullable(&number)),
+                    ("usageType", &nullable(&string)), ("power", &nullable(&string)),
+                    ("associated_airport", &string),
+                ]),
+                "NavaidDistance": object(vec![
+                    ("distance_nm", &number), ("navaid", &schema_ref("Navaid")),
+                ]),
+                "Region": object(vec![
+                    ("id", &integer), ("code", &string), ("local_code", &string), ("name", &string),
+                    ("continent", &string), ("iso_country", &string), ("wikipedia_link", &string),
+                    ("keywords", &strings),
+                ]),
+                "Country": object(vec![
+                    ("id", &integer), ("code", &string), ("name", &string), ("continent", &string),
+                    ("wikipedia_link", &string), ("keywords", &strings),
+                ]),
+            },
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{self, airport, airport_frequency, country, navaid, region, runway};
+
+    fn dataset() -> Dataset {
+        Dataset::new(
+            test_support::airports([
+                airport(1, "EGLL").position(51.47, -0.46),
+                airport(2, "EGKK").position(51.15, -0.19),
+            ]),
+            test_support::airport_frequencies([
+                airport_frequency(1, 1, "TWR", "118.5"),
+                airport_frequency(2, 1, "ATIS", "128.075"),
+            ]),
+            test_support::countries([country(1, "GB", "EU")]),
+            test_support::navaids([navaid(1, "BIG", "VOR-DME").position(51.33, 0.03)]),
+            test_support::regions([region(1, "GB-ENG", "EU")]),
+            test_support::runways((1..=3).map(|id| runway(id, 1))),
+        )
+    }
+
+    fn idents(response: &ApiResponse) -> Vec<&str> {
+        response.body()["items"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|a| a["ident"].as_str().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn not_found_and_method_not_allowed() {
+        let dataset = dataset();
+        let api = Api::new(&dataset);
+        assert_eq!(404, api.handle("GET", "/").status());
+        assert_eq!(404, api.handle("GET", "/navaids").status());
+        assert_eq!(404, api.handle("GET", "/airports/EGLL/gates").status());
+        assert_eq!(404, api.handle("GET", "/airports/XXXX").status());
+        assert_eq!(404, api.handle("GET", "/airports/XXXX/runways").status());
+        assert_eq!(404, api.handle("GET", "/regions/GB-SCT").status());
+        assert_eq!(405, api.handle("POST", "/airports").status());
+        assert_eq!(405, api.handle("DELETE", "/airports/EGLL").status());
+    }
+
+    #[test]
+    fn invalid_parameters() {
+        let dataset = dataset();
+        let api = Api::new(&dataset);
+        for target in [
+            "/airports?limit=ten",
+            "/airports?limit=-1",
+            "/airports?offset=1.5",
+            "/airports?sort=distance",
+            "/airports/EGLL/runways?limit=x",
+            "/navaids/near?lat=north&lon=0",
+            "/navaids/near?lat=51",
+            "/navaids/near?lat=51&lon=0&radius=far",
+        ] {
+            let response = api.handle("GET", target);
+            assert_eq!(400, response.status(), "{}", target);
+            assert!(response.body()["error"].is_string());
+        }
+    }
+
+    #[test]
+    fn pagination() {
+        let dataset = dataset();
+        let api = Api::new(&dataset);
+        let response = api.handle("GET", "/airports?limit=5000");
+        assert_eq!(MAX_LIMIT, response.body()["limit"]);
+        assert_eq!(vec!["EGLL", "EGKK"], idents(&response));
+        let response = api.handle("GET", "/airports?limit=1&offset=1");
+        assert_eq!(2, response.body()["total"]);
+        assert_eq!(vec!["EGKK"], idents(&response));
+        let response = api.handle("GET", "/airports/EGLL/runways?offset=10");
+        assert_eq!(200, response.status());
+        assert_eq!(3, response.body()["total"]);
+        assert_eq!(json!([]), response.body()["items"]);
+    }
+
+    #[test]
+    fn lookups_ignore_case_and_decode_paths() {
+        let dataset = dataset();
+        let api = Api::new(&dataset);
+        for target in ["/airports/EGLL", "/airports/egll", "/airports/EG%4cL/"] {
+            let response = api.handle("GET", target);
+            assert_eq!(200, response.status(), "{}", target);
+            assert_eq!("EGLL", response.body()["ident"]);
+        }
+        assert_eq!(
+            2,
+            api.handle("GET", "/airports/egll/frequencies").body()["total"]
+        );
+        assert_eq!(200, api.handle("GET", "/countries/gb").status());
+        assert_eq!(200, api.handle("GET", "/regions/gb%2Deng").status());
+        assert_eq!(
+            "airport \"EG LL\" not found",
+            api.handle("GET", "/airports/EG%20LL").body()["error"]
+        );
+    }
+
+    #[test]
+    fn navaids_near() {
+        let dataset = dataset();
+        let api = Api::new(&dataset);
+        let response = api.handle("GET", "/navaids/near?lat=51.47&lon=-0.46");
+        assert_eq!(1, response.body()["total"]);
+        assert_eq!("BIG", response.body()["items"][0]["navaid"]["ident"]);
+        let response = api.handle("GET", "/navaids/near?lat=51.47&lon=-0.46&radius=5");
+        assert_eq!(0, response.body()["total"]);
+    }
+}
@@ -0,0 +1,69 @@
+//! HTTP server exposing OurAirports data as JSON, see the `api` module for the endpoints.
+//!
+//! Data is read from the directory given by `--data-dir` (or the `OURAIRPORTS_DATA_DIR`
+//! environment variable) if set, and fetched from OurAirports otherwise.
+
+use std::error::Error;
+use std::path::PathBuf;
+use std::thread;
+
+use clap::Parser;
+use log::{info, warn};
+use tiny_http::{Header, Response, Server};
+
+use ourairports::api::Api;
+use ourairports::dataset::Dataset;
+
+#[derive(Parser)]
+#[command(version, about = "Serve OurAirports data over HTTP")]
+struct Cli {
+    /// Directory containing OurAirports CSV files. Data is fetched from OurAirports if not set.
+    #[arg(long, env = "OURAIRPORTS_DATA_DIR")]
+    data_dir: Option<PathBuf>,
+    /// Address to listen on
+    #[arg(long, default_value = "127.0.0.1:8080")]
+    bind: String,
+    /// Number of worker threads
+    #[arg(long, default_value_t = 4)]
+    threads: usize,
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    env_logger::init();
+    let cli = Cli::parse();
+    let dataset = match &cli.data_dir {
+        Some(dir) => Dataset::read_dir(dir)?,
+        None => Dataset::fetch()?,
+    };
+    let api = Api::new(&dataset);
+    let server = Server::http(&cli.bind).map_err(|e| e as Box<dyn Error>)?;
+    info!("listening on http://{}", cli.bind);
+
+    thread::scope(|scope| {
+        for _ in 0..cli.threads.max(1) {
+            scope.spawn(|| {
+                for request in server.incoming_requests() {
+                    let response = api.handle(request.method().as_str(), request.url());
+                    info!(
+                        "{} {} {}",
+                        request.method(),
+                        request.url(),
+                        response.status()
+                    );
+                    let content_type = "Content-Type: application/json"
+                        .parse::<Header>()
+                        .expect("valid header");
+                    let result = request.respond(
+                        Response::from_string(response.body().to_string())
+                            .with_status_code(response.status())
+                            .with_header(content_type),
+                    );
+                    if let Err(e) = result {
+                        warn!("failed to send response: {}", e);
+                    }
+                }
+            });
+        }
+    });
+    Ok(())
+}
@@ -0,0 +1,263 @@
+//! Command-line interface for querying and exporting OurAirports data.
+//!
+//! Data is read from the directory given by `--data-dir` (or the `OURAIRPORTS_DATA_DIR`
+//! environment variable) if set, and fetched from OurAirports otherwise. Use
+//! `ourairports fetch --out <dir>` to save the data for offline use.
+
+use std::error::Error;
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clap::{Parser, Subcommand, ValueEnum};
+
+use ourairports::airports::{write_airports_csv, Airport};
+use ourairports::dataset::{download_to_dir, Dataset, DatasetKind};
+use ourairports::geo::{geojson_feature_collection, Coordinate, ToGeoJson};
+use ourairports::query::{AirportIndex, AirportQuery};
+use ourairports::search::SearchIndex;
+
+#[derive(Parser)]
+#[command(version, about = "Query and export OurAirports data")]
+struct Cli {
+    /// Directory containing OurAirports CSV files. Data is fetched from OurAirports if not set.
+    #[arg(long, global = true, env = "OURAIRPORTS_DATA_DIR")]
+    data_dir: Option<PathBuf>,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Show an airport with its runways and frequencies, by ident, ICAO, IATA, local code or ID
+    Lookup { code: String },
+    /// List the airports nearest to a position
+    Nearest {
+        #[arg(allow_negative_numbers = true)]
+        lat: f64,
+        #[arg(allow_negative_numbers = true)]
+        lon: f64,
+        /// Number of airports to list
+        #[arg(short = 'n', long, default_value_t = 10)]
+        count: usize,
+    },
+    /// Search airports by name, municipality, code, keywords, region or country
+    Search {
+        text: String,
+        /// Maximum number of results
+        #[arg(short = 'n', long, default_value_t = 10)]
+        count: usize,
+    },
+    /// Export airports matching the given filters
+    Export {
+        #[arg(long, value_enum, default_value_t = Format::Csv)]
+        format: Format,
+        /// Filter as KEY=VALUE, may be repeated. Keys: type, continent, country, region
+        /// (comma-separated lists), scheduled, iata, paved, lighted (true/false),
+        /// min_elevation, max_elevation, min_runway (feet), bbox (south,west,north,east),
+        /// near (lat,lon,radius_nm), sort, order (asc/desc), limit, offset
+        #[arg(long = "filter", value_name = "KEY=VALUE")]
+        filters: Vec<String>,
+        /// Output file, standard output if not set
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Compare two directories of OurAirports CSV files
+    Diff {
+        old_dir: PathBuf,
+        new_dir: PathBuf,
+        /// Print the IDs of all added, removed and changed records as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Check that references between datasets resolve
+    Validate,
+    /// Download the latest OurAirports CSV files
+    Fetch {
+        #[arg(long)]
+        out: PathBuf,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    Csv,
+    Json,
+    Geojson,
+}
+
+fn main() -> ExitCode {
+    env_logger::init();
+    match run(Cli::parse()) {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn load(data_dir: &Option<PathBuf>) -> Result<Dataset, Box<dyn Error>> {
+    Ok(match data_dir {
+        Some(dir) => Dataset::read_dir(dir)?,
+        None => Dataset::fetch()?,
+    })
+}
+
+fn run(cli: Cli) -> Result<ExitCode, Box<dyn Error>> {
+    match cli.command {
+        Command::Lookup { code } => {
+            let dataset = load(&cli.data_dir)?;
+            let matches = lookup(&dataset, &code);
+            if matches.is_empty() {
+                eprintln!("no airport found for \"{}\"", code);
+                return Ok(ExitCode::FAILURE);
+            }
+            let output = matches
+                .into_iter()
+                .map(|airport| {
+                    serde_json::json!({
+                        "airport": airport,
+                        "runways": dataset.runways().values()
+                            .filter(|r| r.airport_ref() == airport.id())
+                            .collect::<Vec<_>>(),
+                        "frequencies": dataset.airport_frequencies().values()
+                            .filter(|f| f.airport_ref() == airport.id())
+                            .collect::<Vec<_>>(),
+                    })
+                })
+                .collect::<Vec<_>>();
+            println!("{}", serde_json::to_string_pretty(&output)?);
+        }
+        Command::Nearest { lat, lon, count } => {
+            let dataset = load(&cli.data_dir)?;
+            let index = AirportIndex::new(dataset.airports());
+            for (airport, distance) in index.nearest(&Coordinate::new(lat, lon), count) {
+                println!(
+                    "{:<8} {:>8.1} nm  {}",
+                    airport.ident(),
+                    distance,
+                    airport.name()
+                );
+            }
+        }
+        Command::Search { text, count } => {
+            let dataset = load(&cli.data_dir)?;
+            let index = SearchIndex::with_regions_and_countries(
+                dataset.airports(),
+                dataset.regions(),
+                dataset.countries(),
+            );
+            for result in index.search(&text, count) {
+                let airport = result.airport();
+                println!(
+                    "{:<8} {:<4} {}, {}",
+                    airport.ident(),
+                    airport.iata_code_opt().unwrap_or_default(),
+                    airport.name(),
+                    airport.municipality_opt().unwrap_or_default()
+                );
+            }
+        }
+        Command::Export {
+            format,
+            filters,
+            output,
+        } => {
+            let query = parse_filters(&filters)?;
+            let dataset = load(&cli.data_dir)?;
+            let index = AirportIndex::with_runways(dataset.airports(), dataset.runways());
+            let airports = query.run(&index).into_airports();
+            let mut writer: Box<dyn Write> = match output {
+                Some(path) => Box::new(File::create(path)?),
+                None => Box::new(io::stdout().lock()),
+            };
+            match format {
+                Format::Csv => write_airports_csv(&mut writer, airports)?,
+                Format::Json => serde_json::to_writer_pretty(&mut writer, &airports)?,
+                Format::Geojson => serde_json::to_writer_pretty(
+                    &mut writer,
+                    &geojson_feature_collection(
+                        airports.iter().filter_map(|a| a.to_geojson_feature()),
+                    ),
+                )?,
+            }
+            writer.flush()?;
+        }
+        Command::Diff {
+            old_dir,
+            new_dir,
+            json,
+        } => {
+            let diff = Dataset::read_dir(old_dir)?.diff(&Dataset::read_dir(new_dir)?);
+            if json {
+                println!("{}", serde_json::to_string_pretty(&diff)?);
+            } else {
+                for kind in DatasetKind::ALL {
+                    let records = diff.get(kind);
+                    println!(
+                        "{}: {} added, {} removed, {} changed",
+                        kind,
+                        records.added().len(),
+                        records.removed().len(),
+                        records.changed().len()
+                    );
+                }
+            }
+        }
+        Command::Validate => {
+            let issues = load(&cli.data_dir)?.validate();
+            for issue in &issues {
+                println!("{}", issue);
+            }
+            if !issues.is_empty() {
+                eprintln!("{} issues found", issues.len());
+                return Ok(ExitCode::FAILURE);
+            }
+        }
+        Command::Fetch { out } => download_to_dir(out)?,
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Finds airports by ID, ident, GPS code, IATA code or local code (case-insensitive).
+fn lookup<'a>(dataset: &'a Dataset, code: &str) -> Vec<&'a Airport> {
+    if let Some(airport) = code.parse().ok().and_then(|id| dataset.airports().get(&id)) {
+        return vec![airport];
+    }
+    let code = code.to_uppercase();
+    fn ident(airport: &Airport) -> Option<&str> {
+        Some(airport.ident())
+    }
+    let by = |field: fn(&Airport) -> Option<&str>| {
+        dataset
+            .airports()
+            .values()
+            .filter(|a| field(a).is_some_and(|f| f.eq_ignore_ascii_case(&code)))
+            .collect::<Vec<_>>()
+    };
+    [
+        ident,
+        Airport::gps_code_opt,
+        Airport::iata_code_opt,
+        Airport::local_code_opt,
+    ]
+    .into_iter()
+    .map(by)
+    .find(|matches| !matches.is_empty())
+    .unwrap_or_default()
+}
+
+/// Parses `--filter KEY=VALUE` arguments into a query.
+fn parse_filters(filters: &[String]) -> Result<AirportQuery, Box<dyn Error>> {
+    let params = filters
+        .iter()
+        .map(|filter| {
+            filter
+                .split_once('=')
+                .ok_or_else(|| format!("filter \"{}\" is not of the form KEY=VALUE", filter))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(AirportQuery::new().params(params)?)
+}
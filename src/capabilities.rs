@@ -0,0 +1,248 @@
+//! Contains a summary of what each airport offers, derived from its runways and frequencies.
+//!
+//! # Examples
+//! ```no_run
+//! use ourairports::capabilities::airport_capabilities;
+//! use ourairports::dataset::Dataset;
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!     let dataset = Dataset::fetch()?;
+//!     let capabilities = airport_capabilities(
+//!         dataset.airports(),
+//!         dataset.runways(),
+//!         dataset.airport_frequencies(),
+//!     );
+//!
+//!     // airports with a paved, lighted runway of at least 6000 ft and a tower
+//!     let night_jet_capable = capabilities
+//!         .values()
+//!         .filter(|c| c.longest_paved_lighted_runway_ft().is_some_and(|l| l >= 6000))
+//!         .filter(|c| c.towered())
+//!         .count();
+//!     println!("{} airports", night_jet_capable);
+//! #    Ok(())
+//! # }
+//! ```
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::airport_frequencies::AirportFrequency;
+use crate::airports::{Airport, AirportType};
+use crate::runways::Runway;
+use crate::Id;
+
+/// Frequency types which indicate a control tower.
+const TOWER_FREQUENCY_TYPES: [&str; 2] = ["TWR", "TOWER"];
+
+/// Frequency types which indicate an ATIS broadcast.
+const ATIS_FREQUENCY_TYPES: [&str; 2] = ["ATIS", "D-ATIS"];
+
+/// Summary of the facilities of an airport.
+///
+/// Usable runways are runways which are not [closed](Runway::closed()), helipads or water
+/// runways. Helipads and water runways are counted separately.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct AirportCapabilities {
+    airport_id: Id,
+    airport_type: AirportType,
+    usable_runway_count: usize,
+    longest_runway_ft: Option<i32>,
+    widest_runway_ft: Option<i32>,
+    longest_paved_lighted_runway_ft: Option<i32>,
+    has_paved_runway: bool,
+    has_lighted_runway: bool,
+    helipad_count: usize,
+    has_water_runway: bool,
+    towered: bool,
+    atis_frequency_mhz: Option<String>,
+}
+
+impl AirportCapabilities {
+    /// Capabilities of an airport without runways or frequencies.
+    fn new(airport: &Airport) -> Self {
+        AirportCapabilities {
+            airport_id: airport.id(),
+            airport_type: airport.airport_type().clone(),
+            usable_runway_count: 0,
+            longest_runway_ft: None,
+            widest_runway_ft: None,
+            longest_paved_lighted_runway_ft: None,
+            has_paved_runway: false,
+            has_lighted_runway: false,
+            helipad_count: 0,
+            has_water_runway: false,
+            towered: false,
+            atis_frequency_mhz: None,
+        }
+    }
+    fn add_runway(&mut self, runway: &Runway) {
+        if runway.closed() {
+            return;
+        }
+        if runway.helipad() {
+            self.helipad_count += 1;
+            return;
+        }
+        if runway.water() {
+            self.has_water_runway = true;
+            return;
+        }
+        self.usable_runway_count += 1;
+        self.longest_runway_ft = self.longest_runway_ft.max(runway.length_ft());
+        self.widest_runway_ft = self.widest_runway_ft.max(runway.width_ft());
+        self.has_paved_runway |= runway.paved();
+        self.has_lighted_runway |= runway.lighted();
+        if runway.paved() && runway.lighted() {
+            self.longest_paved_lighted_runway_ft =
+                self.longest_paved_lighted_runway_ft.max(runway.length_ft());
+        }
+    }
+    fn add_frequency(&mut self, frequency: &AirportFrequency) {
+        let frequency_type = frequency.frequency_type().trim().to_uppercase();
+        if TOWER_FREQUENCY_TYPES.contains(&frequency_type.as_str()) {
+            self.towered = true;
+        }
+        if self.atis_frequency_mhz.is_none()
+            && ATIS_FREQUENCY_TYPES.contains(&frequency_type.as_str())
+        {
+            self.atis_frequency_mhz = Some(frequency.frequency_mhz().to_string());
+        }
+    }
+    /// Internal OurAirports integer identifier for the airport.
+    pub fn airport_id(&self) -> Id {
+        self.airport_id
+    }
+    /// Type of the airport.
+    pub fn airport_type(&self) -> &AirportType {
+        &self.airport_type
+    }
+    /// Number of usable runways.
+    pub fn usable_runway_count(&self) -> usize {
+        self.usable_runway_count
+    }
+    /// Length of the longest usable runway in feet. Returns `None` if there are no usable
+    /// runways with a known length.
+    pub fn longest_runway_ft(&self) -> Option<i32> {
+        self.longest_runway_ft
+    }
+    /// Width of the widest usable runway in feet. Returns `None` if there are no usable
+    /// runways with a known width.
+    pub fn widest_runway_ft(&self) -> Option<i32> {
+        self.widest_runway_ft
+    }
+    /// Length of the longest usable runway which is both paved and lighted, in feet. Returns
+    /// `None` if there is no such runway with a known length.
+    pub fn longest_paved_lighted_runway_ft(&self) -> Option<i32> {
+        self.longest_paved_lighted_runway_ft
+    }
+    /// `true` if any usable runway is [paved](Runway::paved()).
+    pub fn has_paved_runway(&self) -> bool {
+        self.has_paved_runway
+    }
+    /// `true` if any usable runway is lighted.
+    pub fn has_lighted_runway(&self) -> bool {
+        self.has_lighted_runway
+    }
+    /// Number of helipads which are not closed.
+    pub fn helipad_count(&self) -> usize {
+        self.helipad_count
+    }
+    /// `true` if there is a water runway which is not closed.
+    pub fn has_water_runway(&self) -> bool {
+        self.has_water_runway
+    }
+    /// `true` if the airport has a tower frequency.
+    pub fn towered(&self) -> bool {
+        self.towered
+    }
+    /// Frequency of the ATIS broadcast in megahertz, if the airport has one.
+    pub fn atis_frequency_mhz(&self) -> Option<&str> {
+        self.atis_frequency_mhz.as_deref()
+    }
+}
+
+/// Returns the [`AirportCapabilities`] of every airport in `airports`, with the airport ID as
+/// the key.
+///
+/// Each dataset is read once; runways and frequencies of airports not in `airports` are ignored.
+pub fn airport_capabilities(
+    airports: &BTreeMap<Id, Airport>,
+    runways: &BTreeMap<Id, Runway>,
+    frequencies: &BTreeMap<Id, AirportFrequency>,
+) -> BTreeMap<Id, AirportCapabilities> {
+    let mut capabilities = airports
+        .values()
+        .map(|airport| (airport.id(), AirportCapabilities::new(airport)))
+        .collect::<BTreeMap<_, _>>();
+    for runway in runways.values() {
+        if let Some(c) = capabilities.get_mut(&runway.airport_ref()) {
+            c.add_runway(runway);
+        }
+    }
+    for frequency in frequencies.values() {
+        if let Some(c) = capabilities.get_mut(&frequency.airport_ref()) {
+            c.add_frequency(frequency);
+        }
+    }
+    capabilities
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{self, airport, airport_frequency, runway};
+
+    #[test]
+    fn runways_and_frequencies() {
+        let airports = test_support::airports([airport(1, "AAAA"), airport(2, "BBBB")]);
+        let runway = |id, length_ft, width_ft, surface, lighted| {
+            runway(id, 1)
+                .set("length_ft", length_ft)
+                .set("width_ft", width_ft)
+                .set("surface", surface)
+                .set("lighted", lighted)
+                .set("le_ident", "09")
+        };
+        let runways = test_support::runways([
+            runway(1, 8000, 150, "ASP", 1),
+            runway(2, 3000, 200, "GRS", 0),
+            runway(3, 12000, 300, "CON", 1).set("closed", 1),
+            runway(4, 100, 100, "ASP", 1).set("le_ident", "H1"),
+            runway(5, 9000, 100, "ASP", 1).set("le_ident", "H2"),
+            runway(6, 10000, 500, "", 0).set("le_ident", "18W"),
+            runway(7, 10000, 500, "WATER", 0).set("le_ident", "ALL"),
+            // an unknown airport
+            test_support::runway(8, 3),
+        ]);
+        let frequencies = test_support::airport_frequencies([
+            airport_frequency(1, 1, "ATIS", "127.1"),
+            airport_frequency(2, 1, "D-ATIS", "128.2"),
+            airport_frequency(3, 1, " twr ", "118.5"),
+            airport_frequency(4, 2, "CTAF", "122.8"),
+            airport_frequency(5, 2, "TWR/APP", "119.1"),
+            airport_frequency(6, 3, "TWR", "118.0"),
+        ]);
+        let capabilities = airport_capabilities(&airports, &runways, &frequencies);
+        assert_eq!(vec![1, 2], capabilities.keys().copied().collect::<Vec<_>>());
+
+        let c = &capabilities[&1];
+        assert_eq!(2, c.usable_runway_count());
+        assert_eq!(Some(8000), c.longest_runway_ft());
+        assert_eq!(Some(200), c.widest_runway_ft());
+        assert_eq!(Some(8000), c.longest_paved_lighted_runway_ft());
+        assert!(c.has_paved_runway() && c.has_lighted_runway());
+        assert_eq!(2, c.helipad_count());
+        assert!(c.has_water_runway());
+        assert!(c.towered());
+        assert_eq!(Some("127.1"), c.atis_frequency_mhz());
+
+        let c = &capabilities[&2];
+        assert_eq!(0, c.usable_runway_count());
+        assert_eq!(None, c.longest_runway_ft());
+        assert!(!c.has_paved_runway() && !c.has_water_runway());
+        assert!(!c.towered());
+        assert_eq!(None, c.atis_frequency_mhz());
+    }
+}
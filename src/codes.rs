@@ -0,0 +1,619 @@
+//! Contains typed, validated codes used in the OurAirports data.
+//!
+//! The records keep their codes as strings, so that data with invalid codes can still be read;
+//! the typed codes are available from accessors such as [`Airport::country_code()`],
+//! [`Airport::region_code()`] and [`Airport::codes()`].
+//!
+//! # Examples
+//! ```
+//! use ourairports::codes::*;
+//!
+//! let region: RegionCode = "GB-ENG".parse().unwrap();
+//! assert_eq!("GB", region.country().as_str());
+//! assert_eq!("ENG", region.local_code());
+//! assert!(!region.is_unassigned());
+//!
+//! let unassigned: RegionCode = "US-U-A".parse().unwrap();
+//! assert!(unassigned.is_unassigned());
+//!
+//! // Kosovo uses a code which is not part of ISO 3166-1
+//! let kosovo: CountryCode = "XK".parse().unwrap();
+//! assert!(kosovo.is_user_assigned());
+//!
+//! assert!("GBR".parse::<CountryCode>().is_err());
+//! assert!("GB".parse::<RegionCode>().is_err());
+//!
+//! // identifiers generated by OurAirports for airports without a code
+//! let generated: OurAirportsIdent = "GB-0001".parse().unwrap();
+//! assert!(generated.is_generated());
+//! assert_eq!(None, generated.icao_code());
+//! let heathrow: OurAirportsIdent = "EGLL".parse().unwrap();
+//! assert_eq!(Some("EGLL".parse().unwrap()), heathrow.icao_code());
+//! ```
+//!
+//! [`Airport::country_code()`]: crate::airports::Airport::country_code()
+//! [`Airport::region_code()`]: crate::airports::Airport::region_code()
+//! [`Airport::codes()`]: crate::airports::Airport::codes()
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::airports::Airport;
+
+/// Local code of the pseudo-region of each country for airports not (yet) assigned to a region.
+const UNASSIGNED_LOCAL_CODE: &str = "U-A";
+
+/// Maximum length of the local part of a [`RegionCode`]. ISO 3166-2 uses at most three
+/// characters, but OurAirports has some unofficial codes.
+const MAX_LOCAL_CODE_LEN: usize = 6;
+
+/// Maximum length of a [`LocalCode`].
+const MAX_LOCAL_AIRPORT_CODE_LEN: usize = 8;
+
+/// A two-letter [ISO 3166-1 alpha-2](https://en.wikipedia.org/wiki/ISO_3166-1_alpha-2) country
+/// code, e.g. "GB".
+///
+/// Codes are not checked against the list of assigned codes, so the unofficial codes used by
+/// OurAirports, such as "XK" for Kosovo, are valid.
+#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct CountryCode([u8; 2]);
+
+impl CountryCode {
+    /// The code as a string.
+    pub fn as_str(&self) -> &str {
+        letters_str(&self.0)
+    }
+    /// `true` if the code is in one of the ranges ISO 3166-1 leaves for user assignment (AA,
+    /// QM to QZ, XA to XZ and ZZ), such as "XK" for Kosovo.
+    pub fn is_user_assigned(&self) -> bool {
+        matches!(
+            self.0,
+            [b'A', b'A'] | [b'Q', b'M'..=b'Z'] | [b'X', _] | [b'Z', b'Z']
+        )
+    }
+}
+
+impl fmt::Debug for CountryCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("CountryCode").field(&self.as_str()).finish()
+    }
+}
+
+impl fmt::Display for CountryCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Error returned when parsing an invalid [`CountryCode`].
+#[derive(thiserror::Error, Debug, Clone, Eq, PartialEq)]
+#[error("invalid country code \"{0}\"")]
+pub struct ParseCountryCodeError(String);
+
+impl FromStr for CountryCode {
+    type Err = ParseCountryCodeError;
+
+    /// Parses a code of two ASCII letters (case-insensitive).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_letters(s)
+            .map(CountryCode)
+            .ok_or_else(|| ParseCountryCodeError(s.to_string()))
+    }
+}
+
+impl PartialEq<str> for CountryCode {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl Serialize for CountryCode {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for CountryCode {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(de::Error::custom)
+    }
+}
+
+/// A region (high-level administrative subdivision) code: a [`CountryCode`] and a local code
+/// separated by a hyphen, e.g. "GB-ENG".
+///
+/// Local codes are usually [ISO 3166-2](https://en.wikipedia.org/wiki/ISO_3166-2) codes of up to
+/// three letters or digits, but OurAirports uses some longer unofficial ones, as well as the
+/// pseudo-region "U-A" of each country for airports not assigned to a region.
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct RegionCode {
+    country: CountryCode,
+    local_code: String,
+}
+
+impl RegionCode {
+    /// The unassigned pseudo-region of `country`, e.g. "GB-U-A".
+    pub fn unassigned(country: CountryCode) -> Self {
+        RegionCode {
+            country,
+            local_code: UNASSIGNED_LOCAL_CODE.to_string(),
+        }
+    }
+    /// The country containing the region.
+    pub fn country(&self) -> CountryCode {
+        self.country
+    }
+    /// The code of the region within its country, e.g. "ENG".
+    pub fn local_code(&self) -> &str {
+        &self.local_code
+    }
+    /// `true` if this is the pseudo-region "U-A" of airports not assigned to a region.
+    pub fn is_unassigned(&self) -> bool {
+        self.local_code == UNASSIGNED_LOCAL_CODE
+    }
+}
+
+impl fmt::Display for RegionCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}", self.country, self.local_code)
+    }
+}
+
+/// Error returned when parsing an invalid [`RegionCode`].
+#[derive(thiserror::Error, Debug, Clone, Eq, PartialEq)]
+#[error("invalid region code \"{0}\"")]
+pub struct ParseRegionCodeError(String);
+
+impl FromStr for RegionCode {
+    type Err = ParseRegionCodeError;
+
+    /// Parses a code such as "GB-ENG" or "US-U-A" (case-insensitive).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || ParseRegionCodeError(s.to_string());
+        let (country, local_code) = s.split_once('-').ok_or_else(error)?;
+        let country = country.parse().map_err(|_| error())?;
+        let local_code = local_code.to_ascii_uppercase();
+        let valid = local_code == UNASSIGNED_LOCAL_CODE
+            || (1..=MAX_LOCAL_CODE_LEN).contains(&local_code.len())
+                && local_code.bytes().all(|b| b.is_ascii_alphanumeric());
+        if valid {
+            Ok(RegionCode {
+                country,
+                local_code,
+            })
+        } else {
+            Err(error())
+        }
+    }
+}
+
+impl PartialEq<str> for RegionCode {
+    fn eq(&self, other: &str) -> bool {
+        other.split_once('-').is_some_and(|(country, local_code)| {
+            self.country == *country && self.local_code == local_code
+        })
+    }
+}
+
+impl Serialize for RegionCode {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for RegionCode {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(de::Error::custom)
+    }
+}
+
+/// A four-letter [ICAO airport code](https://en.wikipedia.org/wiki/ICAO_airport_code) (location
+/// indicator), e.g. "EGLL".
+#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct IcaoCode([u8; 4]);
+
+impl IcaoCode {
+    /// The code as a string.
+    pub fn as_str(&self) -> &str {
+        letters_str(&self.0)
+    }
+    /// The first letter of the code, which identifies the ICAO region, e.g. 'E' for northern
+    /// Europe.
+    pub fn region_letter(&self) -> char {
+        char::from(self.0[0])
+    }
+}
+
+impl fmt::Debug for IcaoCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("IcaoCode").field(&self.as_str()).finish()
+    }
+}
+
+impl fmt::Display for IcaoCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Error returned when parsing an invalid [`IcaoCode`].
+#[derive(thiserror::Error, Debug, Clone, Eq, PartialEq)]
+#[error("invalid ICAO code \"{0}\"")]
+pub struct ParseIcaoCodeError(String);
+
+impl FromStr for IcaoCode {
+    type Err = ParseIcaoCodeError;
+
+    /// Parses a code of four ASCII letters (case-insensitive).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_letters(s)
+            .map(IcaoCode)
+            .ok_or_else(|| ParseIcaoCodeError(s.to_string()))
+    }
+}
+
+impl PartialEq<str> for IcaoCode {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl Serialize for IcaoCode {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for IcaoCode {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(de::Error::custom)
+    }
+}
+
+/// A three-letter [IATA airport code](https://en.wikipedia.org/wiki/IATA_airport_code), e.g.
+/// "LHR".
+#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct IataCode([u8; 3]);
+
+impl IataCode {
+    /// The code as a string.
+    pub fn as_str(&self) -> &str {
+        letters_str(&self.0)
+    }
+}
+
+impl fmt::Debug for IataCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("IataCode").field(&self.as_str()).finish()
+    }
+}
+
+impl fmt::Display for IataCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Error returned when parsing an invalid [`IataCode`].
+#[derive(thiserror::Error, Debug, Clone, Eq, PartialEq)]
+#[error("invalid IATA code \"{0}\"")]
+pub struct ParseIataCodeError(String);
+
+impl FromStr for IataCode {
+    type Err = ParseIataCodeError;
+
+    /// Parses a code of three ASCII letters (case-insensitive).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_letters(s)
+            .map(IataCode)
+            .ok_or_else(|| ParseIataCodeError(s.to_string()))
+    }
+}
+
+impl PartialEq<str> for IataCode {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl Serialize for IataCode {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for IataCode {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(de::Error::custom)
+    }
+}
+
+/// A national airport code, such as an
+/// [FAA location identifier](https://en.wikipedia.org/wiki/Location_identifier#FAA_identifier),
+/// e.g. "JFK" or "00AK".
+///
+/// Codes are up to eight ASCII letters, digits or hyphens, starting and ending with a letter or
+/// digit.
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct LocalCode(String);
+
+impl LocalCode {
+    /// The code as a string.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for LocalCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Error returned when parsing an invalid [`LocalCode`].
+#[derive(thiserror::Error, Debug, Clone, Eq, PartialEq)]
+#[error("invalid local airport code \"{0}\"")]
+pub struct ParseLocalCodeError(String);
+
+impl FromStr for LocalCode {
+    type Err = ParseLocalCodeError;
+
+    /// Parses a code (case-insensitive).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let bytes = s.as_bytes();
+        let valid = s.len() <= MAX_LOCAL_AIRPORT_CODE_LEN
+            && bytes.first().is_some_and(u8::is_ascii_alphanumeric)
+            && bytes.last().is_some_and(u8::is_ascii_alphanumeric)
+            && bytes
+                .iter()
+                .all(|b| b.is_ascii_alphanumeric() || *b == b'-');
+        if valid {
+            Ok(LocalCode(s.to_ascii_uppercase()))
+        } else {
+            Err(ParseLocalCodeError(s.to_string()))
+        }
+    }
+}
+
+impl PartialEq<str> for LocalCode {
+    fn eq(&self, other: &str) -> bool {
+        self.0 == other
+    }
+}
+
+impl Serialize for LocalCode {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for LocalCode {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(de::Error::custom)
+    }
+}
+
+/// The identifier of an airport in OurAirports, as in
+/// [`Airport::ident()`](crate::airports::Airport::ident()).
+///
+/// This is the ICAO code if the airport has one, otherwise a local code, or failing that a code
+/// generated by OurAirports from the country code and a number, e.g. "GB-0001".
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum OurAirportsIdent {
+    /// An ICAO code.
+    Icao(IcaoCode),
+    /// A local code.
+    Local(LocalCode),
+    /// A code generated by OurAirports. The number is kept as the digits it was written with,
+    /// e.g. "0001", so the code is displayed as it was parsed.
+    Generated {
+        country: CountryCode,
+        number: String,
+    },
+}
+
+impl OurAirportsIdent {
+    /// `true` if the identifier was generated by OurAirports, so it is not used outside it.
+    pub fn is_generated(&self) -> bool {
+        matches!(self, OurAirportsIdent::Generated { .. })
+    }
+    /// The ICAO code, if the identifier is one.
+    pub fn icao_code(&self) -> Option<IcaoCode> {
+        match self {
+            OurAirportsIdent::Icao(code) => Some(*code),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for OurAirportsIdent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OurAirportsIdent::Icao(code) => write!(f, "{}", code),
+            OurAirportsIdent::Local(code) => write!(f, "{}", code),
+            OurAirportsIdent::Generated { country, number } => {
+                write!(f, "{}-{}", country, number)
+            }
+        }
+    }
+}
+
+/// Error returned when parsing an invalid [`OurAirportsIdent`].
+#[derive(thiserror::Error, Debug, Clone, Eq, PartialEq)]
+#[error("invalid OurAirports identifier \"{0}\"")]
+pub struct ParseOurAirportsIdentError(String);
+
+impl FromStr for OurAirportsIdent {
+    type Err = ParseOurAirportsIdentError;
+
+    /// Parses an identifier (case-insensitive). Four letters are taken to be an ICAO code, and a
+    /// country code, a hyphen and a number to be a generated code.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let generated = s.split_once('-').and_then(|(country, number)| {
+            if number.is_empty() || !number.bytes().all(|b| b.is_ascii_digit()) {
+                return None;
+            }
+            Some(OurAirportsIdent::Generated {
+                country: country.parse().ok()?,
+                number: number.to_string(),
+            })
+        });
+        if let Some(generated) = generated {
+            return Ok(generated);
+        }
+        if let Ok(code) = s.parse() {
+            return Ok(OurAirportsIdent::Icao(code));
+        }
+        s.parse()
+            .map(OurAirportsIdent::Local)
+            .map_err(|_| ParseOurAirportsIdentError(s.to_string()))
+    }
+}
+
+impl Serialize for OurAirportsIdent {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for OurAirportsIdent {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(de::Error::custom)
+    }
+}
+
+/// Parses exactly `N` ASCII letters, converted to upper case.
+fn parse_letters<const N: usize>(s: &str) -> Option<[u8; N]> {
+    let mut letters: [u8; N] = s.as_bytes().try_into().ok()?;
+    if !letters.iter().all(u8::is_ascii_alphabetic) {
+        return None;
+    }
+    letters.make_ascii_uppercase();
+    Some(letters)
+}
+
+/// `letters` from [`parse_letters()`] as a string.
+fn letters_str(letters: &[u8]) -> &str {
+    std::str::from_utf8(letters).expect("codes are ASCII")
+}
+
+/// The typed codes of an airport, as returned by
+/// [`Airport::codes()`](crate::airports::Airport::codes()). Codes which are empty or invalid are
+/// `None`.
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize)]
+pub struct AirportCodes {
+    ident: Option<OurAirportsIdent>,
+    icao: Option<IcaoCode>,
+    iata: Option<IataCode>,
+    local: Option<LocalCode>,
+}
+
+impl AirportCodes {
+    /// Parses the codes of `airport`.
+    ///
+    /// A [`gps_code`](Airport::gps_code_opt()) of four letters is taken to be the ICAO code of
+    /// the airport, without checking its region letter against the country of the airport. Some
+    /// airports have a four-letter GPS code which is not an ICAO code, so it should not be relied
+    /// on as one for airports which do not have an ICAO code as their ident.
+    pub fn new(airport: &Airport) -> Self {
+        let ident = airport.ident().parse::<OurAirportsIdent>().ok();
+        AirportCodes {
+            icao: airport
+                .gps_code_opt()
+                .and_then(|code| code.parse().ok())
+                .or_else(|| ident.as_ref()?.icao_code()),
+            ident,
+            iata: airport.iata_code_opt().and_then(|code| code.parse().ok()),
+            local: airport.local_code_opt().and_then(|code| code.parse().ok()),
+        }
+    }
+    /// The OurAirports identifier, see [`Airport::ident()`].
+    pub fn ident(&self) -> Option<&OurAirportsIdent> {
+        self.ident.as_ref()
+    }
+    /// The ICAO code, from [`Airport::gps_code_opt()`] or failing that [`Airport::ident()`]. See
+    /// [`AirportCodes::new()`] for how it is recognized.
+    pub fn icao(&self) -> Option<IcaoCode> {
+        self.icao
+    }
+    /// The IATA code, see [`Airport::iata_code_opt()`].
+    pub fn iata(&self) -> Option<IataCode> {
+        self.iata
+    }
+    /// The local code, see [`Airport::local_code_opt()`].
+    pub fn local(&self) -> Option<&LocalCode> {
+        self.local.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Asserts that `code` parses to `expected` and is displayed and serialized as `code`.
+    fn assert_round_trip<T>(code: &str, expected: T)
+    where
+        T: FromStr + fmt::Display + fmt::Debug + PartialEq + Serialize,
+        T::Err: fmt::Debug,
+    {
+        let parsed: T = code.parse().unwrap();
+        assert_eq!(expected, parsed);
+        assert_eq!(code, parsed.to_string());
+        assert_eq!(
+            format!("\"{}\"", code),
+            serde_json::to_string(&parsed).unwrap()
+        );
+        assert_eq!(parsed, parsed.to_string().parse().unwrap());
+    }
+
+    #[test]
+    fn round_trips() {
+        assert_round_trip("GB", CountryCode(*b"GB"));
+        assert_round_trip(
+            "GB-ENG",
+            RegionCode {
+                country: CountryCode(*b"GB"),
+                local_code: "ENG".to_string(),
+            },
+        );
+        assert_round_trip("US-U-A", RegionCode::unassigned(CountryCode(*b"US")));
+        assert_round_trip("EGLL", IcaoCode(*b"EGLL"));
+        assert_round_trip("LHR", IataCode(*b"LHR"));
+        assert_round_trip("00AK", LocalCode("00AK".to_string()));
+        assert_round_trip("EGLL", OurAirportsIdent::Icao(IcaoCode(*b"EGLL")));
+        assert_round_trip(
+            "00AK",
+            OurAirportsIdent::Local(LocalCode("00AK".to_string())),
+        );
+        for number in ["0001", "12345", "7"] {
+            assert_round_trip(
+                &format!("GB-{}", number),
+                OurAirportsIdent::Generated {
+                    country: CountryCode(*b"GB"),
+                    number: number.to_string(),
+                },
+            );
+        }
+    }
+
+    #[test]
+    fn invalid_idents() {
+        for ident in ["", "GB-", "-0001", "GB-0001-", "EGLL-"] {
+            assert!(ident.parse::<OurAirportsIdent>().is_err(), "{}", ident);
+        }
+    }
+}
@@ -20,13 +20,19 @@
 use std::cmp::Ordering;
 use std::collections::BTreeMap;
 use std::hash::{Hash, Hasher};
+use std::io::Read;
 
 use log::debug;
 use serde::{Deserialize, Serialize};
+use url::Url;
 
-use crate::{vec_string_from_string, Continent, FetchError, Id, ToJsonString};
+use crate::codes::CountryCode;
+use crate::{
+    empty_as_none, parse_link, vec_string_from_string, Continent, FetchError, Id, ToJsonString,
+};
 
-const COUNTRIES_CSV_URL: &str = "https://davidmegginson.github.io/ourairports-data/countries.csv";
+pub(crate) const COUNTRIES_CSV_URL: &str =
+    "https://davidmegginson.github.io/ourairports-data/countries.csv";
 
 /// Represents a country or country-like entity (e.g. Hong Kong).
 #[derive(Deserialize, Debug, Clone, Serialize)]
@@ -35,7 +41,8 @@ pub struct Country {
     code: String,
     name: String,
     continent: Continent,
-    wikipedia_link: String,
+    #[serde(with = "empty_as_none")]
+    wikipedia_link: Option<String>,
     #[serde(deserialize_with = "vec_string_from_string")]
     keywords: Vec<String>,
 }
@@ -53,6 +60,10 @@ impl Country {
     pub fn code(&self) -> &str {
         &self.code
     }
+    /// Parsed [`Self::code()`]. Returns `None` if it is not a valid country code.
+    pub fn country_code(&self) -> Option<CountryCode> {
+        self.code.parse().ok()
+    }
     /// The common English-language name for the country.
     /// Other variations of the name may appear in [`self.keywords()`] to assist with search.
     pub fn name(&self) -> &str {
@@ -65,8 +76,18 @@ impl Country {
         &self.continent
     }
     /// Link to the Wikipedia article about the country.
+    pub fn wikipedia_link_opt(&self) -> Option<&str> {
+        self.wikipedia_link.as_deref()
+    }
+    /// Same as [`Self::wikipedia_link_opt()`], but returns an empty string if not available.
+    #[deprecated(since = "0.2.0", note = "use `wikipedia_link_opt()` instead")]
     pub fn wikipedia_link(&self) -> &str {
-        &self.wikipedia_link
+        self.wikipedia_link.as_deref().unwrap_or_default()
+    }
+    /// The Wikipedia article about the country as an `http` or `https` URL. Returns `None` if there
+    /// is no article or the link is not a valid URL.
+    pub fn wikipedia_url(&self) -> Option<Url> {
+        parse_link(self.wikipedia_link.as_deref())
     }
     /// A list of of search keywords/phrases related to the country. Each item represents one
     /// keyword.
@@ -113,9 +134,19 @@ pub fn get_countries_csv() -> Result<BTreeMap<Id, Country>, FetchError> {
     // get data
     debug!("getting data");
     let content = crate::web_request_blocking(COUNTRIES_CSV_URL)?;
+    Ok(read_countries_csv(content.as_bytes())?)
+}
+
+/// Reads [`Country`] records in the OurAirports `countries.csv` format from `reader`, returning a
+/// [`BTreeMap`] with its ID as the key, sorted according to its keys.
+///
+/// # Errors
+/// Returns [`csv::Error`] if the data cannot be read or there's something wrong with the
+/// de serialization process.
+pub fn read_countries_csv<R: Read>(reader: R) -> Result<BTreeMap<Id, Country>, csv::Error> {
     // initialise csv reader & return value
     debug!("initialising CSV reader");
-    let mut rdr = csv::Reader::from_reader(content.as_bytes());
+    let mut rdr = csv::Reader::from_reader(reader);
     debug!("parsing and deserializing data");
     let mut map = BTreeMap::new();
     for result in rdr.deserialize() {
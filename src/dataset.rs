@@ -0,0 +1,512 @@
+//! Contains a type bundling all OurAirports datasets, with functions to fetch them from
+//! OurAirports, read and write them as local files, compare two versions of the data and check
+//! the references between datasets.
+//!
+//! # Examples
+//! ```no_run
+//! use ourairports::dataset::*;
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!     // save the latest data for offline use
+//!     download_to_dir("ourairports-data")?;
+//!     let dataset = Dataset::read_dir("ourairports-data")?;
+//!
+//!     let heathrow_airport = dataset.airports().get(&2434).unwrap();
+//!     assert_eq!("EGLL", heathrow_airport.ident());
+//!     assert!(dataset.validate().is_empty());
+//! #    Ok(())
+//! # }
+//! ```
+
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use std::fs::{self, File};
+use std::path::Path;
+
+use log::debug;
+use serde::{Deserialize, Serialize};
+
+use crate::airport_frequencies::{self, AirportFrequency};
+use crate::airports::{self, Airport};
+use crate::countries::{self, Country};
+use crate::lint::{LintFinding, Linter};
+use crate::navaids::{self, Navaid};
+use crate::regions::{self, Region};
+use crate::runways::{self, Runway};
+use crate::{FetchError, Id};
+
+/// The individual datasets published by OurAirports.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum DatasetKind {
+    /// `airports.csv`, see [`Airport`]
+    Airports,
+    /// `airport-frequencies.csv`, see [`AirportFrequency`]
+    AirportFrequencies,
+    /// `countries.csv`, see [`Country`]
+    Countries,
+    /// `navaids.csv`, see [`Navaid`]
+    Navaids,
+    /// `regions.csv`, see [`Region`]
+    Regions,
+    /// `runways.csv`, see [`Runway`]
+    Runways,
+}
+
+impl DatasetKind {
+    /// All datasets, in alphabetical order.
+    pub const ALL: [DatasetKind; 6] = [
+        DatasetKind::Airports,
+        DatasetKind::AirportFrequencies,
+        DatasetKind::Countries,
+        DatasetKind::Navaids,
+        DatasetKind::Regions,
+        DatasetKind::Runways,
+    ];
+
+    /// The file name OurAirports publishes the dataset under.
+    pub fn file_name(&self) -> &'static str {
+        match self {
+            DatasetKind::Airports => "airports.csv",
+            DatasetKind::AirportFrequencies => "airport-frequencies.csv",
+            DatasetKind::Countries => "countries.csv",
+            DatasetKind::Navaids => "navaids.csv",
+            DatasetKind::Regions => "regions.csv",
+            DatasetKind::Runways => "runways.csv",
+        }
+    }
+
+    fn url(&self) -> &'static str {
+        match self {
+            DatasetKind::Airports => airports::AIRPORTS_CSV_URL,
+            DatasetKind::AirportFrequencies => airport_frequencies::AIRPORT_FREQUENCIES_CSV_URL,
+            DatasetKind::Countries => countries::COUNTRIES_CSV_URL,
+            DatasetKind::Navaids => navaids::NAVAIDS_CSV_URL,
+            DatasetKind::Regions => regions::REGIONS_CSV_URL,
+            DatasetKind::Runways => runways::RUNWAYS_CSV_URL,
+        }
+    }
+}
+
+impl fmt::Display for DatasetKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.file_name().trim_end_matches(".csv"))
+    }
+}
+
+/// All OurAirports datasets, each as a [`BTreeMap`] with the record ID as the key.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Dataset {
+    airports: BTreeMap<Id, Airport>,
+    airport_frequencies: BTreeMap<Id, AirportFrequency>,
+    countries: BTreeMap<Id, Country>,
+    navaids: BTreeMap<Id, Navaid>,
+    regions: BTreeMap<Id, Region>,
+    runways: BTreeMap<Id, Runway>,
+}
+
+impl Dataset {
+    /// Creates a dataset from already loaded data.
+    pub fn new(
+        airports: BTreeMap<Id, Airport>,
+        airport_frequencies: BTreeMap<Id, AirportFrequency>,
+        countries: BTreeMap<Id, Country>,
+        navaids: BTreeMap<Id, Navaid>,
+        regions: BTreeMap<Id, Region>,
+        runways: BTreeMap<Id, Runway>,
+    ) -> Self {
+        Dataset {
+            airports,
+            airport_frequencies,
+            countries,
+            navaids,
+            regions,
+            runways,
+        }
+    }
+
+    /// Fetches the latest version of all datasets from OurAirports.
+    ///
+    /// # Errors
+    /// Returns [`FetchError`] if the data cannot be fetched or there's something wrong
+    /// with the de serialization process.
+    pub fn fetch() -> Result<Self, FetchError> {
+        Ok(Dataset {
+            airports: airports::get_airports_csv()?,
+            airport_frequencies: airport_frequencies::get_airport_frequencies_csv()?,
+            countries: countries::get_countries_csv()?,
+            navaids: navaids::get_navaids_csv()?,
+            regions: regions::get_regions_csv()?,
+            runways: runways::get_runways_csv()?,
+        })
+    }
+
+    /// Reads all datasets from CSV files in `dir`, named as in [`DatasetKind::file_name()`]
+    /// (e.g. as saved by [`download_to_dir()`]).
+    ///
+    /// # Errors
+    /// Returns [`FetchError`] if a file cannot be read or there's something wrong
+    /// with the de serialization process.
+    pub fn read_dir(dir: impl AsRef<Path>) -> Result<Self, FetchError> {
+        let dir = dir.as_ref();
+        let open = |kind: DatasetKind| {
+            let path = dir.join(kind.file_name());
+            debug!("reading {}", path.display());
+            File::open(path)
+        };
+        Ok(Dataset {
+            airports: airports::read_airports_csv(open(DatasetKind::Airports)?)?,
+            airport_frequencies: airport_frequencies::read_airport_frequencies_csv(open(
+                DatasetKind::AirportFrequencies,
+            )?)?,
+            countries: countries::read_countries_csv(open(DatasetKind::Countries)?)?,
+            navaids: navaids::read_navaids_csv(open(DatasetKind::Navaids)?)?,
+            regions: regions::read_regions_csv(open(DatasetKind::Regions)?)?,
+            runways: runways::read_runways_csv(open(DatasetKind::Runways)?)?,
+        })
+    }
+
+    /// All airports.
+    pub fn airports(&self) -> &BTreeMap<Id, Airport> {
+        &self.airports
+    }
+    /// All airport frequencies.
+    pub fn airport_frequencies(&self) -> &BTreeMap<Id, AirportFrequency> {
+        &self.airport_frequencies
+    }
+    /// All countries.
+    pub fn countries(&self) -> &BTreeMap<Id, Country> {
+        &self.countries
+    }
+    /// All navaids.
+    pub fn navaids(&self) -> &BTreeMap<Id, Navaid> {
+        &self.navaids
+    }
+    /// All regions.
+    pub fn regions(&self) -> &BTreeMap<Id, Region> {
+        &self.regions
+    }
+    /// All runways.
+    pub fn runways(&self) -> &BTreeMap<Id, Runway> {
+        &self.runways
+    }
+
+    /// Compares this dataset with a `newer` version of it.
+    pub fn diff(&self, newer: &Dataset) -> DatasetDiff {
+        DatasetDiff {
+            airports: RecordDiff::new(&self.airports, &newer.airports),
+            airport_frequencies: RecordDiff::new(
+                &self.airport_frequencies,
+                &newer.airport_frequencies,
+            ),
+            countries: RecordDiff::new(&self.countries, &newer.countries),
+            navaids: RecordDiff::new(&self.navaids, &newer.navaids),
+            regions: RecordDiff::new(&self.regions, &newer.regions),
+            runways: RecordDiff::new(&self.runways, &newer.runways),
+        }
+    }
+
+    /// Checks the data against all [lint rules](crate::lint::LintRule) with the default
+    /// thresholds, see [`Linter`].
+    ///
+    /// Returns an empty vector if nothing suspicious was found.
+    pub fn lint(&self) -> Vec<LintFinding> {
+        Linter::new().lint(self)
+    }
+
+    /// Checks that the references between datasets resolve, e.g. that every runway belongs to a
+    /// known airport and every airport is in a known region and country, and that country and
+    /// region codes are valid and consistent.
+    ///
+    /// Returns an empty vector if no problems were found.
+    pub fn validate(&self) -> Vec<ValidationIssue> {
+        let country_codes = self
+            .countries
+            .values()
+            .map(Country::code)
+            .collect::<HashSet<_>>();
+        let region_codes = self
+            .regions
+            .values()
+            .map(Region::code)
+            .collect::<HashSet<_>>();
+        let airport_idents = self
+            .airports
+            .values()
+            .map(Airport::ident)
+            .collect::<HashSet<_>>();
+        let mut issues = vec![];
+        let mut issue =
+            |kind, id, message: String| issues.push(ValidationIssue { kind, id, message });
+
+        for airport in self.airports.values() {
+            if !country_codes.contains(airport.iso_country()) {
+                issue(
+                    DatasetKind::Airports,
+                    airport.id(),
+                    format!("unknown country \"{}\"", airport.iso_country()),
+                );
+            }
+            if !region_codes.contains(airport.iso_region()) {
+                issue(
+                    DatasetKind::Airports,
+                    airport.id(),
+                    format!("unknown region \"{}\"", airport.iso_region()),
+                );
+            }
+            if !airport.has_consistent_region() {
+                issue(
+                    DatasetKind::Airports,
+                    airport.id(),
+                    format!(
+                        "region \"{}\" is not in country \"{}\"",
+                        airport.iso_region(),
+                        airport.iso_country()
+                    ),
+                );
+            }
+        }
+        for country in self.countries.values() {
+            if country.country_code().is_none() {
+                issue(
+                    DatasetKind::Countries,
+                    country.id(),
+                    format!("invalid country code \"{}\"", country.code()),
+                );
+            }
+        }
+        for region in self.regions.values() {
+            if region.region_code().is_none() {
+                issue(
+                    DatasetKind::Regions,
+                    region.id(),
+                    format!("invalid region code \"{}\"", region.code()),
+                );
+            } else if !region.has_consistent_code() {
+                issue(
+                    DatasetKind::Regions,
+                    region.id(),
+                    format!(
+                        "code \"{}\" does not match country \"{}\" and local code \"{}\"",
+                        region.code(),
+                        region.iso_country(),
+                        region.local_code()
+                    ),
+                );
+            }
+            if !country_codes.contains(region.iso_country()) {
+                issue(
+                    DatasetKind::Regions,
+                    region.id(),
+                    format!("unknown country \"{}\"", region.iso_country()),
+                );
+            }
+        }
+        let mut check_airport_ref =
+            |kind, id, airport_ref, airport_ident: &str| match self.airports.get(&airport_ref) {
+                None => issue(kind, id, format!("unknown airport ID {}", airport_ref)),
+                Some(airport) if airport.ident() != airport_ident => issue(
+                    kind,
+                    id,
+                    format!(
+                        "airport ident \"{}\" does not match airport {} (\"{}\")",
+                        airport_ident,
+                        airport_ref,
+                        airport.ident()
+                    ),
+                ),
+                Some(_) => {}
+            };
+        for runway in self.runways.values() {
+            check_airport_ref(
+                DatasetKind::Runways,
+                runway.id(),
+                runway.airport_ref(),
+                runway.airport_ident(),
+            );
+        }
+        for frequency in self.airport_frequencies.values() {
+            check_airport_ref(
+                DatasetKind::AirportFrequencies,
+                frequency.id(),
+                frequency.airport_ref(),
+                frequency.airport_ident(),
+            );
+        }
+        for navaid in self.navaids.values() {
+            if !navaid.iso_country().is_empty() && !country_codes.contains(navaid.iso_country()) {
+                issue(
+                    DatasetKind::Navaids,
+                    navaid.id(),
+                    format!("unknown country \"{}\"", navaid.iso_country()),
+                );
+            }
+            if let Some(ident) = navaid.associated_airport_opt() {
+                if !airport_idents.contains(ident) {
+                    issue(
+                        DatasetKind::Navaids,
+                        navaid.id(),
+                        format!("unknown associated airport \"{}\"", ident),
+                    );
+                }
+            }
+        }
+        issues
+    }
+}
+
+/// Downloads the latest version of all datasets from OurAirports into `dir`, creating it if
+/// needed. The files can be read back with [`Dataset::read_dir()`].
+///
+/// Each file is first written to a temporary file in `dir` and then renamed, so readers never see
+/// a partially written file.
+///
+/// # Errors
+/// Returns [`FetchError`] if the data cannot be fetched or written.
+pub fn download_to_dir(dir: impl AsRef<Path>) -> Result<(), FetchError> {
+    let dir = dir.as_ref();
+    fs::create_dir_all(dir)?;
+    for kind in DatasetKind::ALL {
+        let content = crate::web_request_blocking(kind.url())?;
+        let path = dir.join(kind.file_name());
+        let temp_path = dir.join(format!(".{}.tmp", kind.file_name()));
+        debug!("writing {}", path.display());
+        fs::write(&temp_path, content)?;
+        fs::rename(temp_path, path)?;
+    }
+    Ok(())
+}
+
+/// IDs of records which were added, removed or changed between two versions of a dataset.
+#[derive(Debug, Clone, Default, Eq, PartialEq, Serialize)]
+pub struct RecordDiff {
+    added: Vec<Id>,
+    removed: Vec<Id>,
+    changed: Vec<Id>,
+}
+
+impl RecordDiff {
+    /// Compares two versions of a dataset. Records are considered changed if any of their fields
+    /// differ.
+    pub fn new<T: Serialize>(old: &BTreeMap<Id, T>, new: &BTreeMap<Id, T>) -> Self {
+        let mut diff = RecordDiff::default();
+        for (id, old_record) in old {
+            match new.get(id) {
+                None => diff.removed.push(*id),
+                Some(new_record) => {
+                    if serde_json::to_value(old_record).ok()
+                        != serde_json::to_value(new_record).ok()
+                    {
+                        diff.changed.push(*id);
+                    }
+                }
+            }
+        }
+        diff.added = new
+            .keys()
+            .filter(|id| !old.contains_key(id))
+            .copied()
+            .collect();
+        diff
+    }
+    /// IDs of records only in the newer version, sorted.
+    pub fn added(&self) -> &[Id] {
+        &self.added
+    }
+    /// IDs of records only in the older version, sorted.
+    pub fn removed(&self) -> &[Id] {
+        &self.removed
+    }
+    /// IDs of records in both versions with different field values, sorted.
+    pub fn changed(&self) -> &[Id] {
+        &self.changed
+    }
+    /// `true` if both versions are identical.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Differences between two versions of a [`Dataset`], as returned by [`Dataset::diff()`].
+#[derive(Debug, Clone, Default, Eq, PartialEq, Serialize)]
+pub struct DatasetDiff {
+    airports: RecordDiff,
+    airport_frequencies: RecordDiff,
+    countries: RecordDiff,
+    navaids: RecordDiff,
+    regions: RecordDiff,
+    runways: RecordDiff,
+}
+
+impl DatasetDiff {
+    /// Differences in the given dataset.
+    pub fn get(&self, kind: DatasetKind) -> &RecordDiff {
+        match kind {
+            DatasetKind::Airports => &self.airports,
+            DatasetKind::AirportFrequencies => &self.airport_frequencies,
+            DatasetKind::Countries => &self.countries,
+            DatasetKind::Navaids => &self.navaids,
+            DatasetKind::Regions => &self.regions,
+            DatasetKind::Runways => &self.runways,
+        }
+    }
+    /// Differences in airports.
+    pub fn airports(&self) -> &RecordDiff {
+        &self.airports
+    }
+    /// Differences in airport frequencies.
+    pub fn airport_frequencies(&self) -> &RecordDiff {
+        &self.airport_frequencies
+    }
+    /// Differences in countries.
+    pub fn countries(&self) -> &RecordDiff {
+        &self.countries
+    }
+    /// Differences in navaids.
+    pub fn navaids(&self) -> &RecordDiff {
+        &self.navaids
+    }
+    /// Differences in regions.
+    pub fn regions(&self) -> &RecordDiff {
+        &self.regions
+    }
+    /// Differences in runways.
+    pub fn runways(&self) -> &RecordDiff {
+        &self.runways
+    }
+    /// `true` if both versions of the dataset are identical.
+    pub fn is_empty(&self) -> bool {
+        DatasetKind::ALL
+            .iter()
+            .all(|kind| self.get(*kind).is_empty())
+    }
+}
+
+/// A reference between datasets which does not resolve, as returned by [`Dataset::validate()`].
+#[derive(Debug, Clone, Eq, PartialEq, Serialize)]
+pub struct ValidationIssue {
+    kind: DatasetKind,
+    id: Id,
+    message: String,
+}
+
+impl ValidationIssue {
+    /// The dataset containing the offending record.
+    pub fn kind(&self) -> DatasetKind {
+        self.kind
+    }
+    /// ID of the offending record.
+    pub fn id(&self) -> Id {
+        self.id
+    }
+    /// Description of the problem.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}: {}", self.kind, self.id, self.message)
+    }
+}
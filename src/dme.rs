@@ -0,0 +1,255 @@
+//! Contains DME and TACAN channels, with the standard ICAO pairing of channels to VHF and UHF
+//! frequencies (ICAO Annex 10, Volume I, Table A).
+//!
+//! # Examples
+//! ```
+//! use ourairports::dme::*;
+//!
+//! let channel: DmeChannel = "100X".parse().unwrap();
+//! assert_eq!(Some(115_300), channel.vhf_frequency_khz());
+//! assert_eq!(1124, channel.interrogation_frequency_mhz());
+//! assert_eq!(1187, channel.reply_frequency_mhz());
+//! assert_eq!(Some(channel), DmeChannel::from_vhf_frequency_khz(115_300));
+//!
+//! // channels without a paired VHF frequency can only be used by TACAN
+//! assert!("5Y".parse::<DmeChannel>().unwrap().is_tacan_only());
+//! ```
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::navaids::{Navaid, NavaidType};
+
+/// Pulse code of a DME channel.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum DmeMode {
+    X,
+    Y,
+}
+
+/// A DME (or TACAN) channel, from 1X to 126Y.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct DmeChannel {
+    number: u8,
+    mode: DmeMode,
+}
+
+impl DmeChannel {
+    /// Creates channel `number` with `mode`. Returns `None` if `number` is not between 1 and 126.
+    pub fn new(number: u8, mode: DmeMode) -> Option<Self> {
+        (1..=126)
+            .contains(&number)
+            .then_some(DmeChannel { number, mode })
+    }
+    /// Returns the channel paired with the VHF frequency `frequency_khz`, or `None` if it is not
+    /// a paired frequency (108.00 to 117.95 MHz).
+    pub fn from_vhf_frequency_khz(frequency_khz: u32) -> Option<Self> {
+        [DmeMode::X, DmeMode::Y]
+            .into_iter()
+            .flat_map(|mode| (1..=126).map(move |number| DmeChannel { number, mode }))
+            .find(|channel| channel.vhf_frequency_khz() == Some(frequency_khz))
+    }
+    /// Channel number from 1 to 126.
+    pub fn number(&self) -> u8 {
+        self.number
+    }
+    /// Pulse code of the channel.
+    pub fn mode(&self) -> DmeMode {
+        self.mode
+    }
+    /// The VHF (VOR or ILS localiser) frequency paired with the channel in kilohertz. Returns
+    /// `None` for channels 1 to 16 and 60 to 69, which are not paired.
+    pub fn vhf_frequency_khz(&self) -> Option<u32> {
+        let offset_khz = match self.mode {
+            DmeMode::X => 0,
+            DmeMode::Y => 50,
+        };
+        let number = u32::from(self.number);
+        match number {
+            17..=59 => Some(108_000 + (number - 17) * 100 + offset_khz),
+            70..=126 => Some(112_300 + (number - 70) * 100 + offset_khz),
+            _ => None,
+        }
+    }
+    /// `true` if the channel has no paired VHF frequency, so it can only be tuned directly, as
+    /// with TACAN.
+    pub fn is_tacan_only(&self) -> bool {
+        self.vhf_frequency_khz().is_none()
+    }
+    /// Frequency transmitted by the aircraft to interrogate the ground station, in megahertz.
+    pub fn interrogation_frequency_mhz(&self) -> u32 {
+        1024 + u32::from(self.number)
+    }
+    /// Frequency of the ground station's reply, in megahertz.
+    pub fn reply_frequency_mhz(&self) -> u32 {
+        let interrogation = self.interrogation_frequency_mhz();
+        match (self.mode, self.number) {
+            (DmeMode::X, 1..=63) | (DmeMode::Y, 64..) => interrogation - 63,
+            _ => interrogation + 63,
+        }
+    }
+}
+
+impl fmt::Display for DmeChannel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{:?}", self.number, self.mode)
+    }
+}
+
+/// Error returned when parsing an invalid [`DmeChannel`].
+#[derive(thiserror::Error, Debug, Clone, Eq, PartialEq)]
+#[error("invalid DME channel \"{0}\"")]
+pub struct ParseDmeChannelError(String);
+
+impl FromStr for DmeChannel {
+    type Err = ParseDmeChannelError;
+
+    /// Parses a channel such as "100X", "17Y", "100 X" or "CH100X" (case-insensitive).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || ParseDmeChannelError(s.to_string());
+        let upper = s.trim().to_uppercase();
+        let channel = upper.strip_prefix("CH").unwrap_or(&upper).trim_start();
+        let mode = match channel.chars().last() {
+            Some('X') => DmeMode::X,
+            Some('Y') => DmeMode::Y,
+            _ => return Err(error()),
+        };
+        let number = channel[..channel.len() - 1].trim_end();
+        if !number.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(error());
+        }
+        number
+            .parse()
+            .ok()
+            .and_then(|number| DmeChannel::new(number, mode))
+            .ok_or_else(error)
+    }
+}
+
+impl Serialize for DmeChannel {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+/// Disagreement between the frequencies and DME channel of a navaid, as returned by
+/// [`check_navaid()`].
+#[derive(Debug, Clone, Eq, PartialEq, Serialize)]
+#[serde(tag = "issue", rename_all = "snake_case")]
+pub enum DmeIssue {
+    /// [`Navaid::dme_channel_opt()`] is not a valid channel.
+    InvalidChannel { dme_channel: String },
+    /// [`Navaid::dme_frequency_khz_opt()`] is set, but the channel has no paired VHF frequency.
+    UnpairedChannel {
+        channel: DmeChannel,
+        dme_frequency_khz: String,
+    },
+    /// [`Navaid::dme_frequency_khz_opt()`] is not the VHF frequency paired with the channel.
+    DmeFrequencyMismatch {
+        channel: DmeChannel,
+        expected_khz: u32,
+        dme_frequency_khz: String,
+    },
+    /// [`Navaid::frequency_khz()`] of a VHF or UHF navaid is not the VHF frequency paired with
+    /// the channel.
+    FrequencyMismatch {
+        channel: DmeChannel,
+        expected_khz: u32,
+        frequency_khz: String,
+    },
+}
+
+impl fmt::Display for DmeIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DmeIssue::InvalidChannel { dme_channel } => {
+                write!(f, "invalid DME channel \"{}\"", dme_channel)
+            }
+            DmeIssue::UnpairedChannel {
+                channel,
+                dme_frequency_khz,
+            } => write!(
+                f,
+                "DME channel {} has no paired VHF frequency, but DME frequency is {} kHz",
+                channel, dme_frequency_khz
+            ),
+            DmeIssue::DmeFrequencyMismatch {
+                channel,
+                expected_khz,
+                dme_frequency_khz,
+            } => write!(
+                f,
+                "DME frequency {} kHz does not match channel {} ({} kHz)",
+                dme_frequency_khz, channel, expected_khz
+            ),
+            DmeIssue::FrequencyMismatch {
+                channel,
+                expected_khz,
+                frequency_khz,
+            } => write!(
+                f,
+                "frequency {} kHz does not match DME channel {} ({} kHz)",
+                frequency_khz, channel, expected_khz
+            ),
+        }
+    }
+}
+
+/// Checks that the frequency, DME frequency and DME channel of `navaid` agree.
+///
+/// The frequency is only checked for navaids whose frequency is the paired VHF frequency (VOR-DME,
+/// VORTAC, DME and TACAN), not for NDB-DMEs. Returns an empty vector if no problems were found or
+/// the navaid has no DME channel.
+pub fn check_navaid(navaid: &Navaid) -> Vec<DmeIssue> {
+    let dme_channel = navaid.dme_channel_opt().unwrap_or_default();
+    if dme_channel.trim().is_empty() {
+        return vec![];
+    }
+    let channel = match dme_channel.parse::<DmeChannel>() {
+        Ok(channel) => channel,
+        Err(_) => {
+            return vec![DmeIssue::InvalidChannel {
+                dme_channel: dme_channel.to_string(),
+            }]
+        }
+    };
+    let mut issues = vec![];
+    let dme_frequency = navaid.dme_frequency_khz_opt().unwrap_or_default().trim();
+    match channel.vhf_frequency_khz() {
+        None if !dme_frequency.is_empty() => issues.push(DmeIssue::UnpairedChannel {
+            channel,
+            dme_frequency_khz: dme_frequency.to_string(),
+        }),
+        None => {}
+        Some(expected_khz) => {
+            if !dme_frequency.is_empty() && parse_khz(dme_frequency) != Some(expected_khz) {
+                issues.push(DmeIssue::DmeFrequencyMismatch {
+                    channel,
+                    expected_khz,
+                    dme_frequency_khz: dme_frequency.to_string(),
+                });
+            }
+            let frequency = navaid.frequency_khz().trim();
+            let paired_type = matches!(
+                navaid.navaid_type(),
+                NavaidType::VorDme | NavaidType::Vortac | NavaidType::Dme | NavaidType::Tacan
+            );
+            if paired_type && !frequency.is_empty() && parse_khz(frequency) != Some(expected_khz) {
+                issues.push(DmeIssue::FrequencyMismatch {
+                    channel,
+                    expected_khz,
+                    frequency_khz: frequency.to_string(),
+                });
+            }
+        }
+    }
+    issues
+}
+
+/// Parses a frequency in kilohertz, which may have a fractional part.
+fn parse_khz(frequency_khz: &str) -> Option<u32> {
+    let khz = frequency_khz.parse::<f64>().ok()?;
+    (khz >= 0.0 && khz <= f64::from(u32::MAX)).then(|| khz.round() as u32)
+}
@@ -0,0 +1,417 @@
+//! Contains an enrichment pass which fills in runway headings, end coordinates and elevations
+//! missing from the source data.
+//!
+//! Each value of an [`EnrichedRunway`] is wrapped in an [`EnrichedValue`], which records whether
+//! it was taken from the source data ([`ValueSource::Sourced`]) or inferred
+//! ([`ValueSource::Derived`]). Derived values are approximations and should not be used where
+//! accuracy matters.
+//!
+//! Missing values are inferred as follows:
+//! - **Heading**: the reciprocal of the opposite end's heading, the bearing between the two end
+//!   coordinates, or the runway designator (e.g. "09" is 090° magnetic) corrected by the
+//!   magnetic variation at the airport, in that order of preference.
+//! - **End coordinates**: the opposite end's coordinates, moved by the runway length along its
+//!   heading.
+//! - **Elevation**: the airport elevation.
+//!
+//! # Examples
+//! ```no_run
+//! use ourairports::dataset::Dataset;
+//! use ourairports::enrich::*;
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!     let dataset = Dataset::fetch()?;
+//!     let enricher = RunwayEnricher::from_dataset(&dataset);
+//!     for runway in dataset.runways().values() {
+//!         let enriched = enricher.enrich(runway);
+//!         if let Some(heading) = enriched.low_end().heading_deg_true() {
+//!             let source = if heading.is_derived() { "derived" } else { "sourced" };
+//!             println!("{} {}: {:.0}° ({})", runway.airport_ident(), runway.low_end().ident(),
+//!                 heading.value(), source);
+//!         }
+//!     }
+//! #    Ok(())
+//! # }
+//! ```
+
+use std::collections::BTreeMap;
+
+use chrono::{NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::airports::Airport;
+use crate::dataset::Dataset;
+use crate::geo::{normalize_bearing, Coordinate, SpatialIndex, FEET_PER_NM};
+use crate::magnetic::{magnetic_to_true_deg, magnetic_variation_deg};
+use crate::navaids::Navaid;
+use crate::runways::{Runway, RunwayEnd};
+use crate::Id;
+
+/// Maximum distance in nautical miles of a navaid from an airport for the navaid's magnetic
+/// variation to be used at the airport.
+const MAX_VARIATION_DISTANCE_NM: f64 = 100.0;
+
+/// Where an [`EnrichedValue`] came from.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum ValueSource {
+    /// The value is from the OurAirports data.
+    Sourced,
+    /// The value was inferred from other values, and is approximate.
+    Derived,
+}
+
+/// A value together with where it came from.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+pub struct EnrichedValue<T> {
+    value: T,
+    source: ValueSource,
+}
+
+impl<T> EnrichedValue<T> {
+    fn sourced(value: T) -> Self {
+        EnrichedValue {
+            value,
+            source: ValueSource::Sourced,
+        }
+    }
+    fn derived(value: T) -> Self {
+        EnrichedValue {
+            value,
+            source: ValueSource::Derived,
+        }
+    }
+    /// The value.
+    pub fn value(&self) -> &T {
+        &self.value
+    }
+    /// Where the value came from.
+    pub fn source(&self) -> ValueSource {
+        self.source
+    }
+    /// `true` if the value was inferred rather than taken from the source data.
+    pub fn is_derived(&self) -> bool {
+        self.source == ValueSource::Derived
+    }
+}
+
+/// One end of an [`EnrichedRunway`].
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct EnrichedRunwayEnd {
+    ident: String,
+    heading_deg_true: Option<EnrichedValue<f64>>,
+    coordinate: Option<EnrichedValue<Coordinate>>,
+    elevation_ft: Option<EnrichedValue<i32>>,
+}
+
+impl EnrichedRunwayEnd {
+    /// Identifier for this end of the runway.
+    pub fn ident(&self) -> &str {
+        &self.ident
+    }
+    /// Heading of this end of the runway in degrees true. Returns `None` if not available and
+    /// cannot be derived.
+    pub fn heading_deg_true(&self) -> Option<&EnrichedValue<f64>> {
+        self.heading_deg_true.as_ref()
+    }
+    /// Position of the centre of this end of the runway. Returns `None` if not available and
+    /// cannot be derived.
+    pub fn coordinate(&self) -> Option<&EnrichedValue<Coordinate>> {
+        self.coordinate.as_ref()
+    }
+    /// Elevation above MSL of this end of the runway in feet. Returns `None` if not available and
+    /// cannot be derived.
+    pub fn elevation_ft(&self) -> Option<&EnrichedValue<i32>> {
+        self.elevation_ft.as_ref()
+    }
+}
+
+/// A [`Runway`] with missing end values filled in where possible.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct EnrichedRunway<'a> {
+    runway: &'a Runway,
+    low_end: EnrichedRunwayEnd,
+    high_end: EnrichedRunwayEnd,
+}
+
+impl<'a> EnrichedRunway<'a> {
+    /// The original runway.
+    pub fn runway(&self) -> &'a Runway {
+        self.runway
+    }
+    /// The low-numbered end of the runway.
+    pub fn low_end(&self) -> &EnrichedRunwayEnd {
+        &self.low_end
+    }
+    /// The high-numbered end of the runway.
+    pub fn high_end(&self) -> &EnrichedRunwayEnd {
+        &self.high_end
+    }
+    /// Both ends of the runway, low-numbered end first.
+    pub fn ends(&self) -> [&EnrichedRunwayEnd; 2] {
+        [&self.low_end, &self.high_end]
+    }
+}
+
+/// Enriches runways using the airports and navaids of a dataset.
+///
+/// The airport of a runway provides its elevation, and the magnetic variation is taken from the
+/// nearest navaid within 100 nautical miles of the airport which has one, or else from the
+/// [World Magnetic Model](crate::magnetic).
+#[derive(Debug, Clone)]
+pub struct RunwayEnricher<'a> {
+    airports: &'a BTreeMap<Id, Airport>,
+    variations: SpatialIndex<f64>,
+    date: Option<NaiveDate>,
+}
+
+impl<'a> RunwayEnricher<'a> {
+    /// Creates an enricher using `airports` and the magnetic variation of `navaids`.
+    pub fn new(airports: &'a BTreeMap<Id, Airport>, navaids: &BTreeMap<Id, Navaid>) -> Self {
+        RunwayEnricher {
+            airports,
+            variations: navaids
+                .values()
+                .filter_map(|n| Some((n.coordinate()?, n.magnetic_variation_deg()?)))
+                .collect(),
+            date: None,
+        }
+    }
+    /// Creates an enricher using the airports and navaids of `dataset`.
+    pub fn from_dataset(dataset: &'a Dataset) -> Self {
+        Self::new(dataset.airports(), dataset.navaids())
+    }
+    /// Date used for the magnetic variation where no navaid's variation is available. Defaults
+    /// to the current date.
+    pub fn date(mut self, date: NaiveDate) -> Self {
+        self.date = Some(date);
+        self
+    }
+    /// Returns the magnetic variation (positive is east) at the navaid nearest to `coordinate`,
+    /// if there is one with a known variation within 100 nautical miles.
+    pub fn magnetic_variation_deg(&self, coordinate: &Coordinate) -> Option<f64> {
+        self.variations
+            .within_radius(coordinate, MAX_VARIATION_DISTANCE_NM)
+            .first()
+            .map(|(variation, _)| **variation)
+    }
+    /// Returns `runway` with missing values filled in where possible.
+    pub fn enrich<'r>(&self, runway: &'r Runway) -> EnrichedRunway<'r> {
+        let airport = self.airports.get(&runway.airport_ref());
+        let location = airport
+            .map(Airport::coordinate)
+            .or_else(|| runway.ends().into_iter().find_map(end_coordinate));
+        let date = self.date.unwrap_or_else(|| Utc::now().date_naive());
+        let variation = location.map(|l| {
+            self.magnetic_variation_deg(&l)
+                .unwrap_or_else(|| magnetic_variation_deg(&l, date))
+        });
+        enrich_runway(runway, airport, variation)
+    }
+}
+
+/// Returns `runway` with missing values filled in where possible, using the elevation of its
+/// `airport` and the `magnetic_variation_deg` (positive is east) at the airport. Headings are not
+/// derived from the runway designators if the variation is not known.
+pub fn enrich_runway<'a>(
+    runway: &'a Runway,
+    airport: Option<&Airport>,
+    magnetic_variation_deg: Option<f64>,
+) -> EnrichedRunway<'a> {
+    let [low, high] = runway.ends();
+    let low_heading = heading(low, magnetic_variation_deg);
+    let high_heading = heading(high, magnetic_variation_deg);
+    let airport_elevation = airport.and_then(Airport::elevation_ft);
+    let end = |end: RunwayEnd, heading: Option<EnrichedValue<f64>>, other_heading: Option<f64>| {
+        EnrichedRunwayEnd {
+            ident: end.ident().to_string(),
+            heading_deg_true: heading,
+            coordinate: coordinate(end, other_heading),
+            elevation_ft: end
+                .elevation_ft()
+                .map(EnrichedValue::sourced)
+                .or(airport_elevation.map(EnrichedValue::derived)),
+        }
+    };
+    EnrichedRunway {
+        runway,
+        low_end: end(low, low_heading, high_heading.map(|h| h.value)),
+        high_end: end(high, high_heading, low_heading.map(|h| h.value)),
+    }
+}
+
+fn end_coordinate(end: RunwayEnd) -> Option<Coordinate> {
+    Some(Coordinate::new(end.latitude_deg()?, end.longitude_deg()?))
+}
+
+fn heading(end: RunwayEnd, magnetic_variation_deg: Option<f64>) -> Option<EnrichedValue<f64>> {
+    if let Some(heading) = end.heading_deg_true() {
+        return Some(EnrichedValue::sourced(heading));
+    }
+    let other = end.opposite();
+    let derived = other
+        .heading_deg_true()
+        .map(|h| normalize_bearing(h + 180.0))
+        .or_else(|| Some(end_coordinate(end)?.initial_bearing_deg(&end_coordinate(other)?)))
+        .or_else(|| {
+            let magnetic = end
+                .designator()
+                .and_then(|d| d.magnetic_heading_deg())
+                .or_else(|| other.designator()?.reciprocal()?.magnetic_heading_deg())?;
+            Some(magnetic_to_true_deg(magnetic, magnetic_variation_deg?))
+        })?;
+    Some(EnrichedValue::derived(derived))
+}
+
+/// Position of `end` from the opposite end, which has heading `other_heading_deg_true`.
+fn coordinate(
+    end: RunwayEnd,
+    other_heading_deg_true: Option<f64>,
+) -> Option<EnrichedValue<Coordinate>> {
+    if let Some(coordinate) = end_coordinate(end) {
+        return Some(EnrichedValue::sourced(coordinate));
+    }
+    let other = end_coordinate(end.opposite())?;
+    let length_nm = f64::from(end.runway().length_ft()?) / FEET_PER_NM;
+    Some(EnrichedValue::derived(
+        other.destination(other_heading_deg_true?, length_nm),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{self, airport, navaid, runway};
+
+    fn runway_09_27() -> test_support::Row {
+        runway(1, 1)
+            .set("length_ft", 6076)
+            .set("le_ident", "09")
+            .set("he_ident", "27")
+    }
+
+    fn headings(runway: &EnrichedRunway) -> [Option<(f64, ValueSource)>; 2] {
+        runway
+            .ends()
+            .map(|end| end.heading_deg_true().map(|h| (*h.value(), h.source())))
+    }
+
+    #[test]
+    fn heading_from_opposite_end() {
+        let runways = test_support::runways([runway_09_27().set("le_heading_degT", 95.0)]);
+        let enriched = enrich_runway(&runways[&1], None, Some(-30.0));
+        assert_eq!(
+            [
+                Some((95.0, ValueSource::Sourced)),
+                Some((275.0, ValueSource::Derived))
+            ],
+            headings(&enriched)
+        );
+    }
+
+    #[test]
+    fn heading_from_end_coordinates() {
+        let runways = test_support::runways([runway_09_27()
+            .set("le_latitude_deg", 0.0)
+            .set("le_longitude_deg", 0.0)
+            .set("he_latitude_deg", 0.0)
+            .set("he_longitude_deg", -0.01)]);
+        let enriched = enrich_runway(&runways[&1], None, Some(-30.0));
+        // the coordinates take precedence over the designators
+        assert_eq!(
+            [
+                Some((270.0, ValueSource::Derived)),
+                Some((90.0, ValueSource::Derived))
+            ],
+            headings(&enriched)
+        );
+    }
+
+    #[test]
+    fn heading_from_designator_and_variation() {
+        let runways = test_support::runways([
+            runway_09_27(),
+            // only one end is designated
+            runway(2, 1).set("le_ident", "36"),
+        ]);
+        let enriched = enrich_runway(&runways[&1], None, Some(-5.0));
+        assert_eq!(
+            [
+                Some((85.0, ValueSource::Derived)),
+                Some((265.0, ValueSource::Derived))
+            ],
+            headings(&enriched)
+        );
+        let enriched = enrich_runway(&runways[&2], None, Some(5.0));
+        assert_eq!(
+            [
+                Some((5.0, ValueSource::Derived)),
+                Some((185.0, ValueSource::Derived))
+            ],
+            headings(&enriched)
+        );
+        // without a variation, designators give no heading
+        assert_eq!(
+            [None, None],
+            headings(&enrich_runway(&runways[&1], None, None))
+        );
+    }
+
+    #[test]
+    fn opposite_end_coordinate_and_elevation() {
+        let runways = test_support::runways([runway_09_27()
+            .set("le_latitude_deg", 10.0)
+            .set("le_longitude_deg", 20.0)
+            .set("le_heading_degT", 0.0)
+            .set("le_elevation_ft", 100)]);
+        let airports = test_support::airports([airport(1, "XX01").set("elevation_ft", 120)]);
+        let enriched = enrich_runway(&runways[&1], airports.get(&1), None);
+        let [low, high] = enriched.ends();
+        assert_eq!(
+            Some(ValueSource::Sourced),
+            low.coordinate().map(|c| c.source())
+        );
+        let coordinate = high.coordinate().unwrap();
+        assert!(coordinate.is_derived());
+        // 6076 ft is 1 NM, i.e. one minute of latitude
+        let expected = Coordinate::new(10.0, 20.0).destination(0.0, 6076.0 / FEET_PER_NM);
+        assert!((coordinate.value().latitude_deg() - expected.latitude_deg()).abs() < 1e-9);
+        assert!((coordinate.value().latitude_deg() - (10.0 + 1.0 / 60.0)).abs() < 1e-3);
+        assert!((coordinate.value().longitude_deg() - 20.0).abs() < 1e-9);
+        assert_eq!(Some(100), low.elevation_ft().map(|e| *e.value()));
+        assert_eq!(
+            Some((120, ValueSource::Derived)),
+            high.elevation_ft().map(|e| (*e.value(), e.source()))
+        );
+    }
+
+    #[test]
+    fn enricher_variation_sources() {
+        let date = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+        let airports = test_support::airports([
+            airport(1, "NEAR").position(51.0, 0.0),
+            airport(2, "FAR").position(-33.0, 151.0),
+        ]);
+        let navaids = test_support::navaids([navaid(1, "VAR", "VOR")
+            .position(51.5, 0.0)
+            .set("magnetic_variation_deg", -10.0)]);
+        let runways = test_support::runways([
+            runway_09_27(),
+            runway_09_27().set("id", 2).set("airport_ref", 2),
+        ]);
+        let enricher = RunwayEnricher::new(&airports, &navaids).date(date);
+        let low_heading = |id| {
+            *enricher
+                .enrich(&runways[&id])
+                .low_end()
+                .heading_deg_true()
+                .unwrap()
+                .value()
+        };
+        // from the navaid within 100 NM
+        assert_eq!(80.0, low_heading(1));
+        // from the World Magnetic Model
+        let variation = magnetic_variation_deg(&Coordinate::new(-33.0, 151.0), date);
+        assert!((low_heading(2) - (90.0 + variation)).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,189 @@
+//! Contains radial/DME fixes: points described by a magnetic radial from a VOR-DME, VORTAC or
+//! TACAN and a DME distance, such as "radial 270 at 15 DME from XYZ".
+//!
+//! Radials are measured from the navaid's position and relative to its
+//! [`slaved_variation_deg`](Navaid::slaved_variation_deg()), which is the variation the radials
+//! are aligned to, falling back to the [`magnetic_variation_deg`](Navaid::magnetic_variation_deg())
+//! at the navaid. Distances are measured from the DME position if the DME is not co-located.
+//! DME distances are treated as distances over the ground; at typical fix distances the error
+//! from the slant range is negligible.
+//!
+//! # Examples
+//! ```no_run
+//! use ourairports::fix::RadialDme;
+//! use ourairports::geo::Coordinate;
+//! use ourairports::navaids::*;
+//! use ourairports::reception::NavaidIndex;
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!     let navaids = get_navaids_csv()?;
+//!     let lambourne = navaids.values().find(|n| n.ident() == "LAM").unwrap();
+//!
+//!     let fix = lambourne.radial_dme_fix(&RadialDme::new(270.0, 15.0)).unwrap();
+//!     let back = lambourne.radial_dme_to(&fix).unwrap();
+//!     assert_eq!(270.0, back.radial_deg().round());
+//!
+//!     // describe a point relative to the nearest navaid
+//!     let index = NavaidIndex::new(&navaids);
+//!     let (navaid, radial_dme) = index.nearest_radial_dme(&Coordinate::new(51.5, -0.1)).unwrap();
+//!     println!("{} {}", navaid.ident(), radial_dme);
+//! #    Ok(())
+//! # }
+//! ```
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::geo::{normalize_bearing, Coordinate};
+use crate::navaids::{Navaid, NavaidType};
+
+/// Iterations used to place a fix when the DME is not co-located with the navaid.
+const DME_OFFSET_ITERATIONS: usize = 5;
+
+/// A magnetic radial and DME distance from a navaid.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+pub struct RadialDme {
+    radial_deg: f64,
+    distance_nm: f64,
+}
+
+impl RadialDme {
+    /// Creates a radial/DME with the radial `radial_deg` (degrees magnetic) and DME distance
+    /// `distance_nm` (nautical miles).
+    pub fn new(radial_deg: f64, distance_nm: f64) -> Self {
+        RadialDme {
+            radial_deg: normalize_bearing(radial_deg),
+            distance_nm,
+        }
+    }
+    /// Magnetic radial in degrees, from 0 up to 360.
+    pub fn radial_deg(&self) -> f64 {
+        self.radial_deg
+    }
+    /// DME distance in nautical miles.
+    pub fn distance_nm(&self) -> f64 {
+        self.distance_nm
+    }
+}
+
+/// Formats as e.g. "R270/D15.0", with radial 360 for north.
+impl fmt::Display for RadialDme {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let radial = match self.radial_deg.round() as u32 {
+            0 => 360,
+            radial => radial,
+        };
+        write!(f, "R{:03}/D{:.1}", radial, self.distance_nm)
+    }
+}
+
+/// `true` if `navaid` provides both radials and DME.
+pub fn provides_radial_dme(navaid: &Navaid) -> bool {
+    matches!(
+        navaid.navaid_type(),
+        NavaidType::VorDme | NavaidType::Vortac | NavaidType::Tacan
+    )
+}
+
+/// Returns the position at `radial_dme` from `navaid`, or `None` if the navaid does not provide
+/// radials and DME, or its position or variation is not available.
+pub fn radial_dme_fix(navaid: &Navaid, radial_dme: &RadialDme) -> Option<Coordinate> {
+    if !provides_radial_dme(navaid) {
+        return None;
+    }
+    let station = navaid.coordinate()?;
+    let dme = navaid.dme_coordinate()?;
+    let bearing = normalize_bearing(radial_dme.radial_deg + navaid.radial_variation_deg()?);
+    let mut distance = radial_dme.distance_nm;
+    let mut fix = station.destination(bearing, distance);
+    if dme != station {
+        // move along the radial until the distance from the DME is right
+        for _ in 0..DME_OFFSET_ITERATIONS {
+            distance += radial_dme.distance_nm - dme.distance_nm(&fix);
+            fix = station.destination(bearing, distance.max(0.0));
+        }
+    }
+    Some(fix)
+}
+
+/// Returns the radial and DME distance of `point` from `navaid`, or `None` if the navaid does not
+/// provide radials and DME, or its position or variation is not available.
+pub fn radial_dme_to(navaid: &Navaid, point: &Coordinate) -> Option<RadialDme> {
+    if !provides_radial_dme(navaid) {
+        return None;
+    }
+    let bearing = navaid.coordinate()?.initial_bearing_deg(point);
+    Some(RadialDme::new(
+        bearing - navaid.radial_variation_deg()?,
+        navaid.dme_coordinate()?.distance_nm(point),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{self, navaid};
+
+    #[test]
+    fn round_trip_with_slaved_variation() {
+        let navaids = test_support::navaids([navaid(1, "SLV", "VOR-DME")
+            .position(50.0, 0.0)
+            .set("slaved_variation_deg", -10.0)
+            .set("magnetic_variation_deg", -2.0)]);
+        let navaid = &navaids[&1];
+        let fix = navaid.radial_dme_fix(&RadialDme::new(270.0, 15.0)).unwrap();
+        // radials are aligned to the slaved variation, i.e. R270 is 260° true
+        let station = Coordinate::new(50.0, 0.0);
+        assert!((station.initial_bearing_deg(&fix) - 260.0).abs() < 1e-9);
+        assert!((station.distance_nm(&fix) - 15.0).abs() < 1e-9);
+        let back = navaid.radial_dme_to(&fix).unwrap();
+        assert!((back.radial_deg() - 270.0).abs() < 1e-9);
+        assert!((back.distance_nm() - 15.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn round_trip_with_offset_dme() {
+        // the DME is about 0.8 nm east of the VOR
+        let navaids = test_support::navaids([navaid(1, "OFS", "VORTAC")
+            .position(50.0, 0.0)
+            .set("dme_latitude_deg", 50.0)
+            .set("dme_longitude_deg", 0.02)
+            .set("magnetic_variation_deg", 3.0)]);
+        let navaid = &navaids[&1];
+        for radial in [0.0, 90.0, 180.0, 270.0] {
+            let fix = navaid
+                .radial_dme_fix(&RadialDme::new(radial, 15.0))
+                .unwrap();
+            let dme = navaid.dme_coordinate().unwrap();
+            assert!((dme.distance_nm(&fix) - 15.0).abs() < 1e-6, "R{}", radial);
+            let back = navaid.radial_dme_to(&fix).unwrap();
+            // R000 may come back as R359.999...
+            let error = normalize_bearing(back.radial_deg() - radial + 180.0) - 180.0;
+            assert!(error.abs() < 1e-9, "R{}", radial);
+            assert!((back.distance_nm() - 15.0).abs() < 1e-6, "R{}", radial);
+        }
+    }
+
+    #[test]
+    fn navaids_without_radial_dme() {
+        let navaids = test_support::navaids([
+            navaid(1, "VOR", "VOR").set("magnetic_variation_deg", 1.0),
+            navaid(2, "DME", "DME").set("magnetic_variation_deg", 1.0),
+            // no variation
+            navaid(3, "VDM", "VOR-DME"),
+        ]);
+        let radial_dme = RadialDme::new(90.0, 10.0);
+        for navaid in navaids.values() {
+            assert_eq!(None, navaid.radial_dme_fix(&radial_dme));
+            assert_eq!(None, navaid.radial_dme_to(&Coordinate::new(1.0, 1.0)));
+        }
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!("R360/D15.0", RadialDme::new(0.0, 15.0).to_string());
+        assert_eq!("R360/D15.0", RadialDme::new(359.8, 15.0).to_string());
+        assert_eq!("R090/D5.5", RadialDme::new(-270.0, 5.46).to_string());
+    }
+}
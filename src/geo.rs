@@ -0,0 +1,474 @@
+//! Contains geographic primitives shared by the rest of the crate: coordinates, great-circle
+//! distances and bearings, bounding boxes and a simple spatial index.
+//!
+//! All distances are in nautical miles and all angles are in degrees unless stated otherwise.
+//! Distances are also available as a [`Length`], e.g. [`Coordinate::distance()`].
+//! Calculations use a spherical earth model, which is accurate to within about 0.5%.
+//!
+//! # Examples
+//! ```
+//! use ourairports::geo::*;
+//!
+//! // London Heathrow to New York JFK
+//! let egll = Coordinate::new(51.4706, -0.461941);
+//! let kjfk = Coordinate::new(40.639447, -73.779317);
+//! assert_eq!(2991.0, egll.distance_nm(&kjfk).round());
+//! assert_eq!(288.0, egll.initial_bearing_deg(&kjfk).round());
+//! ```
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::units::Length;
+
+/// Mean radius of the earth in nautical miles.
+pub const EARTH_RADIUS_NM: f64 = 3440.065;
+
+/// Number of kilometres in a nautical mile.
+pub const KM_PER_NM: f64 = 1.852;
+
+/// Number of feet in a nautical mile.
+pub const FEET_PER_NM: f64 = 6076.115;
+
+/// Number of statute miles in a nautical mile.
+pub const STATUTE_MILES_PER_NM: f64 = 1.150_779;
+
+/// A point on the surface of the earth in decimal degrees.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+pub struct Coordinate {
+    latitude_deg: f64,
+    longitude_deg: f64,
+}
+
+impl Coordinate {
+    /// Creates a new coordinate. Latitude is positive for north and longitude is positive for
+    /// east. Longitude is normalised to the range [-180, 180).
+    pub fn new(latitude_deg: f64, longitude_deg: f64) -> Self {
+        Coordinate {
+            latitude_deg,
+            longitude_deg: normalize_longitude(longitude_deg),
+        }
+    }
+    /// The latitude in decimal degrees (positive for north).
+    pub fn latitude_deg(&self) -> f64 {
+        self.latitude_deg
+    }
+    /// The longitude in decimal degrees (positive for east).
+    pub fn longitude_deg(&self) -> f64 {
+        self.longitude_deg
+    }
+    /// Great-circle distance to `other` in nautical miles.
+    pub fn distance_nm(&self, other: &Coordinate) -> f64 {
+        let (lat1, lat2) = (
+            self.latitude_deg.to_radians(),
+            other.latitude_deg.to_radians(),
+        );
+        let dlat = lat2 - lat1;
+        let dlon = (other.longitude_deg - self.longitude_deg).to_radians();
+        let a = (dlat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (dlon / 2.0).sin().powi(2);
+        2.0 * EARTH_RADIUS_NM * a.sqrt().min(1.0).asin()
+    }
+    /// Great-circle distance to `other` in kilometres.
+    pub fn distance_km(&self, other: &Coordinate) -> f64 {
+        self.distance_nm(other) * KM_PER_NM
+    }
+    /// Great-circle distance to `other` as a [`Length`] in nautical miles.
+    pub fn distance(&self, other: &Coordinate) -> Length {
+        Length::nautical_miles(self.distance_nm(other))
+    }
+    /// Initial true bearing of the great circle from this point to `other`, in degrees [0, 360).
+    pub fn initial_bearing_deg(&self, other: &Coordinate) -> f64 {
+        let (lat1, lat2) = (
+            self.latitude_deg.to_radians(),
+            other.latitude_deg.to_radians(),
+        );
+        let dlon = (other.longitude_deg - self.longitude_deg).to_radians();
+        let y = dlon.sin() * lat2.cos();
+        let x = lat1.cos() * lat2.sin() - lat1.sin() * lat2.cos() * dlon.cos();
+        normalize_bearing(y.atan2(x).to_degrees())
+    }
+    /// The point reached by travelling `distance_nm` nautical miles from this point along a great
+    /// circle with an initial true bearing of `bearing_deg`.
+    pub fn destination(&self, bearing_deg: f64, distance_nm: f64) -> Coordinate {
+        let lat1 = self.latitude_deg.to_radians();
+        let lon1 = self.longitude_deg.to_radians();
+        let bearing = bearing_deg.to_radians();
+        let d = distance_nm / EARTH_RADIUS_NM;
+        let lat2 = (lat1.sin() * d.cos() + lat1.cos() * d.sin() * bearing.cos()).asin();
+        let lon2 =
+            lon1 + (bearing.sin() * d.sin() * lat1.cos()).atan2(d.cos() - lat1.sin() * lat2.sin());
+        Coordinate::new(lat2.to_degrees(), lon2.to_degrees())
+    }
+    /// Distance in nautical miles from this point to the great circle through `start` and `end`,
+    /// positive if this point is to the right of the great circle when travelling from `start`
+    /// towards `end`.
+    pub fn cross_track_distance_nm(&self, start: &Coordinate, end: &Coordinate) -> f64 {
+        let d13 = start.distance_nm(self) / EARTH_RADIUS_NM;
+        let dtheta =
+            (start.initial_bearing_deg(self) - start.initial_bearing_deg(end)).to_radians();
+        (d13.sin() * dtheta.sin()).asin() * EARTH_RADIUS_NM
+    }
+    /// Distance in nautical miles from `start`, along the great circle through `start` and `end`,
+    /// to the point on it closest to this point. Negative if that point is behind `start`.
+    pub fn along_track_distance_nm(&self, start: &Coordinate, end: &Coordinate) -> f64 {
+        let d13 = start.distance_nm(self) / EARTH_RADIUS_NM;
+        let dtheta =
+            (start.initial_bearing_deg(self) - start.initial_bearing_deg(end)).to_radians();
+        let dxt = (d13.sin() * dtheta.sin()).asin();
+        let along = (d13.cos() / dxt.cos()).clamp(-1.0, 1.0).acos() * EARTH_RADIUS_NM;
+        if dtheta.cos() < 0.0 {
+            -along
+        } else {
+            along
+        }
+    }
+    /// [`Self::cross_track_distance_nm()`] as a [`Length`] in nautical miles.
+    pub fn cross_track_distance(&self, start: &Coordinate, end: &Coordinate) -> Length {
+        Length::nautical_miles(self.cross_track_distance_nm(start, end))
+    }
+    /// [`Self::along_track_distance_nm()`] as a [`Length`] in nautical miles.
+    pub fn along_track_distance(&self, start: &Coordinate, end: &Coordinate) -> Length {
+        Length::nautical_miles(self.along_track_distance_nm(start, end))
+    }
+    /// The point reached by travelling `distance` from this point along a great circle with an
+    /// initial true bearing of `bearing_deg`, see [`Self::destination()`].
+    pub fn destination_at(&self, bearing_deg: f64, distance: Length) -> Coordinate {
+        self.destination(bearing_deg, distance.to_nautical_miles())
+    }
+}
+
+/// Normalises a longitude to the range [-180, 180).
+pub fn normalize_longitude(longitude_deg: f64) -> f64 {
+    if (-180.0..180.0).contains(&longitude_deg) {
+        // avoid rounding errors for values already in range
+        longitude_deg
+    } else {
+        (longitude_deg + 180.0).rem_euclid(360.0) - 180.0
+    }
+}
+
+/// Normalises a bearing or heading to the range [0, 360).
+pub fn normalize_bearing(bearing_deg: f64) -> f64 {
+    bearing_deg.rem_euclid(360.0)
+}
+
+/// An area bounded by two parallels and two meridians.
+///
+/// If `west_deg` is greater than `east_deg`, the box crosses the antimeridian.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+pub struct BoundingBox {
+    south_deg: f64,
+    west_deg: f64,
+    north_deg: f64,
+    east_deg: f64,
+}
+
+impl BoundingBox {
+    /// Creates a new bounding box from its southern, western, northern and eastern edges.
+    pub fn new(south_deg: f64, west_deg: f64, north_deg: f64, east_deg: f64) -> Self {
+        BoundingBox {
+            south_deg,
+            west_deg: normalize_longitude(west_deg),
+            north_deg,
+            east_deg: if east_deg == 180.0 {
+                east_deg
+            } else {
+                normalize_longitude(east_deg)
+            },
+        }
+    }
+    /// Returns the smallest bounding box containing all `coordinates` (not attempting to cross
+    /// the antimeridian), or `None` if there are no coordinates.
+    pub fn from_coordinates<'a>(
+        coordinates: impl IntoIterator<Item = &'a Coordinate>,
+    ) -> Option<Self> {
+        coordinates.into_iter().fold(None, |bbox, c| {
+            let (lat, lon) = (c.latitude_deg, c.longitude_deg);
+            Some(match bbox {
+                None => BoundingBox::new(lat, lon, lat, lon),
+                Some(b) => BoundingBox {
+                    south_deg: b.south_deg.min(lat),
+                    west_deg: b.west_deg.min(lon),
+                    north_deg: b.north_deg.max(lat),
+                    east_deg: b.east_deg.max(lon),
+                },
+            })
+        })
+    }
+    /// Southern edge in decimal degrees.
+    pub fn south_deg(&self) -> f64 {
+        self.south_deg
+    }
+    /// Western edge in decimal degrees.
+    pub fn west_deg(&self) -> f64 {
+        self.west_deg
+    }
+    /// Northern edge in decimal degrees.
+    pub fn north_deg(&self) -> f64 {
+        self.north_deg
+    }
+    /// Eastern edge in decimal degrees.
+    pub fn east_deg(&self) -> f64 {
+        self.east_deg
+    }
+    /// `true` if the box crosses the antimeridian.
+    pub fn crosses_antimeridian(&self) -> bool {
+        self.west_deg > self.east_deg
+    }
+    /// `true` if `coordinate` lies within the box (edges included).
+    pub fn contains(&self, coordinate: &Coordinate) -> bool {
+        let (lat, lon) = (coordinate.latitude_deg, coordinate.longitude_deg);
+        if lat < self.south_deg || lat > self.north_deg {
+            return false;
+        }
+        if self.crosses_antimeridian() {
+            lon >= self.west_deg || lon <= self.east_deg
+        } else {
+            lon >= self.west_deg && lon <= self.east_deg
+        }
+    }
+}
+
+/// Size of a [`SpatialIndex`] cell in degrees.
+const CELL_SIZE_DEG: f64 = 1.0;
+
+fn cell_of(latitude_deg: f64, longitude_deg: f64) -> (i32, i32) {
+    (
+        (latitude_deg / CELL_SIZE_DEG).floor() as i32,
+        (normalize_longitude(longitude_deg) / CELL_SIZE_DEG).floor() as i32,
+    )
+}
+
+/// A grid-based index of items by position, for radius, bounding box and nearest-neighbour
+/// queries.
+#[derive(Debug, Clone)]
+pub struct SpatialIndex<T> {
+    cells: HashMap<(i32, i32), Vec<(Coordinate, T)>>,
+    len: usize,
+}
+
+impl<T> Default for SpatialIndex<T> {
+    fn default() -> Self {
+        SpatialIndex {
+            cells: HashMap::new(),
+            len: 0,
+        }
+    }
+}
+
+impl<T> FromIterator<(Coordinate, T)> for SpatialIndex<T> {
+    fn from_iter<I: IntoIterator<Item = (Coordinate, T)>>(iter: I) -> Self {
+        let mut index = SpatialIndex::new();
+        for (coordinate, item) in iter {
+            index.insert(coordinate, item);
+        }
+        index
+    }
+}
+
+impl<T> SpatialIndex<T> {
+    /// Creates an empty index.
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Adds `item` located at `coordinate` to the index.
+    pub fn insert(&mut self, coordinate: Coordinate, item: T) {
+        self.cells
+            .entry(cell_of(coordinate.latitude_deg, coordinate.longitude_deg))
+            .or_default()
+            .push((coordinate, item));
+        self.len += 1;
+    }
+    /// Returns the number of items in the index.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+    /// Returns `true` if the index contains no items.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+    /// Returns all items within `radius_nm` nautical miles of `center`, together with their
+    /// distance, sorted by increasing distance.
+    pub fn within_radius(&self, center: &Coordinate, radius_nm: f64) -> Vec<(&T, f64)> {
+        let lat_span = (radius_nm / EARTH_RADIUS_NM).to_degrees();
+        let south = (center.latitude_deg - lat_span).max(-90.0);
+        let north = (center.latitude_deg + lat_span).min(90.0);
+        let max_abs_lat = south.abs().max(north.abs());
+        let lon_span = if max_abs_lat >= 89.9 {
+            180.0
+        } else {
+            (lat_span / max_abs_lat.to_radians().cos()).min(180.0)
+        };
+        let bbox = if lon_span >= 180.0 {
+            BoundingBox::new(south, -180.0, north, 180.0)
+        } else {
+            BoundingBox::new(
+                south,
+                center.longitude_deg - lon_span,
+                north,
+                center.longitude_deg + lon_span,
+            )
+        };
+        let mut found = self
+            .candidates(&bbox)
+            .filter_map(|(coordinate, item)| {
+                let distance = center.distance_nm(coordinate);
+                (distance <= radius_nm).then_some((item, distance))
+            })
+            .collect::<Vec<_>>();
+        found.sort_by(|a, b| a.1.total_cmp(&b.1));
+        found
+    }
+    /// Returns all items within `bbox`.
+    pub fn in_bounding_box(&self, bbox: &BoundingBox) -> Vec<&T> {
+        self.candidates(bbox)
+            .filter(|(coordinate, _)| bbox.contains(coordinate))
+            .map(|(_, item)| item)
+            .collect()
+    }
+    /// Returns the `n` items nearest to `center`, together with their distance in nautical miles,
+    /// sorted by increasing distance.
+    pub fn nearest(&self, center: &Coordinate, n: usize) -> Vec<(&T, f64)> {
+        if n == 0 || self.is_empty() {
+            return vec![];
+        }
+        let max_radius = std::f64::consts::PI * EARTH_RADIUS_NM;
+        let mut radius = 50.0;
+        loop {
+            let mut found = self.within_radius(center, radius);
+            if found.len() >= n || radius >= max_radius {
+                found.truncate(n);
+                return found;
+            }
+            radius *= 2.0;
+        }
+    }
+    /// Items in all cells overlapping `bbox`.
+    fn candidates<'a>(&'a self, bbox: &BoundingBox) -> impl Iterator<Item = &'a (Coordinate, T)> {
+        let (south, west) = cell_of(bbox.south_deg, bbox.west_deg);
+        let north = (bbox.north_deg / CELL_SIZE_DEG).floor() as i32;
+        let east = if bbox.east_deg >= 180.0 {
+            (180.0 / CELL_SIZE_DEG) as i32 - 1
+        } else {
+            cell_of(0.0, bbox.east_deg).1
+        };
+        let columns = (360.0 / CELL_SIZE_DEG) as i32;
+        let min_column = -(180.0 / CELL_SIZE_DEG) as i32;
+        let width = if bbox.crosses_antimeridian() && west == east {
+            // the box wraps around the globe and both edges lie in the same column
+            columns - 1
+        } else {
+            (east - west).rem_euclid(columns)
+        };
+        (south..=north)
+            .flat_map(move |row| {
+                (0..=width).map(move |offset| {
+                    (
+                        row,
+                        (west - min_column + offset).rem_euclid(columns) + min_column,
+                    )
+                })
+            })
+            .filter_map(|cell| self.cells.get(&cell))
+            .flatten()
+    }
+}
+
+/// Trait for converting OurAirports data into [GeoJSON](https://geojson.org/) features.
+pub trait ToGeoJson {
+    /// Returns the data as a GeoJSON `Feature`, or `None` if it has no known location.
+    fn to_geojson_feature(&self) -> Option<serde_json::Value>;
+}
+
+/// Returns a GeoJSON `Point` geometry at `coordinate`.
+pub fn geojson_point(coordinate: &Coordinate) -> serde_json::Value {
+    serde_json::json!({
+        "type": "Point",
+        "coordinates": [coordinate.longitude_deg, coordinate.latitude_deg],
+    })
+}
+
+/// Returns a GeoJSON `LineString` geometry through `coordinates`.
+pub fn geojson_line_string(coordinates: &[Coordinate]) -> serde_json::Value {
+    serde_json::json!({
+        "type": "LineString",
+        "coordinates": coordinates
+            .iter()
+            .map(|c| [c.longitude_deg, c.latitude_deg])
+            .collect::<Vec<_>>(),
+    })
+}
+
+/// Returns a GeoJSON `Polygon` geometry with the outer ring `ring`, which is closed if its last
+/// coordinate is not the same as its first.
+pub fn geojson_polygon(ring: &[Coordinate]) -> serde_json::Value {
+    let mut positions = ring
+        .iter()
+        .map(|c| [c.longitude_deg, c.latitude_deg])
+        .collect::<Vec<_>>();
+    if let (Some(first), Some(last)) = (positions.first(), positions.last()) {
+        if first != last {
+            positions.push(*first);
+        }
+    }
+    serde_json::json!({
+        "type": "Polygon",
+        "coordinates": [positions],
+    })
+}
+
+/// Returns a GeoJSON `Feature` with the given geometry and properties.
+pub fn geojson_feature(
+    geometry: serde_json::Value,
+    properties: serde_json::Value,
+) -> serde_json::Value {
+    serde_json::json!({
+        "type": "Feature",
+        "geometry": geometry,
+        "properties": properties,
+    })
+}
+
+/// Returns a GeoJSON `FeatureCollection` of `features`.
+pub fn geojson_feature_collection(
+    features: impl IntoIterator<Item = serde_json::Value>,
+) -> serde_json::Value {
+    serde_json::json!({
+        "type": "FeatureCollection",
+        "features": features.into_iter().collect::<Vec<_>>(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bounding_box_wrapping_around_within_one_cell() {
+        let mut index = SpatialIndex::new();
+        index.insert(Coordinate::new(0.0, 10.8), "inside west");
+        index.insert(Coordinate::new(0.0, 10.1), "inside east");
+        index.insert(Coordinate::new(0.0, -170.0), "inside far");
+        index.insert(Coordinate::new(0.0, 10.5), "outside");
+        let bbox = BoundingBox::new(-1.0, 10.7, 1.0, 10.2);
+        assert!(bbox.crosses_antimeridian());
+        let mut found = index.in_bounding_box(&bbox);
+        found.sort_unstable();
+        assert_eq!(vec![&"inside east", &"inside far", &"inside west"], found);
+    }
+
+    #[test]
+    fn bounding_box_crossing_antimeridian() {
+        let mut index = SpatialIndex::new();
+        index.insert(Coordinate::new(0.0, 179.5), "west of antimeridian");
+        index.insert(Coordinate::new(0.0, -179.5), "east of antimeridian");
+        index.insert(Coordinate::new(0.0, 0.0), "outside");
+        let mut found = index.in_bounding_box(&BoundingBox::new(-1.0, 179.0, 1.0, -179.0));
+        found.sort_unstable();
+        assert_eq!(
+            vec![&"east of antimeridian", &"west of antimeridian"],
+            found
+        );
+    }
+}
@@ -0,0 +1,259 @@
+//! Contains the geometry of runways: centrelines, surface polygons and displaced threshold
+//! positions, as [`Coordinate`]s and as GeoJSON.
+//!
+//! Geometry is computed from the positions of the two runway ends, so it is only available for
+//! runways with both end positions, either from the source data ([`RunwayGeometry::new()`]) or
+//! derived by the [`enrich`](crate::enrich) module ([`RunwayGeometry::from_enriched()`]).
+//! The runway surface is assumed to be a rectangle of [`Runway::width_ft()`] centred on the line
+//! between the two ends.
+//!
+//! # Examples
+//! ```no_run
+//! use ourairports::geo::{geojson_feature_collection, ToGeoJson};
+//! use ourairports::geometry::RunwayGeometry;
+//! use ourairports::runways::*;
+//! use ourairports::units::LengthUnit;
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!     let runways = get_runways_csv()?;
+//!
+//!     // Runway 08L/26R at Vancouver International Airport
+//!     let geometry = RunwayGeometry::new(runways.get(&234512).unwrap()).unwrap();
+//!     println!("{:.0} long", geometry.length().convert_to(LengthUnit::Feet));
+//!
+//!     let map = geojson_feature_collection(
+//!         runways.values().filter_map(|r| r.to_geojson_feature()),
+//!     );
+//! #    Ok(())
+//! # }
+//! ```
+
+use serde_json::json;
+
+use crate::enrich::EnrichedRunway;
+use crate::geo::{
+    geojson_feature, geojson_line_string, geojson_point, geojson_polygon, normalize_bearing,
+    Coordinate, ToGeoJson,
+};
+use crate::runways::{Runway, RunwayEnd};
+use crate::units::Length;
+
+/// Geometry of a [`Runway`] with known end positions.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RunwayGeometry<'a> {
+    runway: &'a Runway,
+    low_end: Coordinate,
+    high_end: Coordinate,
+}
+
+impl<'a> RunwayGeometry<'a> {
+    /// Returns the geometry of `runway`, or `None` if the position of either end is not
+    /// available.
+    pub fn new(runway: &'a Runway) -> Option<Self> {
+        let position =
+            |end: RunwayEnd| Some(Coordinate::new(end.latitude_deg()?, end.longitude_deg()?));
+        Some(RunwayGeometry {
+            runway,
+            low_end: position(runway.low_end())?,
+            high_end: position(runway.high_end())?,
+        })
+    }
+    /// Returns the geometry of an enriched runway, which may use derived end positions, or
+    /// `None` if the position of either end is not available.
+    pub fn from_enriched(runway: &EnrichedRunway<'a>) -> Option<Self> {
+        Some(RunwayGeometry {
+            runway: runway.runway(),
+            low_end: *runway.low_end().coordinate()?.value(),
+            high_end: *runway.high_end().coordinate()?.value(),
+        })
+    }
+    /// The runway.
+    pub fn runway(&self) -> &'a Runway {
+        self.runway
+    }
+    /// Centreline of the runway, from the low-numbered end to the high-numbered end.
+    pub fn centreline(&self) -> [Coordinate; 2] {
+        [self.low_end, self.high_end]
+    }
+    /// Length of the centreline, computed from the positions of the two ends, in nautical miles.
+    ///
+    /// This may differ from [`Runway::length()`], which includes overrun areas, etc.
+    pub fn length(&self) -> Length {
+        self.low_end.distance(&self.high_end)
+    }
+    /// True bearing of the centreline from the low-numbered end to the high-numbered end.
+    pub fn bearing_deg(&self) -> f64 {
+        self.low_end.initial_bearing_deg(&self.high_end)
+    }
+    /// Position of the landing threshold of the low-numbered end: the end of the runway, moved
+    /// along the centreline by [`Runway::le_displaced_threshold_ft()`] if there is one.
+    pub fn low_threshold(&self) -> Coordinate {
+        let displaced = self.runway.le_displaced_threshold();
+        self.low_end
+            .destination_at(self.bearing_deg(), displaced.unwrap_or(Length::feet(0.0)))
+    }
+    /// Position of the landing threshold of the high-numbered end: the end of the runway, moved
+    /// along the centreline by [`Runway::he_displaced_threshold_ft()`] if there is one.
+    pub fn high_threshold(&self) -> Coordinate {
+        let displaced = self.runway.he_displaced_threshold();
+        let bearing = self.high_end.initial_bearing_deg(&self.low_end);
+        self.high_end
+            .destination_at(bearing, displaced.unwrap_or(Length::feet(0.0)))
+    }
+    /// Corners of the runway surface, counterclockwise (as GeoJSON expects) from the right side
+    /// of the low-numbered end, as seen from an aircraft lined up on it. Returns `None` if the
+    /// width is not available.
+    pub fn polygon(&self) -> Option<[Coordinate; 4]> {
+        let half_width = self.runway.width()? / 2.0;
+        let low_bearing = self.bearing_deg();
+        let high_bearing = self.high_end.initial_bearing_deg(&self.low_end);
+        let offset = |point: &Coordinate, bearing: f64| {
+            point.destination_at(normalize_bearing(bearing), half_width)
+        };
+        Some([
+            offset(&self.low_end, low_bearing + 90.0),
+            offset(&self.high_end, high_bearing - 90.0),
+            offset(&self.high_end, high_bearing + 90.0),
+            offset(&self.low_end, low_bearing - 90.0),
+        ])
+    }
+    /// Returns separate GeoJSON features for the surface (see
+    /// [`to_geojson_feature()`](ToGeoJson::to_geojson_feature())), the centreline and both
+    /// landing thresholds. The `part` property of each feature is `surface`, `centreline` or
+    /// `threshold`, and thresholds have the runway end identifier as the `ident` property.
+    pub fn to_geojson_features(&self) -> Vec<serde_json::Value> {
+        let id = self.runway.id();
+        let threshold = |point: Coordinate, end: RunwayEnd| {
+            geojson_feature(
+                geojson_point(&point),
+                json!({
+                    "runway_id": id,
+                    "part": "threshold",
+                    "ident": end.ident(),
+                    "displaced_threshold_ft": end.displaced_threshold_ft(),
+                    "lda_ft": end.lda_ft(),
+                }),
+            )
+        };
+        vec![
+            self.surface_feature(),
+            geojson_feature(
+                geojson_line_string(&self.centreline()),
+                json!({ "runway_id": id, "part": "centreline" }),
+            ),
+            threshold(self.low_threshold(), self.runway.low_end()),
+            threshold(self.high_threshold(), self.runway.high_end()),
+        ]
+    }
+    fn surface_feature(&self) -> serde_json::Value {
+        let geometry = match self.polygon() {
+            Some(polygon) => geojson_polygon(&polygon),
+            None => geojson_line_string(&self.centreline()),
+        };
+        let mut properties = serde_json::to_value(self.runway).unwrap_or_default();
+        if let Some(properties) = properties.as_object_mut() {
+            properties.insert("part".to_string(), json!("surface"));
+        }
+        geojson_feature(geometry, properties)
+    }
+}
+
+/// The runway surface as a `Polygon`, or as the centreline `LineString` if the width is not
+/// available, with the runway as its properties.
+impl ToGeoJson for RunwayGeometry<'_> {
+    fn to_geojson_feature(&self) -> Option<serde_json::Value> {
+        Some(self.surface_feature())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+    use crate::geo::FEET_PER_NM;
+    use crate::test_support::{self, runway};
+    use crate::Id;
+
+    fn runways() -> BTreeMap<Id, Runway> {
+        test_support::runways([
+            runway(1, 1)
+                .set("length_ft", 10000)
+                .set("width_ft", 300)
+                .set("le_ident", "09")
+                .set("le_latitude_deg", 51.0)
+                .set("le_longitude_deg", -1.0)
+                .set("le_displaced_threshold_ft", 1000)
+                .set("he_ident", "27")
+                .set("he_latitude_deg", 51.0)
+                .set("he_longitude_deg", -0.95),
+            // no width, length or displaced thresholds
+            runway(2, 1)
+                .set("le_latitude_deg", 0.0)
+                .set("le_longitude_deg", 0.0)
+                .set("he_latitude_deg", 0.0)
+                .set("he_longitude_deg", 0.01),
+            runway(3, 1).set("le_latitude_deg", 0.0),
+        ])
+    }
+
+    #[test]
+    fn polygon_corners() {
+        let runways = runways();
+        let geometry = RunwayGeometry::new(&runways[&1]).unwrap();
+        let [low, high] = geometry.centreline();
+        let half_width_nm = 150.0 / FEET_PER_NM;
+        let corners = geometry.polygon().unwrap();
+        // right of the low end, right of the high end as seen from the low end, then the left
+        for (corner, end, right) in [
+            (corners[0], low, true),
+            (corners[1], high, true),
+            (corners[2], high, false),
+            (corners[3], low, false),
+        ] {
+            assert!((corner.distance_nm(&end) - half_width_nm).abs() < 1e-6);
+            let cross_track = corner.cross_track_distance_nm(&low, &high);
+            assert!((cross_track.abs() - half_width_nm).abs() < 1e-6);
+            assert_eq!(right, cross_track > 0.0);
+        }
+        // counterclockwise, i.e. a positive shoelace area
+        let area = (0..4)
+            .map(|i| {
+                let (a, b) = (corners[i], corners[(i + 1) % 4]);
+                a.longitude_deg() * b.latitude_deg() - b.longitude_deg() * a.latitude_deg()
+            })
+            .sum::<f64>();
+        assert!(area > 0.0);
+        assert!(RunwayGeometry::new(&runways[&2])
+            .unwrap()
+            .polygon()
+            .is_none());
+        assert!(RunwayGeometry::new(&runways[&3]).is_none());
+    }
+
+    #[test]
+    fn displaced_thresholds() {
+        let runways = runways();
+        let geometry = RunwayGeometry::new(&runways[&1]).unwrap();
+        let [low, high] = geometry.centreline();
+        let threshold = geometry.low_threshold();
+        assert!((threshold.distance_nm(&low) - 1000.0 / FEET_PER_NM).abs() < 1e-6);
+        assert!(threshold.along_track_distance_nm(&low, &high) > 0.0);
+        assert!(threshold.cross_track_distance_nm(&low, &high).abs() < 1e-9);
+        assert_eq!(high, geometry.high_threshold());
+
+        let [low_end, high_end] = runways[&1].ends();
+        assert_eq!(Some(10000), low_end.tora_ft());
+        assert_eq!(Some(9000), low_end.lda_ft());
+        assert_eq!(Some(10000), high_end.tora_ft());
+        assert_eq!(Some(10000), high_end.lda_ft());
+        assert_eq!(None, runways[&2].low_end().tora_ft());
+        assert_eq!(None, runways[&2].low_end().lda_ft());
+
+        let features = geometry.to_geojson_features();
+        assert_eq!(4, features.len());
+        assert_eq!("Polygon", features[0]["geometry"]["type"]);
+        assert_eq!(9000, features[2]["properties"]["lda_ft"]);
+        assert_eq!("27", features[3]["properties"]["ident"]);
+    }
+}
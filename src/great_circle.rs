@@ -0,0 +1,398 @@
+//! Contains analysis of the great-circle route between two airports: distance, courses,
+//! waypoints, airports near the track and ETOPS-style checks of the distance to adequate
+//! airports along the route.
+//!
+//! Adequate airports are selected with an [`AirportQuery`], using only its filters; sorting and
+//! paging are ignored.
+//!
+//! # Examples
+//! ```no_run
+//! use ourairports::airports::*;
+//! use ourairports::great_circle::*;
+//! use ourairports::query::*;
+//! use ourairports::runways::*;
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!     let airports = get_airports_csv()?;
+//!     let runways = get_runways_csv()?;
+//!     let index = AirportIndex::with_runways(&airports, &runways);
+//!
+//!     let route = GreatCircleRoute::new(
+//!         index.get_by_ident("EGLL").unwrap(),
+//!         index.get_by_ident("KJFK").unwrap(),
+//!     );
+//!     println!("{:.0} nm, initial course {:03.0}", route.distance_nm(), route.initial_course_deg());
+//!
+//!     // is every point of the route within 120 minutes at 400 kt of an airport with a paved
+//!     // runway of at least 8000 ft?
+//!     let adequate = AirportQuery::new()
+//!         .min_runway_length_ft(8000)
+//!         .paved_runway(true)
+//!         .airport_types([AirportType::LargeAirport, AirportType::MediumAirport]);
+//!     let analysis = route.diversion_analysis(&index, &adequate);
+//!     println!("{}", analysis.within(DiversionLimit::new(120.0, 400.0).distance_nm()));
+//! #    Ok(())
+//! # }
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+use crate::airports::Airport;
+use crate::geo::{normalize_bearing, Coordinate, SpatialIndex, KM_PER_NM, STATUTE_MILES_PER_NM};
+use crate::query::{AirportIndex, AirportQuery};
+use crate::units::Length;
+
+/// Spacing in nautical miles of the points at which the distance to adequate airports is
+/// checked.
+const DIVERSION_SAMPLE_SPACING_NM: f64 = 5.0;
+
+/// The great-circle route between two airports.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GreatCircleRoute<'a> {
+    from: &'a Airport,
+    to: &'a Airport,
+    distance_nm: f64,
+    initial_course_deg: f64,
+    final_course_deg: f64,
+}
+
+impl<'a> GreatCircleRoute<'a> {
+    /// Creates the route from airport `from` to airport `to`.
+    pub fn new(from: &'a Airport, to: &'a Airport) -> Self {
+        let (start, end) = (from.coordinate(), to.coordinate());
+        GreatCircleRoute {
+            from,
+            to,
+            distance_nm: start.distance_nm(&end),
+            initial_course_deg: start.initial_bearing_deg(&end),
+            final_course_deg: normalize_bearing(end.initial_bearing_deg(&start) + 180.0),
+        }
+    }
+    /// The departure airport.
+    pub fn from(&self) -> &'a Airport {
+        self.from
+    }
+    /// The arrival airport.
+    pub fn to(&self) -> &'a Airport {
+        self.to
+    }
+    /// Length of the route in nautical miles.
+    pub fn distance_nm(&self) -> f64 {
+        self.distance_nm
+    }
+    /// Length of the route in kilometres.
+    pub fn distance_km(&self) -> f64 {
+        self.distance_nm * KM_PER_NM
+    }
+    /// Length of the route in statute miles.
+    pub fn distance_sm(&self) -> f64 {
+        self.distance_nm * STATUTE_MILES_PER_NM
+    }
+    /// Length of the route as a [`Length`] in nautical miles.
+    pub fn distance(&self) -> Length {
+        Length::nautical_miles(self.distance_nm)
+    }
+    /// True course at the departure airport in degrees.
+    pub fn initial_course_deg(&self) -> f64 {
+        self.initial_course_deg
+    }
+    /// True course at the arrival airport in degrees.
+    pub fn final_course_deg(&self) -> f64 {
+        self.final_course_deg
+    }
+
+    /// The point `distance_nm` nautical miles along the route from the departure airport.
+    pub fn point_at(&self, distance_nm: f64) -> Coordinate {
+        self.from
+            .coordinate()
+            .destination(self.initial_course_deg, distance_nm)
+    }
+    /// Points along the route every `spacing_nm` nautical miles, starting at the departure
+    /// airport and ending at the arrival airport. The last interval may be shorter. Only the two
+    /// airports are returned if `spacing_nm` is not positive.
+    pub fn waypoints(&self, spacing_nm: f64) -> Vec<Coordinate> {
+        let mut points = vec![self.from.coordinate()];
+        if spacing_nm > 0.0 {
+            let mut distance = spacing_nm;
+            while distance < self.distance_nm {
+                points.push(self.point_at(distance));
+                distance += spacing_nm;
+            }
+        }
+        if self.distance_nm > 0.0 {
+            points.push(self.to.coordinate());
+        }
+        points
+    }
+    /// Distance in nautical miles from `point` to the nearest point of the route, which may be
+    /// one of the airports.
+    pub fn distance_from_route_nm(&self, point: &Coordinate) -> f64 {
+        let (start, end) = (self.from.coordinate(), self.to.coordinate());
+        let along = point.along_track_distance_nm(&start, &end);
+        if along <= 0.0 {
+            start.distance_nm(point)
+        } else if along >= self.distance_nm {
+            end.distance_nm(point)
+        } else {
+            point.cross_track_distance_nm(&start, &end).abs()
+        }
+    }
+
+    /// Returns the airports in `index` within `corridor_nm` nautical miles of the route, sorted
+    /// by their position along it. The departure and arrival airports are included.
+    pub fn airports_near<'b>(
+        &self,
+        index: &AirportIndex<'b>,
+        corridor_nm: f64,
+    ) -> Vec<AirportNearRoute<'b>> {
+        let (start, end) = (self.from.coordinate(), self.to.coordinate());
+        let midpoint = self.point_at(self.distance_nm / 2.0);
+        let mut found = index
+            .within_radius(&midpoint, self.distance_nm / 2.0 + corridor_nm)
+            .into_iter()
+            .filter_map(|(airport, _)| {
+                let coordinate = airport.coordinate();
+                let distance_nm = self.distance_from_route_nm(&coordinate);
+                (distance_nm <= corridor_nm).then(|| AirportNearRoute {
+                    airport,
+                    along_track_nm: coordinate
+                        .along_track_distance_nm(&start, &end)
+                        .clamp(0.0, self.distance_nm),
+                    distance_nm,
+                })
+            })
+            .collect::<Vec<_>>();
+        found.sort_by(|a, b| a.along_track_nm.total_cmp(&b.along_track_nm));
+        found
+    }
+    /// Returns the highest airport with a known elevation within `corridor_nm` nautical miles of
+    /// the route, including the departure and arrival airports.
+    pub fn highest_airport_near<'b>(
+        &self,
+        index: &AirportIndex<'b>,
+        corridor_nm: f64,
+    ) -> Option<&'b Airport> {
+        self.airports_near(index, corridor_nm)
+            .into_iter()
+            .filter_map(|near| Some((near.airport, near.airport.elevation_ft()?)))
+            .max_by_key(|(_, elevation_ft)| *elevation_ft)
+            .map(|(airport, _)| airport)
+    }
+    /// Returns the airports matching `query` within `corridor_nm` nautical miles of the route,
+    /// other than the departure and arrival airports, sorted by their position along it.
+    pub fn alternates<'b>(
+        &self,
+        index: &AirportIndex<'b>,
+        corridor_nm: f64,
+        query: &AirportQuery,
+    ) -> Vec<AirportNearRoute<'b>> {
+        self.airports_near(index, corridor_nm)
+            .into_iter()
+            .filter(|near| near.airport != self.from && near.airport != self.to)
+            .filter(|near| query.matches(near.airport, index.runways(near.airport.id())))
+            .collect()
+    }
+    /// Finds the point of the route furthest from an adequate airport, i.e. one in `index`
+    /// matching `adequacy`. The route is checked every 5 nm.
+    ///
+    /// The departure and arrival airports count as adequate if they match `adequacy`.
+    pub fn diversion_analysis<'b>(
+        &self,
+        index: &AirportIndex<'b>,
+        adequacy: &AirportQuery,
+    ) -> DiversionAnalysis<'b> {
+        let adequate = index
+            .airports()
+            .iter()
+            .filter(|airport| adequacy.matches(airport, index.runways(airport.id())))
+            .map(|airport| (airport.coordinate(), *airport))
+            .collect::<SpatialIndex<_>>();
+        let mut worst = DiversionAnalysis {
+            point: self.from.coordinate(),
+            along_track_nm: 0.0,
+            nearest_adequate: None,
+            distance_nm: f64::INFINITY,
+        };
+        let mut worst_distance = f64::NEG_INFINITY;
+        let points = self.waypoints(DIVERSION_SAMPLE_SPACING_NM);
+        let last = points.len() - 1;
+        for (i, point) in points.into_iter().enumerate() {
+            let (nearest, distance_nm) = match adequate.nearest(&point, 1).first() {
+                Some((airport, distance)) => (Some(**airport), *distance),
+                None => (None, f64::INFINITY),
+            };
+            if distance_nm > worst_distance {
+                worst_distance = distance_nm;
+                worst = DiversionAnalysis {
+                    point,
+                    along_track_nm: if i == last {
+                        self.distance_nm
+                    } else {
+                        i as f64 * DIVERSION_SAMPLE_SPACING_NM
+                    },
+                    nearest_adequate: nearest,
+                    distance_nm,
+                };
+            }
+        }
+        worst
+    }
+}
+
+/// An airport near a [`GreatCircleRoute`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AirportNearRoute<'a> {
+    airport: &'a Airport,
+    along_track_nm: f64,
+    distance_nm: f64,
+}
+
+impl<'a> AirportNearRoute<'a> {
+    /// The airport.
+    pub fn airport(&self) -> &'a Airport {
+        self.airport
+    }
+    /// Distance in nautical miles from the departure airport to the point of the route closest
+    /// to the airport.
+    pub fn along_track_nm(&self) -> f64 {
+        self.along_track_nm
+    }
+    /// Distance in nautical miles from the airport to the route.
+    pub fn distance_nm(&self) -> f64 {
+        self.distance_nm
+    }
+}
+
+/// The point of a route furthest from an adequate airport, as returned by
+/// [`GreatCircleRoute::diversion_analysis()`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DiversionAnalysis<'a> {
+    point: Coordinate,
+    along_track_nm: f64,
+    nearest_adequate: Option<&'a Airport>,
+    distance_nm: f64,
+}
+
+impl<'a> DiversionAnalysis<'a> {
+    /// The point of the route furthest from an adequate airport.
+    pub fn point(&self) -> Coordinate {
+        self.point
+    }
+    /// Distance of the point from the departure airport along the route, in nautical miles.
+    pub fn along_track_nm(&self) -> f64 {
+        self.along_track_nm
+    }
+    /// The adequate airport nearest to the point. Returns `None` if there are no adequate
+    /// airports.
+    pub fn nearest_adequate(&self) -> Option<&'a Airport> {
+        self.nearest_adequate
+    }
+    /// Maximum distance from an adequate airport along the route, in nautical miles. Infinite if
+    /// there are no adequate airports.
+    pub fn max_distance_nm(&self) -> f64 {
+        self.distance_nm
+    }
+    /// `true` if every point of the route is within `max_diversion_nm` nautical miles of an
+    /// adequate airport.
+    pub fn within(&self, max_diversion_nm: f64) -> bool {
+        self.distance_nm <= max_diversion_nm
+    }
+}
+
+/// Parameters of an ETOPS-style diversion limit: the maximum diversion time with one engine
+/// inoperative and the speed flown.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+pub struct DiversionLimit {
+    minutes: f64,
+    speed_kt: f64,
+}
+
+impl DiversionLimit {
+    /// Creates a limit of `minutes` at `speed_kt` knots in still air.
+    pub fn new(minutes: f64, speed_kt: f64) -> Self {
+        DiversionLimit { minutes, speed_kt }
+    }
+    /// Maximum diversion time in minutes.
+    pub fn minutes(&self) -> f64 {
+        self.minutes
+    }
+    /// Diversion speed in knots.
+    pub fn speed_kt(&self) -> f64 {
+        self.speed_kt
+    }
+    /// Maximum diversion distance in nautical miles.
+    pub fn distance_nm(&self) -> f64 {
+        self.minutes / 60.0 * self.speed_kt
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+    use crate::airports::AirportType;
+    use crate::test_support::{self, airport};
+    use crate::Id;
+
+    /// Airports one and two and a half degrees (about 60 and 150 nm) east of ORIG on the
+    /// equator.
+    fn airports() -> BTreeMap<Id, Airport> {
+        test_support::airports([
+            airport(1, "ORIG"),
+            airport(2, "MID").position(0.0, 1.0),
+            airport(3, "DEST").position(0.0, 2.5),
+        ])
+    }
+
+    #[test]
+    fn waypoints() {
+        let airports = airports();
+        let route = GreatCircleRoute::new(&airports[&1], &airports[&3]);
+        assert!((route.distance_nm() - 150.1).abs() < 0.1);
+        let longitudes = |spacing_nm| {
+            route
+                .waypoints(spacing_nm)
+                .iter()
+                .map(|p| (p.longitude_deg() * 100.0).round() / 100.0)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(vec![0.0, 1.0, 2.0, 2.5], longitudes(60.04));
+        assert_eq!(vec![0.0, 2.5], longitudes(0.0));
+        assert_eq!(vec![0.0, 2.5], longitudes(-5.0));
+        assert_eq!(vec![0.0, 2.5], longitudes(1000.0));
+
+        let same = GreatCircleRoute::new(&airports[&1], &airports[&1]);
+        assert_eq!(0.0, same.distance_nm());
+        assert_eq!(vec![airports[&1].coordinate()], same.waypoints(5.0));
+        assert_eq!(vec![airports[&1].coordinate()], same.waypoints(0.0));
+    }
+
+    #[test]
+    fn diversion_analysis() {
+        let airports = airports();
+        let index = AirportIndex::new(&airports);
+        let route = GreatCircleRoute::new(&airports[&1], &airports[&3]);
+
+        // the worst point is halfway between MID and DEST
+        let analysis = route.diversion_analysis(&index, &AirportQuery::new());
+        assert!((analysis.along_track_nm() - 105.0).abs() < 5.0);
+        assert!((analysis.max_distance_nm() - 45.0).abs() < 5.0);
+        assert!(analysis.within(50.0) && !analysis.within(40.0));
+
+        let none = AirportQuery::new().airport_types([AirportType::LargeAirport]);
+        let analysis = route.diversion_analysis(&index, &none);
+        assert_eq!(None, analysis.nearest_adequate());
+        assert_eq!(f64::INFINITY, analysis.max_distance_nm());
+        assert_eq!(0.0, analysis.along_track_nm());
+        assert!(!analysis.within(f64::MAX));
+
+        let same = GreatCircleRoute::new(&airports[&2], &airports[&2]);
+        let analysis = same.diversion_analysis(&index, &AirportQuery::new());
+        assert_eq!(Some(&airports[&2]), analysis.nearest_adequate());
+        assert_eq!(0.0, analysis.max_distance_nm());
+        assert_eq!(0.0, analysis.along_track_nm());
+        let analysis = same.diversion_analysis(&index, &none);
+        assert_eq!(f64::INFINITY, analysis.max_distance_nm());
+    }
+}
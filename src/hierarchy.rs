@@ -0,0 +1,601 @@
+//! Contains a hierarchy of continents, countries, regions and airports, with statistics about
+//! the airports at each level.
+//!
+//! Countries are placed under the continent of their [`Country`] record, regions under the
+//! country of their [`Region`] record, and airports under their
+//! [`iso_region`](Airport::iso_region()). Countries and regions without a record in the data,
+//! but with regions or airports, are included with their code only, under the continent of their
+//! first region or airport.
+//!
+//! The statistics of a continent cover the airports whose own [`continent`](Airport::continent())
+//! it is, which can differ from the continent of their country: e.g. airports in Siberia count
+//! towards Asia, although Russia is placed under Europe.
+//!
+//! # Examples
+//! ```no_run
+//! use ourairports::airports::AirportType;
+//! use ourairports::dataset::Dataset;
+//! use ourairports::hierarchy::Hierarchy;
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!     let dataset = Dataset::fetch()?;
+//!     let hierarchy = Hierarchy::from_dataset(&dataset);
+//!
+//!     for region in hierarchy.country("GB").unwrap().regions() {
+//!         let stats = region.stats();
+//!         println!(
+//!             "{}: {} airports, {} large, {} with scheduled service",
+//!             region.code(),
+//!             stats.airport_count(),
+//!             stats.count(&AirportType::LargeAirport),
+//!             stats.scheduled_service_count(),
+//!         );
+//!     }
+//! #    Ok(())
+//! # }
+//! ```
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
+
+use crate::airports::{Airport, AirportType};
+use crate::countries::Country;
+use crate::dataset::Dataset;
+use crate::geo::{BoundingBox, Coordinate};
+use crate::regions::Region;
+use crate::runways::Runway;
+use crate::units::Length;
+use crate::{Continent, Id};
+
+/// Statistics about a group of airports and their runways.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Default)]
+pub struct AirportStats {
+    airport_count: usize,
+    counts_by_type: BTreeMap<AirportType, usize>,
+    scheduled_service_count: usize,
+    runway_count: usize,
+    open_runway_count: usize,
+    paved_runway_count: usize,
+    lighted_runway_count: usize,
+    total_runway_length_ft: i64,
+    bounding_box: Option<BoundingBox>,
+    centroid: Option<Coordinate>,
+}
+
+impl AirportStats {
+    /// Statistics about `airports`, with their runways from `runways` (runways are looked up by
+    /// airport ID).
+    fn collect<'a>(
+        airports: impl IntoIterator<Item = &'a Airport>,
+        runways: &HashMap<Id, Vec<&Runway>>,
+    ) -> Self {
+        let mut stats = AirportStats::default();
+        let mut coordinates = vec![];
+        for airport in airports {
+            stats.airport_count += 1;
+            *stats
+                .counts_by_type
+                .entry(airport.airport_type().clone())
+                .or_default() += 1;
+            if airport.scheduled_service() {
+                stats.scheduled_service_count += 1;
+            }
+            for runway in runways.get(&airport.id()).into_iter().flatten() {
+                stats.runway_count += 1;
+                if !runway.closed() {
+                    stats.open_runway_count += 1;
+                }
+                if runway.paved() {
+                    stats.paved_runway_count += 1;
+                }
+                if runway.lighted() {
+                    stats.lighted_runway_count += 1;
+                }
+                stats.total_runway_length_ft += i64::from(runway.length_ft().unwrap_or(0));
+            }
+            coordinates.push(airport.coordinate());
+        }
+        stats.bounding_box = BoundingBox::from_coordinates(&coordinates);
+        stats.centroid = centroid(&coordinates);
+        stats
+    }
+    /// Number of airports.
+    pub fn airport_count(&self) -> usize {
+        self.airport_count
+    }
+    /// Number of airports of each type. Types without airports are not included.
+    pub fn counts_by_type(&self) -> &BTreeMap<AirportType, usize> {
+        &self.counts_by_type
+    }
+    /// Number of airports of type `airport_type`.
+    pub fn count(&self, airport_type: &AirportType) -> usize {
+        self.counts_by_type
+            .get(airport_type)
+            .copied()
+            .unwrap_or_default()
+    }
+    /// Number of airports which currently have scheduled airline service.
+    pub fn scheduled_service_count(&self) -> usize {
+        self.scheduled_service_count
+    }
+    /// Number of runways, including closed runways.
+    pub fn runway_count(&self) -> usize {
+        self.runway_count
+    }
+    /// Number of runways which are not [closed](Runway::closed()).
+    pub fn open_runway_count(&self) -> usize {
+        self.open_runway_count
+    }
+    /// Number of [paved](Runway::paved()) runways.
+    pub fn paved_runway_count(&self) -> usize {
+        self.paved_runway_count
+    }
+    /// Number of [lighted](Runway::lighted()) runways.
+    pub fn lighted_runway_count(&self) -> usize {
+        self.lighted_runway_count
+    }
+    /// Total length of all runways with a known length, in feet.
+    pub fn total_runway_length_ft(&self) -> i64 {
+        self.total_runway_length_ft
+    }
+    /// [`Self::total_runway_length_ft()`] as a [`Length`].
+    pub fn total_runway_length(&self) -> Length {
+        Length::feet(self.total_runway_length_ft as f64)
+    }
+    /// The smallest bounding box containing all airports (not attempting to cross the
+    /// antimeridian). Returns `None` if there are no airports.
+    pub fn bounding_box(&self) -> Option<BoundingBox> {
+        self.bounding_box
+    }
+    /// The geographic centre of the airports, i.e. the mean of their positions on the sphere.
+    /// Returns `None` if there are no airports, or the airports are spread evenly around the
+    /// globe.
+    pub fn centroid(&self) -> Option<Coordinate> {
+        self.centroid
+    }
+}
+
+/// A continent with its countries.
+#[derive(Debug, Clone)]
+pub struct ContinentNode<'a> {
+    continent: Continent,
+    countries: BTreeMap<String, CountryNode<'a>>,
+    stats: AirportStats,
+}
+
+impl<'a> ContinentNode<'a> {
+    /// The continent.
+    pub fn continent(&self) -> &Continent {
+        &self.continent
+    }
+    /// The countries of the continent, sorted by code.
+    pub fn countries(&self) -> impl Iterator<Item = &CountryNode<'a>> {
+        self.countries.values()
+    }
+    /// The country with the code `code` (e.g. "GB"), if it is on this continent.
+    pub fn country(&self, code: &str) -> Option<&CountryNode<'a>> {
+        self.countries.get(code)
+    }
+    /// Statistics about all airports on the continent, as given by [`Airport::continent()`].
+    ///
+    /// These may include airports of countries placed under another continent, and exclude
+    /// airports of the countries of this continent which lie on another continent.
+    pub fn stats(&self) -> &AirportStats {
+        &self.stats
+    }
+}
+
+/// A country with its regions.
+#[derive(Debug, Clone)]
+pub struct CountryNode<'a> {
+    code: String,
+    country: Option<&'a Country>,
+    regions: BTreeMap<String, RegionNode<'a>>,
+    stats: AirportStats,
+}
+
+impl<'a> CountryNode<'a> {
+    /// The code of the country, see [`Country::code()`].
+    pub fn code(&self) -> &str {
+        &self.code
+    }
+    /// The country record. Returns `None` if the country is not in the data.
+    pub fn country(&self) -> Option<&'a Country> {
+        self.country
+    }
+    /// The regions of the country, sorted by code.
+    pub fn regions(&self) -> impl Iterator<Item = &RegionNode<'a>> {
+        self.regions.values()
+    }
+    /// The region with the code `code` (e.g. "GB-ENG"), if it is in this country.
+    pub fn region(&self, code: &str) -> Option<&RegionNode<'a>> {
+        self.regions.get(code)
+    }
+    /// Statistics about all airports of the country.
+    pub fn stats(&self) -> &AirportStats {
+        &self.stats
+    }
+}
+
+/// A region with its airports.
+#[derive(Debug, Clone)]
+pub struct RegionNode<'a> {
+    code: String,
+    region: Option<&'a Region>,
+    airports: Vec<&'a Airport>,
+    stats: AirportStats,
+}
+
+impl<'a> RegionNode<'a> {
+    /// The code of the region, see [`Region::code()`].
+    pub fn code(&self) -> &str {
+        &self.code
+    }
+    /// The region record. Returns `None` if the region is not in the data.
+    pub fn region(&self) -> Option<&'a Region> {
+        self.region
+    }
+    /// The airports of the region, sorted by ID.
+    pub fn airports(&self) -> &[&'a Airport] {
+        &self.airports
+    }
+    /// Statistics about the airports of the region.
+    pub fn stats(&self) -> &AirportStats {
+        &self.stats
+    }
+}
+
+/// The hierarchy of continents, countries, regions and airports.
+#[derive(Debug, Clone)]
+pub struct Hierarchy<'a> {
+    continents: BTreeMap<Continent, ContinentNode<'a>>,
+    stats: AirportStats,
+}
+
+impl<'a> Hierarchy<'a> {
+    /// Builds the hierarchy of `airports`, `countries` and `regions`, with runway statistics
+    /// from `runways`.
+    pub fn new(
+        airports: &'a BTreeMap<Id, Airport>,
+        countries: &'a BTreeMap<Id, Country>,
+        regions: &'a BTreeMap<Id, Region>,
+        runways: &BTreeMap<Id, Runway>,
+    ) -> Self {
+        let mut runways_by_airport: HashMap<Id, Vec<&Runway>> = HashMap::new();
+        for runway in runways.values() {
+            runways_by_airport
+                .entry(runway.airport_ref())
+                .or_default()
+                .push(runway);
+        }
+
+        // country code -> (continent, record), region code -> (country code, record)
+        let mut country_info: BTreeMap<&str, (Continent, Option<&Country>)> = countries
+            .values()
+            .map(|c| (c.code(), (c.continent().clone(), Some(c))))
+            .collect();
+        let mut region_info: BTreeMap<&str, (&str, Option<&Region>)> = BTreeMap::new();
+        for region in regions.values() {
+            country_info
+                .entry(region.iso_country())
+                .or_insert_with(|| (region.continent().clone(), None));
+            region_info.insert(region.code(), (region.iso_country(), Some(region)));
+        }
+        let mut airports_by_region: BTreeMap<&str, Vec<&Airport>> = BTreeMap::new();
+        for airport in airports.values() {
+            country_info
+                .entry(airport.iso_country())
+                .or_insert_with(|| (airport.continent().clone(), None));
+            region_info
+                .entry(airport.iso_region())
+                .or_insert_with(|| (airport.iso_country(), None));
+            airports_by_region
+                .entry(airport.iso_region())
+                .or_default()
+                .push(airport);
+        }
+
+        let mut country_regions: BTreeMap<&str, BTreeMap<String, RegionNode>> = BTreeMap::new();
+        for (code, (country_code, region)) in region_info {
+            let airports = airports_by_region.remove(code).unwrap_or_default();
+            let stats = AirportStats::collect(airports.iter().copied(), &runways_by_airport);
+            country_regions.entry(country_code).or_default().insert(
+                code.to_string(),
+                RegionNode {
+                    code: code.to_string(),
+                    region,
+                    airports,
+                    stats,
+                },
+            );
+        }
+
+        let mut continents: BTreeMap<Continent, ContinentNode> = BTreeMap::new();
+        for (code, (continent, country)) in country_info {
+            let regions = country_regions.remove(code).unwrap_or_default();
+            let stats = AirportStats::collect(
+                regions.values().flat_map(|r| r.airports.iter().copied()),
+                &runways_by_airport,
+            );
+            continents
+                .entry(continent.clone())
+                .or_insert_with(|| ContinentNode {
+                    continent,
+                    countries: BTreeMap::new(),
+                    stats: AirportStats::default(),
+                })
+                .countries
+                .insert(
+                    code.to_string(),
+                    CountryNode {
+                        code: code.to_string(),
+                        country,
+                        regions,
+                        stats,
+                    },
+                );
+        }
+        let mut airports_by_continent: BTreeMap<&Continent, Vec<&Airport>> = BTreeMap::new();
+        for airport in airports.values() {
+            airports_by_continent
+                .entry(airport.continent())
+                .or_default()
+                .push(airport);
+        }
+        for (continent, airports) in airports_by_continent {
+            continents
+                .entry(continent.clone())
+                .or_insert_with(|| ContinentNode {
+                    continent: continent.clone(),
+                    countries: BTreeMap::new(),
+                    stats: AirportStats::default(),
+                })
+                .stats = AirportStats::collect(airports, &runways_by_airport);
+        }
+        Hierarchy {
+            stats: AirportStats::collect(airports.values(), &runways_by_airport),
+            continents,
+        }
+    }
+    /// Builds the hierarchy of the airports, countries and regions of `dataset`, with runway
+    /// statistics from its runways.
+    pub fn from_dataset(dataset: &'a Dataset) -> Self {
+        Self::new(
+            dataset.airports(),
+            dataset.countries(),
+            dataset.regions(),
+            dataset.runways(),
+        )
+    }
+    /// The continents, in the order of [`Continent`].
+    pub fn continents(&self) -> impl Iterator<Item = &ContinentNode<'a>> {
+        self.continents.values()
+    }
+    /// The continent `continent`. Returns `None` if it has no countries and no airports.
+    pub fn continent(&self, continent: &Continent) -> Option<&ContinentNode<'a>> {
+        self.continents.get(continent)
+    }
+    /// The country with the code `code` (e.g. "GB").
+    pub fn country(&self, code: &str) -> Option<&CountryNode<'a>> {
+        self.continents.values().find_map(|c| c.country(code))
+    }
+    /// The region with the code `code` (e.g. "GB-ENG").
+    pub fn region(&self, code: &str) -> Option<&RegionNode<'a>> {
+        self.continents
+            .values()
+            .flat_map(|c| c.countries())
+            .find_map(|c| c.region(code))
+    }
+    /// Statistics about all airports.
+    pub fn stats(&self) -> &AirportStats {
+        &self.stats
+    }
+}
+
+/// The mean of `coordinates` on the sphere, or `None` if there are none or the mean is
+/// undefined.
+fn centroid(coordinates: &[Coordinate]) -> Option<Coordinate> {
+    let (mut x, mut y, mut z) = (0.0, 0.0, 0.0);
+    for c in coordinates {
+        let (lat, lon) = (
+            c.latitude_deg().to_radians(),
+            c.longitude_deg().to_radians(),
+        );
+        x += lat.cos() * lon.cos();
+        y += lat.cos() * lon.sin();
+        z += lat.sin();
+    }
+    let horizontal = x.hypot(y);
+    if coordinates.is_empty() || horizontal.hypot(z) < 1e-9 * coordinates.len() as f64 {
+        return None;
+    }
+    Some(Coordinate::new(
+        z.atan2(horizontal).to_degrees(),
+        y.atan2(x).to_degrees(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{self, airport, country, region, runway};
+
+    struct Data {
+        airports: BTreeMap<Id, Airport>,
+        countries: BTreeMap<Id, Country>,
+        regions: BTreeMap<Id, Region>,
+        runways: BTreeMap<Id, Runway>,
+    }
+
+    impl Data {
+        fn new() -> Self {
+            let airport =
+                |id, ident, airport_type, latitude, longitude, continent, region: &str| {
+                    airport(id, ident)
+                        .set("type", airport_type)
+                        .position(latitude, longitude)
+                        .set("continent", continent)
+                        .set("iso_country", &region[..2])
+                        .set("iso_region", region)
+                        .set("scheduled_service", "yes")
+                };
+            let runway = |id, airport_ref, length_ft, surface, lighted| {
+                runway(id, airport_ref)
+                    .set("length_ft", length_ft)
+                    .set("surface", surface)
+                    .set("lighted", u8::from(lighted))
+            };
+            Data {
+                airports: test_support::airports([
+                    airport(1, "UUEE", "large_airport", 55.97, 37.41, "EU", "RU-MOW"),
+                    airport(2, "UIII", "medium_airport", 52.27, 104.39, "AS", "RU-IRK"),
+                    airport(3, "EGLL", "large_airport", 51.4, -0.46, "EU", "GB-ENG"),
+                    airport(4, "EGLW", "heliport", 51.5, -0.18, "EU", "GB-ENG")
+                        .set("scheduled_service", "no"),
+                    airport(5, "RJTT", "large_airport", 35.55, 139.78, "AS", "JP-13"),
+                    airport(6, "XX-0001", "small_airport", -10.0, 170.0, "OC", "XX-U-A")
+                        .set("scheduled_service", "no"),
+                ]),
+                countries: test_support::countries([
+                    country(1, "RU", "EU"),
+                    country(2, "GB", "EU"),
+                    country(3, "JP", "AS"),
+                ]),
+                regions: test_support::regions([
+                    region(11, "RU-MOW", "EU"),
+                    region(12, "RU-IRK", "AS"),
+                    region(21, "GB-ENG", "EU"),
+                    region(31, "JP-13", "AS"),
+                    region(32, "JP-01", "AS"),
+                ]),
+                runways: test_support::runways([
+                    runway(101, 1, 12139, "CON", true),
+                    runway(102, 1, 3000, "TURF", false).set("closed", 1),
+                    runway(301, 3, 12799, "ASP", true),
+                    runway(302, 3, 12001, "ASP", true),
+                    runway(401, 4, 100, "CON", false),
+                ]),
+            }
+        }
+
+        fn hierarchy(&self) -> Hierarchy<'_> {
+            Hierarchy::new(
+                &self.airports,
+                &self.countries,
+                &self.regions,
+                &self.runways,
+            )
+        }
+    }
+
+    #[test]
+    fn continent_stats_use_airport_continent() {
+        let data = Data::new();
+        let hierarchy = data.hierarchy();
+        let europe = hierarchy.continent(&Continent::Europe).unwrap();
+        let asia = hierarchy.continent(&Continent::Asia).unwrap();
+        // Russia is placed under Europe, but Irkutsk counts towards Asia
+        assert_eq!(
+            vec!["GB", "RU"],
+            europe.countries().map(|c| c.code()).collect::<Vec<_>>()
+        );
+        assert_eq!(2, europe.country("RU").unwrap().stats().airport_count());
+        assert_eq!(3, europe.stats().airport_count());
+        assert_eq!(2, asia.stats().airport_count());
+        assert_eq!(1, asia.stats().count(&AirportType::MediumAirport));
+        // countries without a record are placed under the continent of their first airport
+        let oceania = hierarchy.continent(&Continent::Oceania).unwrap();
+        assert!(oceania.country("XX").unwrap().country().is_none());
+        assert_eq!(1, oceania.stats().airport_count());
+        assert!(hierarchy.continent(&Continent::Africa).is_none());
+    }
+
+    #[test]
+    fn region_stats() {
+        let data = Data::new();
+        let hierarchy = data.hierarchy();
+        let stats = hierarchy.region("GB-ENG").unwrap().stats();
+        assert_eq!(2, stats.airport_count());
+        assert_eq!(1, stats.count(&AirportType::LargeAirport));
+        assert_eq!(1, stats.count(&AirportType::Heliport));
+        assert_eq!(0, stats.count(&AirportType::SmallAirport));
+        assert_eq!(2, stats.counts_by_type().len());
+        assert_eq!(1, stats.scheduled_service_count());
+        assert_eq!(3, stats.runway_count());
+        assert_eq!(3, stats.open_runway_count());
+        assert_eq!(3, stats.paved_runway_count());
+        assert_eq!(2, stats.lighted_runway_count());
+        assert_eq!(24900, stats.total_runway_length_ft());
+        assert_eq!(24900.0, stats.total_runway_length().to_feet());
+    }
+
+    #[test]
+    fn country_stats_count_closed_runways() {
+        let data = Data::new();
+        let hierarchy = data.hierarchy();
+        let stats = hierarchy.country("RU").unwrap().stats();
+        assert_eq!(2, stats.airport_count());
+        assert_eq!(2, stats.scheduled_service_count());
+        assert_eq!(2, stats.runway_count());
+        assert_eq!(1, stats.open_runway_count());
+        assert_eq!(1, stats.paved_runway_count());
+        assert_eq!(1, stats.lighted_runway_count());
+        assert_eq!(15139, stats.total_runway_length_ft());
+    }
+
+    #[test]
+    fn bounding_box_and_centroid() {
+        let data = Data::new();
+        let hierarchy = data.hierarchy();
+        let stats = hierarchy.region("GB-ENG").unwrap().stats();
+        let bbox = stats.bounding_box().unwrap();
+        assert_eq!(
+            (51.4, -0.46, 51.5, -0.18),
+            (
+                bbox.south_deg(),
+                bbox.west_deg(),
+                bbox.north_deg(),
+                bbox.east_deg()
+            )
+        );
+        let centroid = stats.centroid().unwrap();
+        assert!((centroid.latitude_deg() - 51.45).abs() < 1e-3);
+        assert!((centroid.longitude_deg() - -0.32).abs() < 1e-3);
+
+        let stats = hierarchy.stats();
+        assert_eq!(6, stats.airport_count());
+        let bbox = stats.bounding_box().unwrap();
+        assert_eq!(
+            (-10.0, -0.46, 55.97, 170.0),
+            (
+                bbox.south_deg(),
+                bbox.west_deg(),
+                bbox.north_deg(),
+                bbox.east_deg()
+            )
+        );
+    }
+
+    #[test]
+    fn empty_region_stats() {
+        let data = Data::new();
+        let hierarchy = data.hierarchy();
+        let region = hierarchy.region("JP-01").unwrap();
+        assert!(region.airports().is_empty());
+        assert_eq!(&AirportStats::default(), region.stats());
+        assert!(region.stats().bounding_box().is_none());
+        assert!(region.stats().centroid().is_none());
+    }
+
+    #[test]
+    fn centroid_on_the_sphere() {
+        // across the antimeridian, not at the mean longitude of 0
+        let c = centroid(&[Coordinate::new(0.0, 179.0), Coordinate::new(0.0, -179.0)]).unwrap();
+        assert!(c.latitude_deg().abs() < 1e-9);
+        assert!((c.longitude_deg().abs() - 180.0).abs() < 1e-9);
+        // opposite points have no centre
+        assert!(centroid(&[Coordinate::new(0.0, 0.0), Coordinate::new(0.0, 180.0)]).is_none());
+        assert!(centroid(&[]).is_none());
+    }
+}
@@ -12,7 +12,7 @@
 //!     let heathrow_airport = airports.get(&2434).unwrap();
 //!     assert_eq!(2434, heathrow_airport.id());
 //!     assert_eq!("EGLL", heathrow_airport.ident());
-//!     assert_eq!("LHR", heathrow_airport.iata_code());
+//!     assert_eq!(Some("LHR"), heathrow_airport.iata_code_opt());
 //!     assert_eq!(&AirportType::LargeAirport, heathrow_airport.airport_type());
 //!
 //!    Ok(())
@@ -24,18 +24,45 @@
 //! [data dictionary](https://ourairports.com/help/data-dictionary.html) and
 //! [map legend](https://ourairports.com/help/#legend).
 
-
 use log::debug;
 use reqwest::blocking::Client;
 use serde::de::{self, Unexpected};
 use serde::{Deserialize, Deserializer, Serialize};
+use url::Url;
 
 pub mod airport_frequencies;
 pub mod airports;
+#[cfg(feature = "server")]
+pub mod api;
+pub mod capabilities;
+pub mod codes;
 pub mod countries;
+pub mod dataset;
+pub mod dme;
+pub mod enrich;
+pub mod fix;
+pub mod geo;
+pub mod geometry;
+pub mod great_circle;
+pub mod hierarchy;
+pub mod lint;
+pub mod magnetic;
 pub mod navaids;
+pub mod query;
+pub mod reception;
 pub mod regions;
+pub mod routing;
 pub mod runways;
+pub mod search;
+pub mod shared;
+pub mod suitability;
+pub mod sun;
+#[cfg(test)]
+mod test_support;
+#[cfg(feature = "tz")]
+pub mod time_zone;
+pub mod units;
+pub mod wind;
 
 /// Type of all ID fields.
 pub type Id = u64;
@@ -47,6 +74,8 @@ pub enum FetchError {
     NetworkError(#[from] reqwest::Error),
     #[error("Error in deserializing: {0}")]
     DeserializeError(#[from] csv::Error),
+    #[error("IO error: {0}")]
+    IoError(#[from] std::io::Error),
 }
 
 /// List of allowed continent values.
@@ -114,11 +143,40 @@ where
     }
 }
 
+/// (De)serializes an optional string as an empty string when it is `None`, the way it appears in
+/// the source data.
+mod empty_as_none {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub(crate) fn deserialize<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Ok(Option::<String>::deserialize(deserializer)?.filter(|s| !s.is_empty()))
+    }
+
+    pub(crate) fn serialize<S>(value: &Option<String>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(value.as_deref().unwrap_or_default())
+    }
+}
+
+/// Parses a link from the source data as an `http` or `https` URL, assuming `http` if the link has
+/// no scheme (e.g. "www.example.com"). Returns `None` if the link is missing or not a valid URL.
+fn parse_link(link: Option<&str>) -> Option<Url> {
+    let link = link?.trim();
+    let url = match Url::parse(link) {
+        Err(url::ParseError::RelativeUrlWithoutBase) => Url::parse(&format!("http://{}", link)),
+        result => result,
+    }
+    .ok()?;
+    (matches!(url.scheme(), "http" | "https") && url.host().is_some()).then_some(url)
+}
+
 fn web_request_blocking(url: &str) -> Result<String, reqwest::Error> {
     debug!("requesting data from {}", url);
-    let client = Client::builder()
-        .timeout(None)
-        .build()?;
+    let client = Client::builder().timeout(None).build()?;
     client.get(url).send()?.text()
 }
-
@@ -0,0 +1,788 @@
+//! Contains lint rules which find suspicious records in the OurAirports data, such as misplaced
+//! airports or runway ends, inconsistent runway headings and frequencies outside the aviation
+//! bands, for fixing upstream.
+//!
+//! Unlike [`Dataset::validate()`], which checks that references between datasets resolve, the
+//! rules are heuristics: a finding is worth a look, but may be correct data (e.g. an island
+//! airport far from the rest of its region).
+//!
+//! # Examples
+//! ```no_run
+//! use ourairports::dataset::Dataset;
+//! use ourairports::lint::*;
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!     let dataset = Dataset::fetch()?;
+//!     let findings = Linter::new()
+//!         .heading_tolerance_deg(5.0)
+//!         .disable(LintRule::LargeAirportWithoutScheduledService)
+//!         .lint(&dataset);
+//!     for finding in findings.iter().filter(|f| f.severity() >= Severity::Warning) {
+//!         println!("{}", finding);
+//!     }
+//! #    Ok(())
+//! # }
+//! ```
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::airport_frequencies::AirportFrequency;
+use crate::airports::{Airport, AirportType};
+use crate::dataset::{Dataset, DatasetKind};
+use crate::geo::{normalize_bearing, normalize_longitude, Coordinate};
+use crate::navaids::{Navaid, NavaidType};
+use crate::runways::Runway;
+use crate::Id;
+
+/// Default distance in nautical miles by which an airport must lie outside the spread of its
+/// region to be reported by [`LintRule::RegionOutlier`].
+const DEFAULT_REGION_OUTLIER_NM: f64 = 300.0;
+
+/// Multiple of the median distance of the airports of a region from its centre within which
+/// airports are considered part of the region's spread.
+const REGION_SPREAD_FACTOR: f64 = 3.0;
+
+/// Default distance in nautical miles from the airport above which a runway end is reported by
+/// [`LintRule::RunwayEndFarFromAirport`].
+const DEFAULT_RUNWAY_END_DISTANCE_NM: f64 = 5.0;
+
+/// Default deviation in degrees from 180° between the runway end headings above which a runway
+/// is reported by [`LintRule::RunwayHeadingMismatch`].
+const DEFAULT_HEADING_TOLERANCE_DEG: f64 = 10.0;
+
+/// Aviation bands in MHz for airport frequencies: HF, the VHF airband and military UHF.
+const AIRPORT_FREQUENCY_BANDS_MHZ: [(f64, f64); 3] =
+    [(2.85, 23.35), (108.0, 137.0), (225.0, 400.0)];
+
+/// LF/MF band of NDBs in kHz.
+const NDB_BAND_KHZ: (f64, f64) = (190.0, 1750.0);
+
+/// VHF band of VORs and of the VHF frequencies paired with DME channels, in kHz.
+const VOR_BAND_KHZ: (f64, f64) = (108_000.0, 117_950.0);
+
+/// UHF band of DME and TACAN transponders in kHz, used for channels without a paired VHF
+/// frequency.
+const DME_BAND_KHZ: (f64, f64) = (962_000.0, 1_213_000.0);
+
+/// How serious a [`LintFinding`] is.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    /// Unusual, but often correct.
+    Info,
+    /// Likely an error.
+    Warning,
+    /// Almost certainly an error.
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        })
+    }
+}
+
+/// The lint rules checked by a [`Linter`].
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum LintRule {
+    /// An airport is far from the other airports of its region: its distance from the centre of
+    /// the region (the median position of its airports) is more than three times the median
+    /// distance of the region's airports from the centre, plus
+    /// [`Linter::region_outlier_distance_nm()`]. Airports not assigned to a region ("U-A") are
+    /// not checked.
+    RegionOutlier,
+    /// A runway end is far from its airport's reference point, see
+    /// [`Linter::runway_end_distance_nm()`].
+    RunwayEndFarFromAirport,
+    /// The headings of the two ends of a runway are not roughly 180° apart, see
+    /// [`Linter::heading_tolerance_deg()`].
+    RunwayHeadingMismatch,
+    /// An IATA code is used by more than one airport which is not closed.
+    DuplicateIataCode,
+    /// A large airport has no scheduled airline service.
+    LargeAirportWithoutScheduledService,
+    /// An airport or navaid frequency is not a number or is outside the aviation bands for its
+    /// use. Empty frequencies are not checked.
+    FrequencyOutsideBand,
+}
+
+impl LintRule {
+    /// All rules.
+    pub const ALL: [LintRule; 6] = [
+        LintRule::RegionOutlier,
+        LintRule::RunwayEndFarFromAirport,
+        LintRule::RunwayHeadingMismatch,
+        LintRule::DuplicateIataCode,
+        LintRule::LargeAirportWithoutScheduledService,
+        LintRule::FrequencyOutsideBand,
+    ];
+
+    /// The severity of findings of this rule.
+    pub fn severity(&self) -> Severity {
+        match self {
+            LintRule::RegionOutlier => Severity::Warning,
+            LintRule::RunwayEndFarFromAirport => Severity::Warning,
+            LintRule::RunwayHeadingMismatch => Severity::Warning,
+            LintRule::DuplicateIataCode => Severity::Error,
+            LintRule::LargeAirportWithoutScheduledService => Severity::Info,
+            LintRule::FrequencyOutsideBand => Severity::Error,
+        }
+    }
+    /// The name of the rule, e.g. "region_outlier".
+    pub fn name(&self) -> &'static str {
+        match self {
+            LintRule::RegionOutlier => "region_outlier",
+            LintRule::RunwayEndFarFromAirport => "runway_end_far_from_airport",
+            LintRule::RunwayHeadingMismatch => "runway_heading_mismatch",
+            LintRule::DuplicateIataCode => "duplicate_iata_code",
+            LintRule::LargeAirportWithoutScheduledService => {
+                "large_airport_without_scheduled_service"
+            }
+            LintRule::FrequencyOutsideBand => "frequency_outside_band",
+        }
+    }
+}
+
+impl fmt::Display for LintRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// A suspicious record, as returned by [`Linter::lint()`].
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct LintFinding {
+    rule: LintRule,
+    severity: Severity,
+    kind: DatasetKind,
+    id: Id,
+    message: String,
+}
+
+impl LintFinding {
+    fn new(rule: LintRule, kind: DatasetKind, id: Id, message: String) -> Self {
+        LintFinding {
+            rule,
+            severity: rule.severity(),
+            kind,
+            id,
+            message,
+        }
+    }
+    /// The rule which found the record.
+    pub fn rule(&self) -> LintRule {
+        self.rule
+    }
+    /// How serious the finding is.
+    pub fn severity(&self) -> Severity {
+        self.severity
+    }
+    /// The dataset containing the record.
+    pub fn kind(&self) -> DatasetKind {
+        self.kind
+    }
+    /// ID of the record.
+    pub fn id(&self) -> Id {
+        self.id
+    }
+    /// Description of the problem.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for LintFinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} [{}] {} {}: {}",
+            self.severity, self.rule, self.kind, self.id, self.message
+        )
+    }
+}
+
+/// Checks a [`Dataset`] against the [`LintRule`]s.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct Linter {
+    region_outlier_nm: f64,
+    runway_end_distance_nm: f64,
+    heading_tolerance_deg: f64,
+    disabled: BTreeSet<LintRule>,
+}
+
+impl Linter {
+    /// A linter checking all rules with the default thresholds.
+    pub fn new() -> Self {
+        Linter {
+            region_outlier_nm: DEFAULT_REGION_OUTLIER_NM,
+            runway_end_distance_nm: DEFAULT_RUNWAY_END_DISTANCE_NM,
+            heading_tolerance_deg: DEFAULT_HEADING_TOLERANCE_DEG,
+            disabled: BTreeSet::new(),
+        }
+    }
+    /// Reports airports which lie more than `distance_nm` nautical miles outside the spread of
+    /// their region, see [`LintRule::RegionOutlier`]. Defaults to 300.
+    pub fn region_outlier_distance_nm(mut self, distance_nm: f64) -> Self {
+        self.region_outlier_nm = distance_nm;
+        self
+    }
+    /// Reports runway ends which are more than `distance_nm` nautical miles from their airport's
+    /// reference point. Defaults to 5.
+    pub fn runway_end_distance_nm(mut self, distance_nm: f64) -> Self {
+        self.runway_end_distance_nm = distance_nm;
+        self
+    }
+    /// Reports runways whose end headings differ from 180° apart by more than `tolerance_deg`.
+    /// Defaults to 10.
+    pub fn heading_tolerance_deg(mut self, tolerance_deg: f64) -> Self {
+        self.heading_tolerance_deg = tolerance_deg;
+        self
+    }
+    /// Does not check `rule`.
+    pub fn disable(mut self, rule: LintRule) -> Self {
+        self.disabled.insert(rule);
+        self
+    }
+    /// `true` if `rule` is checked.
+    pub fn is_enabled(&self, rule: LintRule) -> bool {
+        !self.disabled.contains(&rule)
+    }
+
+    /// Checks `dataset` against the enabled rules. Findings are sorted by rule, then by dataset
+    /// and record ID. Returns an empty vector if nothing suspicious was found.
+    pub fn lint(&self, dataset: &Dataset) -> Vec<LintFinding> {
+        let mut findings = vec![];
+        for rule in LintRule::ALL {
+            if !self.is_enabled(rule) {
+                continue;
+            }
+            match rule {
+                LintRule::RegionOutlier => self.region_outliers(dataset.airports(), &mut findings),
+                LintRule::RunwayEndFarFromAirport => {
+                    self.runway_ends(dataset.airports(), dataset.runways(), &mut findings)
+                }
+                LintRule::RunwayHeadingMismatch => {
+                    self.runway_headings(dataset.runways(), &mut findings)
+                }
+                LintRule::DuplicateIataCode => {
+                    duplicate_iata_codes(dataset.airports(), &mut findings)
+                }
+                LintRule::LargeAirportWithoutScheduledService => {
+                    large_airports_without_service(dataset.airports(), &mut findings)
+                }
+                LintRule::FrequencyOutsideBand => {
+                    airport_frequency_bands(dataset.airport_frequencies(), &mut findings);
+                    navaid_frequency_bands(dataset.navaids(), &mut findings);
+                }
+            }
+        }
+        findings.sort_by_key(|f| (f.rule, f.kind, f.id));
+        findings
+    }
+
+    fn region_outliers(&self, airports: &BTreeMap<Id, Airport>, findings: &mut Vec<LintFinding>) {
+        let mut by_region: BTreeMap<&str, Vec<(Id, Coordinate)>> = BTreeMap::new();
+        for airport in airports.values() {
+            if airport.region_code().is_some_and(|r| r.is_unassigned()) {
+                continue;
+            }
+            by_region
+                .entry(airport.iso_region())
+                .or_default()
+                .push((airport.id(), airport.coordinate()));
+        }
+        for (region, members) in by_region.into_iter().filter(|(_, m)| m.len() > 1) {
+            let centre = median_position(members.iter().map(|(_, c)| c));
+            let distances = members
+                .iter()
+                .map(|(_, c)| centre.distance_nm(c))
+                .collect::<Vec<_>>();
+            let max_distance_nm =
+                REGION_SPREAD_FACTOR * median(distances.clone()) + self.region_outlier_nm;
+            for ((id, _), distance_nm) in members.iter().zip(distances) {
+                if distance_nm > max_distance_nm {
+                    findings.push(LintFinding::new(
+                        LintRule::RegionOutlier,
+                        DatasetKind::Airports,
+                        *id,
+                        format!(
+                            "{:.0} NM from the centre of region \"{}\"",
+                            distance_nm, region
+                        ),
+                    ));
+                }
+            }
+        }
+    }
+
+    fn runway_ends(
+        &self,
+        airports: &BTreeMap<Id, Airport>,
+        runways: &BTreeMap<Id, Runway>,
+        findings: &mut Vec<LintFinding>,
+    ) {
+        for runway in runways.values() {
+            let airport = match airports.get(&runway.airport_ref()) {
+                Some(airport) => airport,
+                None => continue,
+            };
+            for end in [runway.low_end(), runway.high_end()] {
+                let position = match (end.latitude_deg(), end.longitude_deg()) {
+                    (Some(latitude), Some(longitude)) => Coordinate::new(latitude, longitude),
+                    _ => continue,
+                };
+                let distance_nm = airport.coordinate().distance_nm(&position);
+                if distance_nm > self.runway_end_distance_nm {
+                    findings.push(LintFinding::new(
+                        LintRule::RunwayEndFarFromAirport,
+                        DatasetKind::Runways,
+                        runway.id(),
+                        format!(
+                            "end \"{}\" is {:.1} NM from airport \"{}\"",
+                            end.ident(),
+                            distance_nm,
+                            airport.ident()
+                        ),
+                    ));
+                }
+            }
+        }
+    }
+
+    fn runway_headings(&self, runways: &BTreeMap<Id, Runway>, findings: &mut Vec<LintFinding>) {
+        for runway in runways.values() {
+            let (low, high) = match (runway.le_heading_deg_true(), runway.he_heading_deg_true()) {
+                (Some(low), Some(high)) => (low, high),
+                _ => continue,
+            };
+            let difference = normalize_bearing(high - low);
+            if (difference - 180.0).abs() > self.heading_tolerance_deg {
+                findings.push(LintFinding::new(
+                    LintRule::RunwayHeadingMismatch,
+                    DatasetKind::Runways,
+                    runway.id(),
+                    format!(
+                        "headings {:.0}° and {:.0}° are {:.0}° apart",
+                        low, high, difference
+                    ),
+                ));
+            }
+        }
+    }
+}
+
+impl Default for Linter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn duplicate_iata_codes(airports: &BTreeMap<Id, Airport>, findings: &mut Vec<LintFinding>) {
+    let mut by_code: BTreeMap<String, Vec<&Airport>> = BTreeMap::new();
+    for airport in airports.values() {
+        if airport.airport_type() == &AirportType::ClosedAirport {
+            continue;
+        }
+        if let Some(code) = airport.iata_code_opt() {
+            by_code
+                .entry(code.trim().to_uppercase())
+                .or_default()
+                .push(airport);
+        }
+    }
+    for (code, airports) in by_code.into_iter().filter(|(_, a)| a.len() > 1) {
+        for airport in &airports {
+            let others = airports
+                .iter()
+                .filter(|other| other.id() != airport.id())
+                .map(|other| format!("\"{}\"", other.ident()))
+                .collect::<Vec<_>>();
+            findings.push(LintFinding::new(
+                LintRule::DuplicateIataCode,
+                DatasetKind::Airports,
+                airport.id(),
+                format!(
+                    "IATA code \"{}\" is also used by {}",
+                    code,
+                    others.join(", ")
+                ),
+            ));
+        }
+    }
+}
+
+fn large_airports_without_service(
+    airports: &BTreeMap<Id, Airport>,
+    findings: &mut Vec<LintFinding>,
+) {
+    for airport in airports.values() {
+        if airport.airport_type() == &AirportType::LargeAirport && !airport.scheduled_service() {
+            findings.push(LintFinding::new(
+                LintRule::LargeAirportWithoutScheduledService,
+                DatasetKind::Airports,
+                airport.id(),
+                "large airport without scheduled service".to_string(),
+            ));
+        }
+    }
+}
+
+fn airport_frequency_bands(
+    frequencies: &BTreeMap<Id, AirportFrequency>,
+    findings: &mut Vec<LintFinding>,
+) {
+    for frequency in frequencies.values() {
+        let text = frequency.frequency_mhz().trim();
+        if text.is_empty() {
+            continue;
+        }
+        let in_band = text.parse::<f64>().is_ok_and(|mhz| {
+            AIRPORT_FREQUENCY_BANDS_MHZ
+                .iter()
+                .any(|(low, high)| (*low..=*high).contains(&mhz))
+        });
+        if !in_band {
+            findings.push(LintFinding::new(
+                LintRule::FrequencyOutsideBand,
+                DatasetKind::AirportFrequencies,
+                frequency.id(),
+                format!("frequency \"{}\" MHz is not in an aviation band", text),
+            ));
+        }
+    }
+}
+
+fn navaid_frequency_bands(navaids: &BTreeMap<Id, Navaid>, findings: &mut Vec<LintFinding>) {
+    for navaid in navaids.values() {
+        let text = navaid.frequency_khz().trim();
+        if text.is_empty() {
+            continue;
+        }
+        let bands: &[(f64, f64)] = match navaid.navaid_type() {
+            NavaidType::Ndb | NavaidType::NdbDme => &[NDB_BAND_KHZ],
+            NavaidType::Vor | NavaidType::VorDme | NavaidType::Vortac => &[VOR_BAND_KHZ],
+            NavaidType::Dme | NavaidType::Tacan => &[VOR_BAND_KHZ, DME_BAND_KHZ],
+        };
+        let in_band = text.parse::<f64>().is_ok_and(|khz| {
+            bands
+                .iter()
+                .any(|(low, high)| (*low..=*high).contains(&khz))
+        });
+        if !in_band {
+            findings.push(LintFinding::new(
+                LintRule::FrequencyOutsideBand,
+                DatasetKind::Navaids,
+                navaid.id(),
+                format!(
+                    "frequency \"{}\" kHz is not in a band for this navaid type",
+                    text
+                ),
+            ));
+        }
+    }
+}
+
+/// The median latitude and longitude of `coordinates`. Longitudes are taken relative to the mean
+/// direction of the coordinates, so positions on both sides of the antimeridian are handled.
+fn median_position<'a>(coordinates: impl Iterator<Item = &'a Coordinate> + Clone) -> Coordinate {
+    let (x, y) = coordinates.clone().fold((0.0, 0.0), |(x, y), c| {
+        let (lat, lon) = (
+            c.latitude_deg().to_radians(),
+            c.longitude_deg().to_radians(),
+        );
+        (x + lat.cos() * lon.cos(), y + lat.cos() * lon.sin())
+    });
+    let reference_deg = y.atan2(x).to_degrees();
+    let (latitudes, longitudes) = coordinates
+        .map(|c| {
+            (
+                c.latitude_deg(),
+                normalize_longitude(c.longitude_deg() - reference_deg),
+            )
+        })
+        .unzip();
+    Coordinate::new(median(latitudes), reference_deg + median(longitudes))
+}
+
+/// The median of `values`, which must not be empty.
+fn median(mut values: Vec<f64>) -> f64 {
+    values.sort_by(f64::total_cmp);
+    let middle = values.len() / 2;
+    if values.len().is_multiple_of(2) {
+        (values[middle - 1] + values[middle]) / 2.0
+    } else {
+        values[middle]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{self, airport, airport_frequency, navaid, runway, Row};
+
+    fn dataset(
+        airports: Vec<Row>,
+        runways: Vec<Row>,
+        airport_frequencies: Vec<Row>,
+        navaids: Vec<Row>,
+    ) -> Dataset {
+        Dataset::new(
+            test_support::airports(airports),
+            test_support::airport_frequencies(airport_frequencies),
+            BTreeMap::new(),
+            test_support::navaids(navaids),
+            BTreeMap::new(),
+            test_support::runways(runways),
+        )
+    }
+
+    /// An airport in `region` at the given position.
+    fn located(id: Id, latitude: f64, longitude: f64, region: &str) -> Row {
+        airport(id, &format!("A{}", id))
+            .position(latitude, longitude)
+            .set("iso_country", &region[..2])
+            .set("iso_region", region)
+    }
+
+    /// IDs of the records found by `rule` with `linter`.
+    fn found(linter: Linter, rule: LintRule, dataset: &Dataset) -> Vec<Id> {
+        linter
+            .lint(dataset)
+            .into_iter()
+            .filter(|f| f.rule() == rule)
+            .map(|f| f.id())
+            .collect()
+    }
+
+    #[test]
+    fn region_outliers_include_clustered_bad_records() {
+        let mut airports = (1..=8)
+            .map(|id| located(id, 51.0 + 0.1 * id as f64, -1.0, "GB-ENG"))
+            .collect::<Vec<_>>();
+        // two misplaced airports next to each other
+        airports.push(located(9, 0.0, 0.0, "GB-ENG"));
+        airports.push(located(10, 0.1, 0.0, "GB-ENG"));
+        // not assigned to a region
+        airports.push(located(11, -40.0, 0.0, "GB-U-A"));
+        airports.push(located(12, 51.0, -1.0, "GB-U-A"));
+        let dataset = dataset(airports, vec![], vec![], vec![]);
+        assert_eq!(
+            vec![9, 10],
+            found(Linter::new(), LintRule::RegionOutlier, &dataset)
+        );
+    }
+
+    #[test]
+    fn region_outlier_threshold() {
+        // the centre is at 0°N 0°E, 1° of latitude is about 60.04 NM
+        let airports = vec![
+            located(1, 0.0, 0.0, "XX-01"),
+            located(2, 0.0, 0.0, "XX-01"),
+            located(3, 0.0, 0.0, "XX-01"),
+            located(4, 1.0, 0.0, "XX-01"),
+        ];
+        let dataset = dataset(airports, vec![], vec![], vec![]);
+        let linter = |nm| Linter::new().region_outlier_distance_nm(nm);
+        assert_eq!(
+            vec![4],
+            found(linter(60.0), LintRule::RegionOutlier, &dataset)
+        );
+        assert!(found(linter(60.1), LintRule::RegionOutlier, &dataset).is_empty());
+    }
+
+    #[test]
+    fn region_centre_across_antimeridian() {
+        let airports = vec![
+            located(1, -17.0, 179.5, "FJ-W"),
+            located(2, -17.5, -179.5, "FJ-W"),
+            located(3, -16.5, 179.8, "FJ-W"),
+            located(4, -17.2, -179.9, "FJ-W"),
+        ];
+        let dataset = dataset(airports, vec![], vec![], vec![]);
+        let linter = Linter::new().region_outlier_distance_nm(1.0);
+        assert!(found(linter, LintRule::RegionOutlier, &dataset).is_empty());
+    }
+
+    #[test]
+    fn runway_end_threshold() {
+        let runway = |id, airport_ref, low_latitude, high_latitude| {
+            runway(id, airport_ref)
+                .set("le_latitude_deg", low_latitude)
+                .set("le_longitude_deg", 0.0)
+                .set("he_latitude_deg", high_latitude)
+                .set("he_longitude_deg", 0.0)
+        };
+        // 0.08° and 0.09° of latitude are about 4.8 and 5.4 NM
+        let runways = vec![
+            runway(1, 1, 0.08, 0.0),
+            runway(2, 1, 0.0, -0.09),
+            test_support::runway(3, 1),
+            // runway of an unknown airport
+            runway(4, 2, 10.0, 10.0),
+        ];
+        let dataset = dataset(vec![airport(1, "A1")], runways, vec![], vec![]);
+        let rule = LintRule::RunwayEndFarFromAirport;
+        assert_eq!(vec![2], found(Linter::new(), rule, &dataset));
+        let linter = Linter::new().runway_end_distance_nm(4.0);
+        assert_eq!(vec![1, 2], found(linter, rule, &dataset));
+    }
+
+    #[test]
+    fn runway_headings_wrap_around() {
+        let runway = |id, low, high| {
+            runway(id, 1)
+                .set("le_heading_degT", low)
+                .set("he_heading_degT", high)
+        };
+        let runways = vec![
+            runway(1, 359.0, 179.0),
+            runway(2, 179.0, 359.0),
+            runway(3, 350.0, 165.0),
+            // exactly at the tolerance
+            runway(4, 0.0, 190.0),
+            runway(5, 5.0, 175.0),
+            // just outside the tolerance
+            runway(6, 0.0, 190.5),
+            runway(7, 355.0, 164.0),
+            runway(8, 90.0, 90.0),
+        ];
+        let dataset = dataset(vec![airport(1, "A1")], runways, vec![], vec![]);
+        let rule = LintRule::RunwayHeadingMismatch;
+        assert_eq!(vec![6, 7, 8], found(Linter::new(), rule, &dataset));
+        // runway 3 is exactly at the tolerance
+        let linter = Linter::new().heading_tolerance_deg(5.0);
+        assert_eq!(vec![4, 5, 6, 7, 8], found(linter, rule, &dataset));
+    }
+
+    #[test]
+    fn duplicate_iata_codes_ignore_closed_airports() {
+        let airport = |id, airport_type, iata_code| {
+            airport(id, &format!("A{}", id))
+                .set("type", airport_type)
+                .set("iata_code", iata_code)
+        };
+        let airports = vec![
+            airport(1, "large_airport", "LHR"),
+            airport(2, "small_airport", " lhr"),
+            airport(3, "closed", "LHR"),
+            airport(4, "medium_airport", "ABC"),
+            airport(5, "closed", "ABC"),
+            airport(6, "small_airport", ""),
+            airport(7, "small_airport", ""),
+        ];
+        let dataset = dataset(airports, vec![], vec![], vec![]);
+        let findings = Linter::new()
+            .lint(&dataset)
+            .into_iter()
+            .filter(|f| f.rule() == LintRule::DuplicateIataCode)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![1, 2],
+            findings.iter().map(|f| f.id()).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            "IATA code \"LHR\" is also used by \"A2\"",
+            findings[0].message()
+        );
+        assert_eq!(Severity::Error, findings[0].severity());
+    }
+
+    #[test]
+    fn large_airports_without_service() {
+        let airport = |id, airport_type, scheduled_service| {
+            airport(id, &format!("A{}", id))
+                .set("type", airport_type)
+                .set("scheduled_service", scheduled_service)
+        };
+        let airports = vec![
+            airport(1, "large_airport", "yes"),
+            airport(2, "large_airport", "no"),
+            airport(3, "medium_airport", "no"),
+        ];
+        let dataset = dataset(airports, vec![], vec![], vec![]);
+        let rule = LintRule::LargeAirportWithoutScheduledService;
+        assert_eq!(vec![2], found(Linter::new(), rule, &dataset));
+        assert!(found(Linter::new().disable(rule), rule, &dataset).is_empty());
+    }
+
+    #[test]
+    fn airport_frequency_band_edges() {
+        let frequencies = [
+            "2.85", "23.35", "108", "137.000", "225", "400", "2.8", "23.4", "107.9", "137.1",
+            "224.9", "400.1", "abc", "", " ",
+        ]
+        .iter()
+        .zip(1..)
+        .map(|(mhz, id)| airport_frequency(id, 1, "TWR", mhz))
+        .collect();
+        let dataset = dataset(vec![], vec![], frequencies, vec![]);
+        assert_eq!(
+            vec![7, 8, 9, 10, 11, 12, 13],
+            found(Linter::new(), LintRule::FrequencyOutsideBand, &dataset)
+        );
+    }
+
+    #[test]
+    fn navaid_frequency_band_edges() {
+        let navaid = |id, navaid_type, frequency_khz| {
+            navaid(id, &format!("N{}", id), navaid_type).set("frequency_khz", frequency_khz)
+        };
+        let navaids = vec![
+            navaid(1, "NDB", "190"),
+            navaid(2, "NDB-DME", "1750"),
+            navaid(3, "NDB", "189"),
+            navaid(4, "NDB", "1751"),
+            navaid(5, "VOR", "108000"),
+            navaid(6, "VOR-DME", "117950"),
+            navaid(7, "VORTAC", "107950"),
+            navaid(8, "VOR", "117975"),
+            navaid(9, "VOR", "1750"),
+            navaid(10, "DME", "108000"),
+            navaid(11, "TACAN", "962000"),
+            navaid(12, "DME", "1213000"),
+            navaid(13, "DME", "961999"),
+            navaid(14, "TACAN", "1213001"),
+            navaid(15, "DME", "118000"),
+            navaid(16, "VOR", ""),
+        ];
+        let dataset = dataset(vec![], vec![], vec![], navaids);
+        assert_eq!(
+            vec![3, 4, 7, 8, 9, 13, 14, 15],
+            found(Linter::new(), LintRule::FrequencyOutsideBand, &dataset)
+        );
+    }
+
+    #[test]
+    fn findings_are_sorted_by_rule_then_record() {
+        let airport = |id| {
+            airport(id, &format!("A{}", id))
+                .set("type", "large_airport")
+                .set("iata_code", "ABC")
+        };
+        let frequencies = vec![airport_frequency(1, 1, "TWR", "0")];
+        let dataset = dataset(vec![airport(2), airport(1)], vec![], frequencies, vec![]);
+        let findings = Linter::new()
+            .lint(&dataset)
+            .into_iter()
+            .map(|f| (f.rule(), f.id()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                (LintRule::DuplicateIataCode, 1),
+                (LintRule::DuplicateIataCode, 2),
+                (LintRule::LargeAirportWithoutScheduledService, 1),
+                (LintRule::LargeAirportWithoutScheduledService, 2),
+                (LintRule::FrequencyOutsideBand, 1),
+            ],
+            findings
+        );
+    }
+}
@@ -0,0 +1,377 @@
+//! Contains an offline implementation of the [World Magnetic Model](https://www.ncei.noaa.gov/products/world-magnetic-model)
+//! (WMM), for the magnetic variation (declination) at any position and date.
+//!
+//! The WMM2025 coefficients are embedded, so no network access is needed. The model is valid
+//! from 2025 to 2030; dates outside that range are extrapolated with the secular variation and
+//! become less accurate the further they are from it. Outside the polar regions the model is
+//! typically accurate to within half a degree.
+//!
+//! Variations are in degrees, positive east, like [`Navaid::magnetic_variation_deg()`]. A
+//! magnetic heading is the true heading minus the variation.
+//!
+//! [`Navaid::magnetic_variation_deg()`]: crate::navaids::Navaid::magnetic_variation_deg()
+//!
+//! # Examples
+//! ```
+//! use chrono::NaiveDate;
+//! use ourairports::geo::Coordinate;
+//! use ourairports::magnetic::*;
+//!
+//! let date = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+//!
+//! // New York has a westerly variation of about 13 degrees
+//! let variation = magnetic_variation_deg(&Coordinate::new(40.64, -73.78), date);
+//! assert!((-13.5..-12.0).contains(&variation));
+//! assert!((52.0..53.5).contains(&true_to_magnetic_deg(40.0, variation)));
+//!
+//! let field = MagneticModel::WMM2025.field(&Coordinate::new(-33.95, 151.18), 0.0, date);
+//! assert!(field.declination_deg() > 12.0 && field.inclination_deg() < -60.0);
+//! ```
+
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::geo::{normalize_bearing, Coordinate, FEET_PER_NM, KM_PER_NM};
+
+/// Semi-major axis of the WGS 84 ellipsoid in kilometres.
+const WGS84_A_KM: f64 = 6378.137;
+
+/// Flattening of the WGS 84 ellipsoid.
+const WGS84_F: f64 = 1.0 / 298.257_223_563;
+
+/// Geomagnetic reference radius of the WMM in kilometres.
+const REFERENCE_RADIUS_KM: f64 = 6371.2;
+
+/// Highest degree of the spherical harmonic expansion of the WMM.
+const MAX_DEGREE: usize = 12;
+
+/// WMM2025 coefficients: degree n, order m, Gauss coefficients g and h (nT) and their secular
+/// variation (nT per year).
+#[rustfmt::skip]
+const WMM2025_COEFFICIENTS: [(usize, usize, f64, f64, f64, f64); 90] = [
+    (1, 0, -29351.8, 0.0, 12.0, 0.0),
+    (1, 1, -1410.8, 4545.4, 9.7, -21.5),
+    (2, 0, -2556.6, 0.0, -11.6, 0.0),
+    (2, 1, 2951.1, -3133.6, -5.2, -27.7),
+    (2, 2, 1649.3, -815.1, -8.0, -12.1),
+    (3, 0, 1361.0, 0.0, -1.3, 0.0),
+    (3, 1, -2404.1, -56.6, -4.2, 4.0),
+    (3, 2, 1243.8, 237.5, 0.4, -0.3),
+    (3, 3, 453.6, -549.5, -15.6, -4.1),
+    (4, 0, 895.0, 0.0, -1.6, 0.0),
+    (4, 1, 799.5, 278.6, -2.4, -1.1),
+    (4, 2, 55.7, -133.9, -6.0, 4.1),
+    (4, 3, -281.1, 212.0, 5.6, 1.6),
+    (4, 4, 12.1, -375.6, -7.0, -4.4),
+    (5, 0, -233.2, 0.0, 0.6, 0.0),
+    (5, 1, 368.9, 45.4, 1.4, -0.5),
+    (5, 2, 187.2, 220.2, 0.0, 2.2),
+    (5, 3, -138.7, -122.9, 0.6, 0.4),
+    (5, 4, -142.0, 43.0, 2.2, 1.7),
+    (5, 5, 20.9, 106.1, 0.9, 1.9),
+    (6, 0, 64.4, 0.0, -0.2, 0.0),
+    (6, 1, 63.8, -18.4, -0.4, 0.3),
+    (6, 2, 76.9, 16.8, 0.9, -1.6),
+    (6, 3, -115.7, 48.8, 1.2, -0.4),
+    (6, 4, -40.9, -59.8, -0.9, 0.9),
+    (6, 5, 14.9, 10.9, 0.3, 0.7),
+    (6, 6, -60.7, 72.7, 0.9, 0.9),
+    (7, 0, 79.5, 0.0, -0.0, 0.0),
+    (7, 1, -77.0, -48.9, -0.1, 0.6),
+    (7, 2, -8.8, -14.4, -0.1, 0.5),
+    (7, 3, 59.3, -1.0, 0.5, -0.8),
+    (7, 4, 15.8, 23.4, -0.1, 0.0),
+    (7, 5, 2.5, -7.4, -0.8, -1.0),
+    (7, 6, -11.1, -25.1, -0.8, 0.6),
+    (7, 7, 14.2, -2.3, 0.8, -0.2),
+    (8, 0, 23.2, 0.0, -0.1, 0.0),
+    (8, 1, 10.8, 7.1, 0.2, -0.2),
+    (8, 2, -17.5, -12.6, 0.0, 0.5),
+    (8, 3, 2.0, 11.4, 0.5, -0.4),
+    (8, 4, -21.7, -9.7, -0.1, 0.4),
+    (8, 5, 16.9, 12.7, 0.3, -0.5),
+    (8, 6, 15.0, 0.7, 0.2, -0.6),
+    (8, 7, -16.8, -5.2, -0.0, 0.3),
+    (8, 8, 0.9, 3.9, 0.2, 0.2),
+    (9, 0, 4.6, 0.0, -0.0, 0.0),
+    (9, 1, 7.8, -24.8, -0.1, -0.3),
+    (9, 2, 3.0, 12.2, 0.1, 0.3),
+    (9, 3, -0.2, 8.3, 0.3, -0.3),
+    (9, 4, -2.5, -3.3, -0.3, 0.3),
+    (9, 5, -13.1, -5.2, 0.0, 0.2),
+    (9, 6, 2.4, 7.2, 0.3, -0.1),
+    (9, 7, 8.6, -0.6, -0.1, -0.2),
+    (9, 8, -8.7, 0.8, 0.1, 0.4),
+    (9, 9, -12.9, 10.0, -0.1, 0.1),
+    (10, 0, -1.3, 0.0, 0.1, 0.0),
+    (10, 1, -6.4, 3.3, 0.0, 0.0),
+    (10, 2, 0.2, 0.0, 0.1, -0.0),
+    (10, 3, 2.0, 2.4, 0.1, -0.2),
+    (10, 4, -1.0, 5.3, -0.0, 0.1),
+    (10, 5, -0.6, -9.1, -0.3, -0.1),
+    (10, 6, -0.9, 0.4, 0.0, 0.1),
+    (10, 7, 1.5, -4.2, -0.1, 0.0),
+    (10, 8, 0.9, -3.8, -0.1, -0.1),
+    (10, 9, -2.7, 0.9, -0.0, 0.2),
+    (10, 10, -3.9, -9.1, -0.0, -0.0),
+    (11, 0, 2.9, 0.0, 0.0, 0.0),
+    (11, 1, -1.5, 0.0, -0.0, -0.0),
+    (11, 2, -2.5, 2.9, 0.0, 0.1),
+    (11, 3, 2.4, -0.6, 0.0, -0.0),
+    (11, 4, -0.6, 0.2, 0.0, 0.1),
+    (11, 5, -0.1, 0.5, -0.1, -0.0),
+    (11, 6, -0.6, -0.3, 0.0, -0.0),
+    (11, 7, -0.1, -1.2, -0.0, 0.1),
+    (11, 8, 1.1, -1.7, -0.1, -0.0),
+    (11, 9, -1.0, -2.9, -0.1, 0.0),
+    (11, 10, -0.2, -1.8, -0.1, 0.0),
+    (11, 11, 2.6, -2.3, -0.1, 0.0),
+    (12, 0, -2.0, 0.0, 0.0, 0.0),
+    (12, 1, -0.2, -1.3, 0.0, -0.0),
+    (12, 2, 0.3, 0.7, -0.0, 0.0),
+    (12, 3, 1.2, 1.0, -0.0, -0.1),
+    (12, 4, -1.3, -1.4, -0.0, 0.1),
+    (12, 5, 0.6, -0.0, -0.0, -0.0),
+    (12, 6, 0.6, 0.6, 0.1, -0.0),
+    (12, 7, 0.5, -0.1, -0.0, -0.0),
+    (12, 8, -0.1, 0.8, 0.0, 0.0),
+    (12, 9, -0.4, 0.1, 0.0, -0.0),
+    (12, 10, -0.2, -1.0, -0.1, -0.0),
+    (12, 11, -1.3, 0.1, -0.0, 0.0),
+    (12, 12, -0.7, 0.2, -0.1, -0.1),
+];
+
+/// A spherical harmonic model of the main geomagnetic field.
+#[derive(Debug, Clone, Copy)]
+pub struct MagneticModel {
+    name: &'static str,
+    epoch: f64,
+    valid_years: f64,
+    coefficients: &'static [(usize, usize, f64, f64, f64, f64)],
+}
+
+impl MagneticModel {
+    /// The World Magnetic Model 2025, valid from 2025 to 2030.
+    pub const WMM2025: MagneticModel = MagneticModel {
+        name: "WMM2025",
+        epoch: 2025.0,
+        valid_years: 5.0,
+        coefficients: &WMM2025_COEFFICIENTS,
+    };
+
+    /// Name of the model, e.g. "WMM2025".
+    pub fn name(&self) -> &str {
+        self.name
+    }
+    /// Epoch of the model as a decimal year.
+    pub fn epoch(&self) -> f64 {
+        self.epoch
+    }
+    /// `true` if `date` is within the validity period of the model.
+    pub fn is_valid_on(&self, date: NaiveDate) -> bool {
+        let year = decimal_year(date);
+        year >= self.epoch && year < self.epoch + self.valid_years
+    }
+
+    /// Magnetic variation in degrees (positive east) at `coordinate` at sea level on `date`.
+    pub fn declination_deg(&self, coordinate: &Coordinate, date: NaiveDate) -> f64 {
+        self.field(coordinate, 0.0, date).declination_deg()
+    }
+
+    /// The magnetic field at `coordinate`, `altitude_ft` above mean sea level and on `date`.
+    ///
+    /// Mean sea level is taken to be the WGS 84 ellipsoid.
+    pub fn field(
+        &self,
+        coordinate: &Coordinate,
+        altitude_ft: f64,
+        date: NaiveDate,
+    ) -> MagneticField {
+        let years = decimal_year(date) - self.epoch;
+        // the field direction is undefined at the geographic poles
+        let latitude = coordinate
+            .latitude_deg()
+            .clamp(-89.999, 89.999)
+            .to_radians();
+        let longitude = coordinate.longitude_deg().to_radians();
+        let height_km = altitude_ft / FEET_PER_NM * KM_PER_NM;
+
+        // geodetic to geocentric spherical coordinates
+        let e2 = WGS84_F * (2.0 - WGS84_F);
+        let prime_vertical = WGS84_A_KM / (1.0 - e2 * latitude.sin().powi(2)).sqrt();
+        let p = (prime_vertical + height_km) * latitude.cos();
+        let z = (prime_vertical * (1.0 - e2) + height_km) * latitude.sin();
+        let radius = p.hypot(z);
+        let geocentric_latitude = (z / radius).asin();
+
+        let legendre = Legendre::new(geocentric_latitude);
+        let (mut north, mut east, mut down) = (0.0, 0.0, 0.0);
+        for &(n, m, g, h, g_dot, h_dot) in self.coefficients {
+            let g = g + g_dot * years;
+            let h = h + h_dot * years;
+            let ratio = (REFERENCE_RADIUS_KM / radius).powi(n as i32 + 2);
+            let (sin_ml, cos_ml) = (m as f64 * longitude).sin_cos();
+            let (p, dp) = (legendre.p[n][m], legendre.dp[n][m]);
+            north += ratio * (g * cos_ml + h * sin_ml) * dp;
+            east += ratio * m as f64 * (g * sin_ml - h * cos_ml) * p;
+            down -= ratio * (n as f64 + 1.0) * (g * cos_ml + h * sin_ml) * p;
+        }
+        east /= geocentric_latitude.cos();
+
+        // rotate from geocentric to geodetic components
+        let (sin_psi, cos_psi) = (geocentric_latitude - latitude).sin_cos();
+        let north_geodetic = north * cos_psi - down * sin_psi;
+        let down_geodetic = north * sin_psi + down * cos_psi;
+
+        let horizontal = north_geodetic.hypot(east);
+        MagneticField {
+            declination_deg: east.atan2(north_geodetic).to_degrees(),
+            inclination_deg: down_geodetic.atan2(horizontal).to_degrees(),
+            horizontal_intensity_nt: horizontal,
+            total_intensity_nt: horizontal.hypot(down_geodetic),
+        }
+    }
+}
+
+/// The magnetic field at a position, as returned by [`MagneticModel::field()`].
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+pub struct MagneticField {
+    declination_deg: f64,
+    inclination_deg: f64,
+    horizontal_intensity_nt: f64,
+    total_intensity_nt: f64,
+}
+
+impl MagneticField {
+    /// Declination (magnetic variation) in degrees, positive east.
+    pub fn declination_deg(&self) -> f64 {
+        self.declination_deg
+    }
+    /// Inclination (dip) in degrees, positive down.
+    pub fn inclination_deg(&self) -> f64 {
+        self.inclination_deg
+    }
+    /// Horizontal intensity in nanotesla.
+    pub fn horizontal_intensity_nt(&self) -> f64 {
+        self.horizontal_intensity_nt
+    }
+    /// Total intensity in nanotesla.
+    pub fn total_intensity_nt(&self) -> f64 {
+        self.total_intensity_nt
+    }
+}
+
+/// Schmidt semi-normalised associated Legendre functions of the colatitude and their derivatives
+/// with respect to the colatitude.
+struct Legendre {
+    p: [[f64; MAX_DEGREE + 1]; MAX_DEGREE + 1],
+    dp: [[f64; MAX_DEGREE + 1]; MAX_DEGREE + 1],
+}
+
+impl Legendre {
+    fn new(latitude: f64) -> Self {
+        // cosine and sine of the colatitude
+        let (cos_t, sin_t) = latitude.sin_cos();
+        let mut p = [[0.0; MAX_DEGREE + 1]; MAX_DEGREE + 1];
+        let mut dp = [[0.0; MAX_DEGREE + 1]; MAX_DEGREE + 1];
+        p[0][0] = 1.0;
+        for n in 1..=MAX_DEGREE {
+            let nf = n as f64;
+            for m in 0..=n {
+                let mf = m as f64;
+                if m == n {
+                    let k = if n == 1 {
+                        1.0
+                    } else {
+                        (1.0 - 1.0 / (2.0 * nf)).sqrt()
+                    };
+                    p[n][n] = k * sin_t * p[n - 1][n - 1];
+                    dp[n][n] = k * (sin_t * dp[n - 1][n - 1] + cos_t * p[n - 1][n - 1]);
+                } else {
+                    let k = ((nf - 1.0).powi(2) - mf.powi(2)).sqrt();
+                    let (p2, dp2) = if n >= 2 {
+                        (p[n - 2][m], dp[n - 2][m])
+                    } else {
+                        (0.0, 0.0)
+                    };
+                    let norm = (nf.powi(2) - mf.powi(2)).sqrt();
+                    p[n][m] = ((2.0 * nf - 1.0) * cos_t * p[n - 1][m] - k * p2) / norm;
+                    dp[n][m] = ((2.0 * nf - 1.0) * (cos_t * dp[n - 1][m] - sin_t * p[n - 1][m])
+                        - k * dp2)
+                        / norm;
+                }
+            }
+        }
+        Legendre { p, dp }
+    }
+}
+
+/// Magnetic variation in degrees (positive east) at `coordinate` on `date`, using the embedded
+/// [`MagneticModel::WMM2025`].
+pub fn magnetic_variation_deg(coordinate: &Coordinate, date: NaiveDate) -> f64 {
+    MagneticModel::WMM2025.declination_deg(coordinate, date)
+}
+
+/// Converts a true heading or bearing to magnetic, given the magnetic variation in degrees
+/// (positive east). The result is from 0 up to 360.
+pub fn true_to_magnetic_deg(true_deg: f64, variation_deg: f64) -> f64 {
+    normalize_bearing(true_deg - variation_deg)
+}
+
+/// Converts a magnetic heading or bearing to true, given the magnetic variation in degrees
+/// (positive east). The result is from 0 up to 360.
+pub fn magnetic_to_true_deg(magnetic_deg: f64, variation_deg: f64) -> f64 {
+    normalize_bearing(magnetic_deg + variation_deg)
+}
+
+/// `date` as a decimal year, e.g. 2025.5 for the beginning of 2 July 2025.
+pub fn decimal_year(date: NaiveDate) -> f64 {
+    let days_in_year = if date.leap_year() { 366.0 } else { 365.0 };
+    f64::from(date.year()) + f64::from(date.ordinal0()) / days_in_year
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wmm2025_test_values() {
+        // (decimal year, height above the ellipsoid in km, latitude, longitude, declination,
+        // inclination) from the WMM2025 test values published with the model
+        let test_values = [(2025.0, 0.0, 80.0, 0.0, 1.28, 83.21)];
+        for (year, height_km, latitude, longitude, declination, inclination) in test_values {
+            let date = NaiveDate::from_yo_opt(year as i32, 1).unwrap();
+            assert_eq!(year, decimal_year(date));
+            let altitude_ft = height_km / KM_PER_NM * FEET_PER_NM;
+            let field = MagneticModel::WMM2025.field(
+                &Coordinate::new(latitude, longitude),
+                altitude_ft,
+                date,
+            );
+            let point = format!("{} {} km {} {}", year, height_km, latitude, longitude);
+            assert!(
+                (field.declination_deg() - declination).abs() <= 0.01,
+                "{}: declination {}",
+                point,
+                field.declination_deg()
+            );
+            assert!(
+                (field.inclination_deg() - inclination).abs() <= 0.01,
+                "{}: inclination {}",
+                point,
+                field.inclination_deg()
+            );
+        }
+    }
+
+    #[test]
+    fn decimal_years() {
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        assert_eq!(2025.0, decimal_year(date(2025, 1, 1)));
+        assert_eq!(2028.5, decimal_year(date(2028, 7, 2)));
+        assert!(MagneticModel::WMM2025.is_valid_on(date(2025, 1, 1)));
+        assert!(MagneticModel::WMM2025.is_valid_on(date(2029, 12, 31)));
+        assert!(!MagneticModel::WMM2025.is_valid_on(date(2030, 1, 1)));
+        assert!(!MagneticModel::WMM2025.is_valid_on(date(2024, 12, 31)));
+    }
+}
@@ -9,7 +9,7 @@
 //!     let navaids = get_navaids_csv()?;
 //!
 //!     let sample_navaid = navaids.get(&86738).unwrap();
-//!     assert_eq!("NZCH", sample_navaid.associated_airport());
+//!     assert_eq!(Some("NZCH"), sample_navaid.associated_airport_opt());
 //!     assert_eq!(&UsageType::Both, sample_navaid.usage_type().unwrap());
 //!     assert_eq!(&NavaidPower::High, sample_navaid.power().unwrap());
 //!     assert_eq!(&NavaidType::VorDme, sample_navaid.navaid_type());
@@ -17,14 +17,22 @@
 //! # }
 //! ```
 
-use crate::{FetchError, Id, ToJsonString};
+use crate::codes::CountryCode;
+use crate::dme::{check_navaid, DmeChannel, DmeIssue};
+use crate::fix::{radial_dme_fix, radial_dme_to, RadialDme};
+use crate::geo::{geojson_feature, geojson_point, Coordinate, ToGeoJson};
+use crate::reception::{radio_line_of_sight_nm, ServiceVolumeClass};
+use crate::units::Elevation;
+use crate::{empty_as_none, FetchError, Id, ToJsonString};
 use log::debug;
 use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
 use std::collections::BTreeMap;
 use std::hash::{Hash, Hasher};
+use std::io::Read;
 
-const NAVAIDS_CSV_URL: &str = "https://davidmegginson.github.io/ourairports-data/navaids.csv";
+pub(crate) const NAVAIDS_CSV_URL: &str =
+    "https://davidmegginson.github.io/ourairports-data/navaids.csv";
 
 /// Represents a single radio navigation.
 ///
@@ -43,8 +51,10 @@ pub struct Navaid {
     longitude_deg: Option<f64>,
     elevation_ft: Option<i32>,
     iso_country: String,
-    dme_frequency_khz: String,
-    dme_channel: String,
+    #[serde(with = "empty_as_none")]
+    dme_frequency_khz: Option<String>,
+    #[serde(with = "empty_as_none")]
+    dme_channel: Option<String>,
     dme_latitude_deg: Option<f64>,
     dme_longitude_deg: Option<f64>,
     dme_elevation_ft: Option<i32>,
@@ -53,7 +63,8 @@ pub struct Navaid {
     #[serde(rename = "usageType")]
     usage_type: Option<UsageType>,
     power: Option<NavaidPower>,
-    associated_airport: String,
+    #[serde(with = "empty_as_none")]
+    associated_airport: Option<String>,
 }
 
 impl Navaid {
@@ -98,10 +109,18 @@ impl Navaid {
     pub fn longitude_deg(&self) -> Option<f64> {
         self.longitude_deg
     }
+    /// The location of the navaid as a [`Coordinate`]. Returns `None` if not available.
+    pub fn coordinate(&self) -> Option<Coordinate> {
+        Some(Coordinate::new(self.latitude_deg?, self.longitude_deg?))
+    }
     /// The navaid's elevation MSL in feet. Returns `None` if not available.
     pub fn elevation_ft(&self) -> Option<i32> {
         self.elevation_ft
     }
+    /// [`Self::elevation_ft()`] as an [`Elevation`]. Returns `None` if not available.
+    pub fn elevation(&self) -> Option<Elevation> {
+        self.elevation_ft.map(|ft| Elevation::feet(ft.into()))
+    }
     /// The two-character [ISO 3166:1-alpha2 code](https://en.wikipedia.org/wiki/List_of_ISO_3166_country_codes)
     /// for the country that operates the navaid.
     ///
@@ -110,15 +129,38 @@ impl Navaid {
     pub fn iso_country(&self) -> &str {
         &self.iso_country
     }
+    /// Parsed [`Self::iso_country()`]. Returns `None` if it is not a valid country code.
+    pub fn country_code(&self) -> Option<CountryCode> {
+        self.iso_country.parse().ok()
+    }
     /// The paired VHF frequency for the DME (or TACAN) in kilohertz.
     ///
     /// Divide by 1,000 to get the paired VHF frequency in megahertz (e.g. 115.3 MHz).
+    pub fn dme_frequency_khz_opt(&self) -> Option<&str> {
+        self.dme_frequency_khz.as_deref()
+    }
+    /// Same as [`Self::dme_frequency_khz_opt()`], but returns an empty string if not available.
+    #[deprecated(since = "0.2.0", note = "use `dme_frequency_khz_opt()` instead")]
     pub fn dme_frequency_khz(&self) -> &str {
-        &self.dme_frequency_khz
+        self.dme_frequency_khz.as_deref().unwrap_or_default()
     }
     /// The DME channel (an alternative way of tuning distance-measuring equipment).
+    pub fn dme_channel_opt(&self) -> Option<&str> {
+        self.dme_channel.as_deref()
+    }
+    /// Same as [`Self::dme_channel_opt()`], but returns an empty string if not available.
+    #[deprecated(since = "0.2.0", note = "use `dme_channel_opt()` instead")]
     pub fn dme_channel(&self) -> &str {
-        &self.dme_channel
+        self.dme_channel.as_deref().unwrap_or_default()
+    }
+    /// The DME channel parsed as a [`DmeChannel`]. Returns `None` if not available or invalid.
+    pub fn parsed_dme_channel(&self) -> Option<DmeChannel> {
+        self.dme_channel.as_deref()?.parse().ok()
+    }
+    /// Checks that the frequency, DME frequency and DME channel agree, see
+    /// [`dme::check_navaid()`](crate::dme::check_navaid()).
+    pub fn dme_issues(&self) -> Vec<DmeIssue> {
+        check_navaid(self)
     }
     /// The latitude of the associated DME in decimal degrees (negative for south).
     ///
@@ -138,6 +180,10 @@ impl Navaid {
     pub fn dme_elevation_ft(&self) -> Option<i32> {
         self.dme_elevation_ft
     }
+    /// [`Self::dme_elevation_ft()`] as an [`Elevation`]. Returns `None` if not available.
+    pub fn dme_elevation(&self) -> Option<Elevation> {
+        self.dme_elevation_ft.map(|ft| Elevation::feet(ft.into()))
+    }
     /// The magnetic variation adjustment built into a VOR's, VOR-DME's, or TACAN's radials.
     /// Positive means east (added to the true direction), and negative means west (subtracted from the true direction).
     ///
@@ -166,8 +212,64 @@ impl Navaid {
     ///
     /// See [`Airport.ident()`](../airports/struct.Airport.html#method.ident) for more information
     /// about airport identifiers.
+    pub fn associated_airport_opt(&self) -> Option<&str> {
+        self.associated_airport.as_deref()
+    }
+    /// Same as [`Self::associated_airport_opt()`], but returns an empty string if not available.
+    #[deprecated(since = "0.2.0", note = "use `associated_airport_opt()` instead")]
     pub fn associated_airport(&self) -> &str {
-        &self.associated_airport
+        self.associated_airport.as_deref().unwrap_or_default()
+    }
+    /// The position of the associated DME, or of the navaid if the DME position is not
+    /// available. Returns `None` if neither is available.
+    pub fn dme_coordinate(&self) -> Option<Coordinate> {
+        match (self.dme_latitude_deg, self.dme_longitude_deg) {
+            (Some(latitude), Some(longitude)) => Some(Coordinate::new(latitude, longitude)),
+            _ => self.coordinate(),
+        }
+    }
+    /// The variation the navaid's radials are aligned to: the
+    /// [slaved variation](Self::slaved_variation_deg()) if available, the
+    /// [magnetic variation](Self::magnetic_variation_deg()) otherwise.
+    pub fn radial_variation_deg(&self) -> Option<f64> {
+        self.slaved_variation_deg.or(self.magnetic_variation_deg)
+    }
+    /// The position at the given magnetic radial and DME distance from the navaid, see
+    /// [`fix::radial_dme_fix()`](crate::fix::radial_dme_fix()).
+    pub fn radial_dme_fix(&self, radial_dme: &RadialDme) -> Option<Coordinate> {
+        radial_dme_fix(self, radial_dme)
+    }
+    /// The magnetic radial and DME distance of `point` from the navaid, see
+    /// [`fix::radial_dme_to()`](crate::fix::radial_dme_to()).
+    pub fn radial_dme_to(&self, point: &Coordinate) -> Option<RadialDme> {
+        radial_dme_to(self, point)
+    }
+    /// Standard service volume class of the navaid, see [`ServiceVolumeClass::of()`].
+    pub fn service_volume_class(&self) -> ServiceVolumeClass {
+        ServiceVolumeClass::of(self)
+    }
+    /// Expected reception range in nautical miles at `altitude_ft` above mean sea level, or
+    /// `None` if the navaid cannot be received at that altitude.
+    pub fn reception_range_nm(&self, altitude_ft: f64) -> Option<f64> {
+        let class = self.service_volume_class();
+        let height_ft = altitude_ft - f64::from(self.elevation_ft().unwrap_or(0));
+        if height_ft < 0.0 {
+            return None;
+        }
+        let range = class.range_nm(height_ft)?;
+        Some(if class.is_ndb() {
+            range
+        } else {
+            range.min(radio_line_of_sight_nm(height_ft, 0.0))
+        })
+    }
+    /// `true` if the navaid is expected to be receivable at `point` and `altitude_ft` above mean
+    /// sea level. Always `false` if the navaid has no known position.
+    pub fn covers(&self, point: &Coordinate, altitude_ft: f64) -> bool {
+        match (self.coordinate(), self.reception_range_nm(altitude_ft)) {
+            (Some(position), Some(range)) => position.distance_nm(point) <= range,
+            _ => false,
+        }
     }
 }
 
@@ -199,6 +301,15 @@ impl Hash for Navaid {
 
 impl ToJsonString for Navaid {}
 
+impl ToGeoJson for Navaid {
+    fn to_geojson_feature(&self) -> Option<serde_json::Value> {
+        Some(geojson_feature(
+            geojson_point(&self.coordinate()?),
+            serde_json::to_value(self).unwrap_or_default(),
+        ))
+    }
+}
+
 /// Possible types of navaids.
 ///
 /// See [OurAirports map legend](https://ourairports.com/help/#navaids)
@@ -249,13 +360,29 @@ pub enum NavaidPower {
     Unknown,
 }
 
+/// Returns a [`BTreeMap`] of all [`Navaid`] in the latest OurAirports `navaids.csv`
+/// with its ID as the key, sorted according to its keys.
+///
+/// # Errors
+/// Returns [`FetchError`] if the data cannot be fetched or there's something wrong
+/// with the de serialization process.
 pub fn get_navaids_csv() -> Result<BTreeMap<Id, Navaid>, FetchError> {
     // get data
     debug!("getting data");
     let content = crate::web_request_blocking(NAVAIDS_CSV_URL)?;
+    Ok(read_navaids_csv(content.as_bytes())?)
+}
+
+/// Reads [`Navaid`] records in the OurAirports `navaids.csv` format from `reader`, returning a
+/// [`BTreeMap`] with its ID as the key, sorted according to its keys.
+///
+/// # Errors
+/// Returns [`csv::Error`] if the data cannot be read or there's something wrong with the
+/// de serialization process.
+pub fn read_navaids_csv<R: Read>(reader: R) -> Result<BTreeMap<Id, Navaid>, csv::Error> {
     // initialise csv reader & return value
     debug!("initialising CSV reader");
-    let mut rdr = csv::Reader::from_reader(content.as_bytes());
+    let mut rdr = csv::Reader::from_reader(reader);
     debug!("parsing and deserializing data");
     let mut map = BTreeMap::new();
     for result in rdr.deserialize() {
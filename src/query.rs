@@ -0,0 +1,1101 @@
+//! Contains a composable query builder over airports, and the index it runs against.
+//!
+//! # Examples
+//! ```no_run
+//! use ourairports::airports::*;
+//! use ourairports::runways::*;
+//! use ourairports::query::*;
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!     let airports = get_airports_csv()?;
+//!     let runways = get_runways_csv()?;
+//!     let index = AirportIndex::with_runways(&airports, &runways);
+//!
+//!     // The ten highest airports in Switzerland with scheduled service and a paved runway of at
+//!     // least 6,000 ft
+//!     let results = AirportQuery::new()
+//!         .countries(["CH"])
+//!         .scheduled_service(true)
+//!         .min_runway_length_ft(6000)
+//!         .paved_runway(true)
+//!         .sort_by(AirportSort::ElevationFt)
+//!         .descending(true)
+//!         .limit(10)
+//!         .run(&index);
+//!     assert!(results.airports().iter().any(|a| a.ident() == "LSZH"));
+//! #    Ok(())
+//! # }
+//! ```
+
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::ops::{Bound, RangeBounds};
+use std::str::FromStr;
+
+use serde::de::DeserializeOwned;
+
+use crate::airports::{Airport, AirportType};
+use crate::geo::{BoundingBox, Coordinate, SpatialIndex};
+use crate::runways::Runway;
+use crate::{Continent, Id};
+
+/// An index over a set of [`Airport`] (and optionally their [`Runway`]) used to answer
+/// [`AirportQuery`] and lookup requests efficiently.
+///
+/// The index borrows the data it was built from.
+#[derive(Debug, Clone)]
+pub struct AirportIndex<'a> {
+    airports: Vec<&'a Airport>,
+    by_id: HashMap<Id, usize>,
+    by_ident: HashMap<&'a str, usize>,
+    by_country: HashMap<&'a str, Vec<usize>>,
+    by_region: HashMap<&'a str, Vec<usize>>,
+    by_type: HashMap<&'a AirportType, Vec<usize>>,
+    spatial: SpatialIndex<usize>,
+    runways: HashMap<Id, Vec<&'a Runway>>,
+}
+
+impl<'a> AirportIndex<'a> {
+    /// Builds an index over `airports` without runway data. Runway-based query filters will not
+    /// match any airport.
+    pub fn new(airports: &'a BTreeMap<Id, Airport>) -> Self {
+        let airports = airports.values().collect::<Vec<_>>();
+        let mut index = AirportIndex {
+            by_id: HashMap::with_capacity(airports.len()),
+            by_ident: HashMap::with_capacity(airports.len()),
+            by_country: HashMap::new(),
+            by_region: HashMap::new(),
+            by_type: HashMap::new(),
+            spatial: SpatialIndex::new(),
+            runways: HashMap::new(),
+            airports: vec![],
+        };
+        for (i, airport) in airports.iter().enumerate() {
+            index.by_id.insert(airport.id(), i);
+            index.by_ident.insert(airport.ident(), i);
+            index
+                .by_country
+                .entry(airport.iso_country())
+                .or_default()
+                .push(i);
+            index
+                .by_region
+                .entry(airport.iso_region())
+                .or_default()
+                .push(i);
+            index
+                .by_type
+                .entry(airport.airport_type())
+                .or_default()
+                .push(i);
+            index.spatial.insert(airport.coordinate(), i);
+        }
+        index.airports = airports;
+        index
+    }
+
+    /// Builds an index over `airports` and their `runways`.
+    pub fn with_runways(
+        airports: &'a BTreeMap<Id, Airport>,
+        runways: &'a BTreeMap<Id, Runway>,
+    ) -> Self {
+        let mut index = Self::new(airports);
+        for runway in runways.values() {
+            index
+                .runways
+                .entry(runway.airport_ref())
+                .or_default()
+                .push(runway);
+        }
+        index
+    }
+
+    /// Returns the number of airports in the index.
+    pub fn len(&self) -> usize {
+        self.airports.len()
+    }
+
+    /// Returns `true` if the index contains no airports.
+    pub fn is_empty(&self) -> bool {
+        self.airports.is_empty()
+    }
+
+    /// All airports in the index, sorted by ID.
+    pub fn airports(&self) -> &[&'a Airport] {
+        &self.airports
+    }
+
+    /// Returns the airport with the given [`Airport::id()`].
+    pub fn get(&self, id: Id) -> Option<&'a Airport> {
+        self.by_id.get(&id).map(|i| self.airports[*i])
+    }
+
+    /// Returns the airport with the given [`Airport::ident()`].
+    pub fn get_by_ident(&self, ident: &str) -> Option<&'a Airport> {
+        self.by_ident.get(ident).map(|i| self.airports[*i])
+    }
+
+    /// Returns the runways of the airport with the given ID. Empty if the airport has no runways
+    /// or the index was built without runway data.
+    pub fn runways(&self, airport_id: Id) -> &[&'a Runway] {
+        self.runways.get(&airport_id).map_or(&[], Vec::as_slice)
+    }
+
+    /// Returns the `n` airports nearest to `coordinate`, together with their distance in nautical
+    /// miles, sorted by increasing distance.
+    pub fn nearest(&self, coordinate: &Coordinate, n: usize) -> Vec<(&'a Airport, f64)> {
+        self.spatial
+            .nearest(coordinate, n)
+            .into_iter()
+            .map(|(i, distance)| (self.airports[*i], distance))
+            .collect()
+    }
+
+    /// Returns all airports within `radius_nm` nautical miles of `coordinate`, together with their
+    /// distance, sorted by increasing distance.
+    pub fn within_radius(
+        &self,
+        coordinate: &Coordinate,
+        radius_nm: f64,
+    ) -> Vec<(&'a Airport, f64)> {
+        self.spatial
+            .within_radius(coordinate, radius_nm)
+            .into_iter()
+            .map(|(i, distance)| (self.airports[*i], distance))
+            .collect()
+    }
+
+    /// Length of the longest runway of the airport which is not closed, in feet.
+    fn longest_runway_ft(&self, airport_id: Id) -> Option<i32> {
+        self.runways(airport_id)
+            .iter()
+            .filter(|r| !r.closed())
+            .filter_map(|r| r.length_ft())
+            .max()
+    }
+}
+
+/// Sort keys for [`AirportQuery`] results.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum AirportSort {
+    /// By [`Airport::id()`]
+    #[default]
+    Id,
+    /// By [`Airport::ident()`]
+    Ident,
+    /// By [`Airport::name()`]
+    Name,
+    /// By [`Airport::elevation_ft()`]. Airports without an elevation sort first.
+    ElevationFt,
+    /// By the length of the longest runway which is not closed. Airports without runway lengths
+    /// sort first.
+    LongestRunwayFt,
+    /// By great-circle distance from the given coordinate.
+    DistanceFrom(Coordinate),
+}
+
+/// A builder for filtering, sorting and paginating airports.
+///
+/// All filters are optional and combined with a logical AND. Filters which accept a set of values
+/// (e.g. [`airport_types`](Self::airport_types())) match any of the given values.
+#[derive(Debug, Clone, Default)]
+pub struct AirportQuery {
+    airport_types: Option<BTreeSet<AirportType>>,
+    continents: Option<BTreeSet<Continent>>,
+    countries: Option<BTreeSet<String>>,
+    regions: Option<BTreeSet<String>>,
+    scheduled_service: Option<bool>,
+    has_iata_code: Option<bool>,
+    elevation_ft: Option<(Bound<i32>, Bound<i32>)>,
+    bounding_box: Option<BoundingBox>,
+    radius: Option<(Coordinate, f64)>,
+    min_runway_length_ft: Option<i32>,
+    paved_runway: bool,
+    lighted_runway: bool,
+    sort: AirportSort,
+    descending: bool,
+    offset: usize,
+    limit: Option<usize>,
+}
+
+impl AirportQuery {
+    /// Creates a query matching all airports.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only match airports of the given types.
+    pub fn airport_types(mut self, airport_types: impl IntoIterator<Item = AirportType>) -> Self {
+        self.airport_types = Some(airport_types.into_iter().collect());
+        self
+    }
+
+    /// Only match airports on the given continents.
+    pub fn continents(mut self, continents: impl IntoIterator<Item = Continent>) -> Self {
+        self.continents = Some(continents.into_iter().collect());
+        self
+    }
+
+    /// Only match airports in the given countries, as in [`Airport::iso_country()`].
+    pub fn countries<S: Into<String>>(mut self, countries: impl IntoIterator<Item = S>) -> Self {
+        self.countries = Some(countries.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Only match airports in the given regions, as in [`Airport::iso_region()`].
+    pub fn regions<S: Into<String>>(mut self, regions: impl IntoIterator<Item = S>) -> Self {
+        self.regions = Some(regions.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Only match airports whose [`Airport::scheduled_service()`] equals `scheduled_service`.
+    pub fn scheduled_service(mut self, scheduled_service: bool) -> Self {
+        self.scheduled_service = Some(scheduled_service);
+        self
+    }
+
+    /// Only match airports with (`true`) or without (`false`) an IATA code.
+    pub fn has_iata_code(mut self, has_iata_code: bool) -> Self {
+        self.has_iata_code = Some(has_iata_code);
+        self
+    }
+
+    /// Only match airports whose elevation in feet is within `range`. Airports without an
+    /// elevation never match.
+    pub fn elevation_ft(mut self, range: impl RangeBounds<i32>) -> Self {
+        self.elevation_ft = Some((range.start_bound().cloned(), range.end_bound().cloned()));
+        self
+    }
+
+    /// Only match airports located within `bounding_box`.
+    pub fn bounding_box(mut self, bounding_box: BoundingBox) -> Self {
+        self.bounding_box = Some(bounding_box);
+        self
+    }
+
+    /// Only match airports within `radius_nm` nautical miles of `center`.
+    pub fn within_radius(mut self, center: Coordinate, radius_nm: f64) -> Self {
+        self.radius = Some((center, radius_nm));
+        self
+    }
+
+    /// Only match airports with a runway which is not closed and at least `length_ft` feet long.
+    ///
+    /// Combined with [`paved_runway`](Self::paved_runway()) and
+    /// [`lighted_runway`](Self::lighted_runway()), the same runway must satisfy all conditions.
+    pub fn min_runway_length_ft(mut self, length_ft: i32) -> Self {
+        self.min_runway_length_ft = Some(length_ft);
+        self
+    }
+
+    /// If `true`, only match airports with a paved runway which is not closed.
+    ///
+    /// See [`Runway::paved()`] for how paved surfaces are recognised.
+    pub fn paved_runway(mut self, paved_runway: bool) -> Self {
+        self.paved_runway = paved_runway;
+        self
+    }
+
+    /// If `true`, only match airports with a lighted runway which is not closed.
+    pub fn lighted_runway(mut self, lighted_runway: bool) -> Self {
+        self.lighted_runway = lighted_runway;
+        self
+    }
+
+    /// Sorts the results by `sort`. Defaults to [`AirportSort::Id`].
+    pub fn sort_by(mut self, sort: AirportSort) -> Self {
+        self.sort = sort;
+        self
+    }
+
+    /// If `true`, sorts the results in descending order. Airports with the same sort key stay in
+    /// ascending order of their IDs, and airports without an elevation or runway length still sort
+    /// first.
+    pub fn descending(mut self, descending: bool) -> Self {
+        self.descending = descending;
+        self
+    }
+
+    /// Skips the first `offset` results.
+    pub fn offset(mut self, offset: usize) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    /// Returns at most `limit` results.
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Applies a filter, sort or pagination option given as text, e.g. from a command line or a
+    /// URL query string.
+    ///
+    /// | Key | Value |
+    /// |-----|-------|
+    /// | `type`, `continent`, `country`, `region` | comma-separated OurAirports codes, e.g. `large_airport,medium_airport` |
+    /// | `scheduled`, `iata`, `paved`, `lighted` | `true` or `false` |
+    /// | `min_elevation`, `max_elevation`, `min_runway` | feet |
+    /// | `bbox` | `south,west,north,east` |
+    /// | `near` | `lat,lon,radius_nm` |
+    /// | `sort` | `id`, `ident`, `name`, `elevation`, `longest_runway` or `distance` (from `near`) |
+    /// | `order` | `asc` or `desc` |
+    /// | `limit`, `offset` | number of results |
+    ///
+    /// `sort=distance` requires a previous `near`. Use [`params()`](Self::params()) to apply
+    /// several options at once, in which case `near` may also come after `sort=distance`.
+    ///
+    /// # Errors
+    /// Returns [`QueryParamError`] if the key is unknown, the value is invalid, or `sort=distance`
+    /// is given without `near`.
+    pub fn param(mut self, key: &str, value: &str) -> Result<Self, QueryParamError> {
+        let invalid = || QueryParamError::InvalidValue(key.to_string(), value.to_string());
+        let list = || value.split(',').map(str::trim);
+        let numbers = |n: usize| {
+            let numbers = list()
+                .map(|v| parse_param_value::<f64>(key, v))
+                .collect::<Result<Vec<_>, _>>()?;
+            if numbers.len() == n {
+                Ok(numbers)
+            } else {
+                Err(invalid())
+            }
+        };
+        self = match key {
+            "type" => self.airport_types(parse_param_codes(key, value)?),
+            "continent" => self.continents(parse_param_codes(key, value)?),
+            "country" => self.countries(list().map(str::to_uppercase)),
+            "region" => self.regions(list().map(str::to_uppercase)),
+            "scheduled" => self.scheduled_service(parse_param_value(key, value)?),
+            "iata" => self.has_iata_code(parse_param_value(key, value)?),
+            "paved" => self.paved_runway(parse_param_value(key, value)?),
+            "lighted" => self.lighted_runway(parse_param_value(key, value)?),
+            "min_runway" => self.min_runway_length_ft(parse_param_value(key, value)?),
+            "min_elevation" | "max_elevation" => {
+                let (mut min, mut max) = self
+                    .elevation_ft
+                    .unwrap_or((Bound::Unbounded, Bound::Unbounded));
+                let elevation = Bound::Included(parse_param_value(key, value)?);
+                if key == "min_elevation" {
+                    min = elevation;
+                } else {
+                    max = elevation;
+                }
+                self.elevation_ft = Some((min, max));
+                self
+            }
+            "bbox" => {
+                let n = numbers(4)?;
+                self.bounding_box(BoundingBox::new(n[0], n[1], n[2], n[3]))
+            }
+            "near" => {
+                let n = numbers(3)?;
+                self.within_radius(Coordinate::new(n[0], n[1]), n[2])
+            }
+            "sort" => {
+                let sort = match value.trim() {
+                    "id" => AirportSort::Id,
+                    "ident" => AirportSort::Ident,
+                    "name" => AirportSort::Name,
+                    "elevation" => AirportSort::ElevationFt,
+                    "longest_runway" => AirportSort::LongestRunwayFt,
+                    "distance" => match self.radius {
+                        Some((center, _)) => AirportSort::DistanceFrom(center),
+                        None => return Err(QueryParamError::MissingParam("near".to_string())),
+                    },
+                    _ => return Err(invalid()),
+                };
+                self.sort_by(sort)
+            }
+            "order" => self.descending(match value.trim() {
+                "asc" => false,
+                "desc" => true,
+                _ => return Err(invalid()),
+            }),
+            "limit" => self.limit(parse_param_value(key, value)?),
+            "offset" => self.offset(parse_param_value(key, value)?),
+            _ => return Err(QueryParamError::UnknownParam(key.to_string())),
+        };
+        Ok(self)
+    }
+
+    /// Applies several options given as text with [`param()`](Self::param()), in order.
+    ///
+    /// # Errors
+    /// Returns [`QueryParamError`] if a key is unknown, a value is invalid, or `sort=distance` is
+    /// given without `near`.
+    pub fn params<K: AsRef<str>, V: AsRef<str>>(
+        mut self,
+        params: impl IntoIterator<Item = (K, V)>,
+    ) -> Result<Self, QueryParamError> {
+        // `sort=distance` is applied last, so that it may come before `near`
+        let mut sort_by_distance = false;
+        for (key, value) in params {
+            let (key, value) = (key.as_ref(), value.as_ref());
+            if key == "sort" {
+                sort_by_distance = value.trim() == "distance";
+                if sort_by_distance {
+                    continue;
+                }
+            }
+            self = self.param(key, value)?;
+        }
+        if sort_by_distance {
+            self = self.param("sort", "distance")?;
+        }
+        Ok(self)
+    }
+
+    /// `true` if `airport`, with the given runways, matches all filters of this query.
+    pub fn matches(&self, airport: &Airport, runways: &[&Runway]) -> bool {
+        fn contains<T: Ord>(set: &Option<BTreeSet<T>>, value: &T) -> bool {
+            set.as_ref().is_none_or(|s| s.contains(value))
+        }
+        fn contains_str(set: &Option<BTreeSet<String>>, value: &str) -> bool {
+            set.as_ref().is_none_or(|s| s.contains(value))
+        }
+
+        if !contains(&self.airport_types, airport.airport_type())
+            || !contains(&self.continents, airport.continent())
+            || !contains_str(&self.countries, airport.iso_country())
+            || !contains_str(&self.regions, airport.iso_region())
+        {
+            return false;
+        }
+        if self
+            .scheduled_service
+            .is_some_and(|s| s != airport.scheduled_service())
+        {
+            return false;
+        }
+        if self
+            .has_iata_code
+            .is_some_and(|h| h != airport.iata_code_opt().is_some())
+        {
+            return false;
+        }
+        if let Some(range) = self.elevation_ft {
+            if !airport.elevation_ft().is_some_and(|e| range.contains(&e)) {
+                return false;
+            }
+        }
+        let coordinate = airport.coordinate();
+        if self.bounding_box.is_some_and(|b| !b.contains(&coordinate)) {
+            return false;
+        }
+        if self
+            .radius
+            .is_some_and(|(center, radius)| center.distance_nm(&coordinate) > radius)
+        {
+            return false;
+        }
+        if self.min_runway_length_ft.is_some() || self.paved_runway || self.lighted_runway {
+            let runway_matches = |r: &&&Runway| {
+                !r.closed()
+                    && (!self.paved_runway || r.paved())
+                    && (!self.lighted_runway || r.lighted())
+                    && self
+                        .min_runway_length_ft
+                        .is_none_or(|min| r.length_ft().is_some_and(|l| l >= min))
+            };
+            if !runways.iter().any(|r| runway_matches(&r)) {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Runs the query against `index`.
+    ///
+    /// Country, region, type and location filters are answered from the index; the remaining
+    /// filters are applied to the smallest candidate set.
+    pub fn run<'a>(&self, index: &AirportIndex<'a>) -> QueryResults<'a> {
+        let mut candidate_sets: Vec<Vec<usize>> = vec![];
+        let from_map = |map: &HashMap<&str, Vec<usize>>, keys: &BTreeSet<String>| {
+            keys.iter()
+                .filter_map(|k| map.get(k.as_str()))
+                .flatten()
+                .copied()
+                .collect::<Vec<_>>()
+        };
+        if let Some(regions) = &self.regions {
+            candidate_sets.push(from_map(&index.by_region, regions));
+        }
+        if let Some(countries) = &self.countries {
+            candidate_sets.push(from_map(&index.by_country, countries));
+        }
+        if let Some(types) = &self.airport_types {
+            candidate_sets.push(
+                types
+                    .iter()
+                    .filter_map(|t| index.by_type.get(t))
+                    .flatten()
+                    .copied()
+                    .collect(),
+            );
+        }
+        if let Some((center, radius)) = &self.radius {
+            candidate_sets.push(
+                index
+                    .spatial
+                    .within_radius(center, *radius)
+                    .into_iter()
+                    .map(|(i, _)| *i)
+                    .collect(),
+            );
+        }
+        if let Some(bbox) = &self.bounding_box {
+            candidate_sets.push(
+                index
+                    .spatial
+                    .in_bounding_box(bbox)
+                    .into_iter()
+                    .copied()
+                    .collect(),
+            );
+        }
+        let mut candidates = candidate_sets
+            .into_iter()
+            .min_by_key(Vec::len)
+            .unwrap_or_else(|| (0..index.airports.len()).collect());
+        candidates.sort_unstable();
+
+        let mut airports = candidates
+            .into_iter()
+            .map(|i| index.airports[i])
+            .filter(|a| self.matches(a, index.runways(a.id())))
+            .collect::<Vec<_>>();
+        self.sort(index, &mut airports);
+
+        let total = airports.len();
+        let airports = airports
+            .into_iter()
+            .skip(self.offset)
+            .take(self.limit.unwrap_or(usize::MAX))
+            .collect();
+        QueryResults { total, airports }
+    }
+
+    fn sort(&self, index: &AirportIndex, airports: &mut [&Airport]) {
+        let order = |ordering: Ordering| {
+            if self.descending {
+                ordering.reverse()
+            } else {
+                ordering
+            }
+        };
+        // missing values sort first in either order
+        let order_optional = |a: Option<i32>, b: Option<i32>| match (a, b) {
+            (Some(a), Some(b)) => order(a.cmp(&b)),
+            (a, b) => a.cmp(&b),
+        };
+        let by_id = |a: &Airport, b: &Airport| a.id().cmp(&b.id());
+        match self.sort {
+            AirportSort::Id => airports.sort_by(|a, b| order(by_id(a, b))),
+            AirportSort::Ident => {
+                airports.sort_by(|a, b| order(a.ident().cmp(b.ident())).then_with(|| by_id(a, b)))
+            }
+            AirportSort::Name => {
+                airports.sort_by(|a, b| order(a.name().cmp(b.name())).then_with(|| by_id(a, b)))
+            }
+            AirportSort::ElevationFt => airports.sort_by(|a, b| {
+                order_optional(a.elevation_ft(), b.elevation_ft()).then_with(|| by_id(a, b))
+            }),
+            AirportSort::LongestRunwayFt => {
+                let longest_runways = airports
+                    .iter()
+                    .map(|a| (a.id(), index.longest_runway_ft(a.id())))
+                    .collect::<HashMap<_, _>>();
+                airports.sort_by(|a, b| {
+                    order_optional(longest_runways[&a.id()], longest_runways[&b.id()])
+                        .then_with(|| by_id(a, b))
+                })
+            }
+            AirportSort::DistanceFrom(center) => airports.sort_by(|a, b| {
+                let distance_a = center.distance_nm(&a.coordinate());
+                order(distance_a.total_cmp(&center.distance_nm(&b.coordinate())))
+                    .then_with(|| by_id(a, b))
+            }),
+        }
+    }
+}
+
+fn parse_param_value<T: FromStr>(key: &str, value: &str) -> Result<T, QueryParamError> {
+    value
+        .trim()
+        .parse()
+        .map_err(|_| QueryParamError::InvalidValue(key.to_string(), value.to_string()))
+}
+
+/// Parses comma-separated OurAirports codes through serde, e.g. `large_airport` or `EU`.
+fn parse_param_codes<T: DeserializeOwned>(
+    key: &str,
+    value: &str,
+) -> Result<Vec<T>, QueryParamError> {
+    value
+        .split(',')
+        .map(|code| serde_json::from_value(serde_json::Value::String(code.trim().into())))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| QueryParamError::InvalidValue(key.to_string(), value.to_string()))
+}
+
+/// Error type for invalid [`AirportQuery::param()`] arguments.
+#[derive(thiserror::Error, Debug, Clone, Eq, PartialEq)]
+pub enum QueryParamError {
+    #[error("Unknown parameter \"{0}\"")]
+    UnknownParam(String),
+    #[error("Invalid value for parameter \"{0}\": \"{1}\"")]
+    InvalidValue(String, String),
+    #[error("Parameter \"{0}\" is required")]
+    MissingParam(String),
+}
+
+/// A page of results of an [`AirportQuery`].
+#[derive(Debug, Clone)]
+pub struct QueryResults<'a> {
+    total: usize,
+    airports: Vec<&'a Airport>,
+}
+
+impl<'a> QueryResults<'a> {
+    /// Total number of matching airports, before applying offset and limit.
+    pub fn total(&self) -> usize {
+        self.total
+    }
+    /// The matching airports in the requested page.
+    pub fn airports(&self) -> &[&'a Airport] {
+        &self.airports
+    }
+    /// Consumes the results, returning the matching airports in the requested page.
+    pub fn into_airports(self) -> Vec<&'a Airport> {
+        self.airports
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{self, airport, runway};
+
+    fn data() -> (BTreeMap<Id, Airport>, BTreeMap<Id, Runway>) {
+        let airport = |id, ident, airport_type, latitude, longitude, region: &str| {
+            airport(id, ident)
+                .set("type", airport_type)
+                .position(latitude, longitude)
+                .set(
+                    "continent",
+                    if region.starts_with("US") { "NA" } else { "EU" },
+                )
+                .set("iso_country", &region[..2])
+                .set("iso_region", region)
+        };
+        let airports = test_support::airports([
+            airport(1, "EGLL", "large_airport", 51.47, -0.46, "GB-ENG")
+                .set("name", "London Heathrow Airport")
+                .set("elevation_ft", 83)
+                .set("scheduled_service", "yes")
+                .set("iata_code", "LHR"),
+            airport(2, "EGKK", "large_airport", 51.15, -0.19, "GB-ENG")
+                .set("name", "London Gatwick Airport")
+                .set("elevation_ft", 202)
+                .set("scheduled_service", "yes")
+                .set("iata_code", "LGW"),
+            airport(3, "EGLW", "heliport", 51.47, -0.18, "GB-ENG")
+                .set("name", "London Heliport")
+                .set("elevation_ft", 18),
+            airport(4, "LFPG", "large_airport", 49.01, 2.55, "FR-IDF")
+                .set("name", "Paris Charles de Gaulle Airport")
+                .set("elevation_ft", 392)
+                .set("scheduled_service", "yes")
+                .set("iata_code", "CDG"),
+            airport(5, "KJFK", "large_airport", 40.64, -73.78, "US-NY")
+                .set("name", "John F Kennedy International Airport")
+                .set("elevation_ft", 13)
+                .set("scheduled_service", "yes")
+                .set("iata_code", "JFK"),
+            airport(6, "XX01", "small_airport", 41.0, -74.0, "US-NY").set("name", "Bayside Strip"),
+        ]);
+        let runway = |id, airport_ref, length_ft, surface, lighted| {
+            runway(id, airport_ref)
+                .set("length_ft", length_ft)
+                .set("surface", surface)
+                .set("lighted", u8::from(lighted))
+        };
+        let runways = test_support::runways([
+            runway(11, 1, 12799, "ASP", true),
+            runway(21, 2, 10879, "ASP", true),
+            runway(31, 3, 100, "CON", false),
+            runway(41, 4, 13829, "CON", true),
+            runway(51, 5, 14511, "ASP", true),
+            runway(61, 6, 2000, "TURF", false),
+            runway(62, 6, 15000, "ASP", true).set("closed", 1),
+        ]);
+        (airports, runways)
+    }
+
+    fn idents<'a>(query: &AirportQuery, index: &AirportIndex<'a>) -> Vec<&'a str> {
+        query
+            .run(index)
+            .into_airports()
+            .into_iter()
+            .map(Airport::ident)
+            .collect()
+    }
+
+    fn params<'a>(params: &[(&str, &str)], index: &AirportIndex<'a>) -> Vec<&'a str> {
+        idents(
+            &AirportQuery::new().params(params.iter().copied()).unwrap(),
+            index,
+        )
+    }
+
+    #[test]
+    fn attribute_filters() {
+        let (airports, runways) = data();
+        let index = AirportIndex::with_runways(&airports, &runways);
+        let query = AirportQuery::new();
+        assert_eq!(
+            vec!["EGLW"],
+            idents(
+                &query.clone().airport_types([AirportType::Heliport]),
+                &index
+            )
+        );
+        assert_eq!(
+            vec!["KJFK", "XX01"],
+            idents(&query.clone().continents([Continent::NorthAmerica]), &index)
+        );
+        assert_eq!(
+            vec!["EGLL", "EGKK", "EGLW", "LFPG"],
+            idents(&query.clone().countries(["GB", "FR"]), &index)
+        );
+        assert_eq!(
+            vec!["LFPG"],
+            idents(&query.clone().regions(["FR-IDF"]), &index)
+        );
+        assert_eq!(
+            vec!["EGLW", "XX01"],
+            idents(&query.clone().scheduled_service(false), &index)
+        );
+        assert_eq!(
+            vec!["EGLL", "EGKK", "LFPG", "KJFK"],
+            idents(&query.clone().has_iata_code(true), &index)
+        );
+        // airports without an elevation never match
+        assert_eq!(
+            vec!["EGLL", "EGLW", "KJFK"],
+            idents(&query.clone().elevation_ft(..=83), &index)
+        );
+        assert_eq!(
+            vec!["EGLW", "KJFK"],
+            idents(&query.clone().elevation_ft(..83), &index)
+        );
+        assert_eq!(
+            vec!["EGLL", "EGLW"],
+            idents(
+                &query
+                    .clone()
+                    .countries(["GB"])
+                    .airport_types([AirportType::LargeAirport, AirportType::Heliport])
+                    .elevation_ft(0..100),
+                &index
+            )
+        );
+    }
+
+    #[test]
+    fn location_filters() {
+        let (airports, runways) = data();
+        let index = AirportIndex::with_runways(&airports, &runways);
+        let query = AirportQuery::new();
+        assert_eq!(
+            vec!["EGLL", "EGKK", "EGLW"],
+            idents(
+                &query
+                    .clone()
+                    .bounding_box(BoundingBox::new(51.0, -1.0, 52.0, 0.0)),
+                &index
+            )
+        );
+        assert_eq!(
+            vec!["EGLL", "EGLW"],
+            idents(
+                &query
+                    .clone()
+                    .within_radius(Coordinate::new(51.47, -0.46), 20.0),
+                &index
+            )
+        );
+        assert!(idents(
+            &query
+                .clone()
+                .bounding_box(BoundingBox::new(51.0, -1.0, 52.0, 0.0))
+                .within_radius(Coordinate::new(40.64, -73.78), 100.0),
+            &index
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn runway_filters() {
+        let (airports, runways) = data();
+        let index = AirportIndex::with_runways(&airports, &runways);
+        let query = AirportQuery::new();
+        // the closed 15,000 ft runway of XX01 does not count
+        assert_eq!(
+            vec!["EGLL", "LFPG", "KJFK"],
+            idents(&query.clone().min_runway_length_ft(12000), &index)
+        );
+        assert_eq!(
+            vec!["EGLL", "EGKK", "EGLW", "LFPG", "KJFK"],
+            idents(&query.clone().paved_runway(true), &index)
+        );
+        assert_eq!(
+            vec!["EGLL", "EGKK", "LFPG", "KJFK"],
+            idents(&query.clone().lighted_runway(true), &index)
+        );
+        // the same runway must satisfy all conditions
+        assert_eq!(
+            vec!["EGLL", "EGKK", "LFPG", "KJFK"],
+            idents(
+                &query
+                    .clone()
+                    .min_runway_length_ft(1000)
+                    .paved_runway(true)
+                    .lighted_runway(true),
+                &index
+            )
+        );
+        // runway filters never match without runway data
+        let index = AirportIndex::new(&airports);
+        assert!(idents(&query.clone().paved_runway(true), &index).is_empty());
+    }
+
+    #[test]
+    fn sort_and_pagination() {
+        let (airports, runways) = data();
+        let index = AirportIndex::with_runways(&airports, &runways);
+        let query = AirportQuery::new();
+        // airports without an elevation sort first
+        assert_eq!(
+            vec!["XX01", "KJFK", "EGLW", "EGLL", "EGKK", "LFPG"],
+            idents(&query.clone().sort_by(AirportSort::ElevationFt), &index)
+        );
+        assert_eq!(
+            vec!["EGLW", "XX01", "EGKK", "EGLL", "LFPG", "KJFK"],
+            idents(&query.clone().sort_by(AirportSort::LongestRunwayFt), &index)
+        );
+        assert_eq!(
+            vec!["EGLL", "EGLW", "EGKK", "LFPG", "XX01", "KJFK"],
+            idents(
+                &query
+                    .clone()
+                    .sort_by(AirportSort::DistanceFrom(Coordinate::new(51.47, -0.46))),
+                &index
+            )
+        );
+        let results = query
+            .clone()
+            .sort_by(AirportSort::Ident)
+            .descending(true)
+            .offset(1)
+            .limit(2)
+            .run(&index);
+        assert_eq!(6, results.total());
+        assert_eq!(
+            vec!["LFPG", "KJFK"],
+            results
+                .airports()
+                .iter()
+                .map(|a| a.ident())
+                .collect::<Vec<_>>()
+        );
+        // airports without an elevation still sort first
+        assert_eq!(
+            vec!["XX01", "LFPG", "EGKK", "EGLL", "EGLW", "KJFK"],
+            idents(
+                &query
+                    .clone()
+                    .sort_by(AirportSort::ElevationFt)
+                    .descending(true),
+                &index
+            )
+        );
+        assert_eq!(
+            vec!["KJFK", "LFPG", "EGLL", "EGKK", "XX01", "EGLW"],
+            idents(
+                &query
+                    .clone()
+                    .sort_by(AirportSort::LongestRunwayFt)
+                    .descending(true),
+                &index
+            )
+        );
+    }
+
+    #[test]
+    fn descending_sort_keeps_ties_in_id_order() {
+        let airports = test_support::airports([
+            airport(1, "B"),
+            airport(2, "A").set("name", "B Airport"),
+            airport(3, "D"),
+            airport(4, "C").set("name", "B Airport"),
+        ]);
+        let index = AirportIndex::new(&airports);
+        let query = AirportQuery::new().sort_by(AirportSort::Name);
+        assert_eq!(vec!["B", "A", "C", "D"], idents(&query, &index));
+        assert_eq!(
+            vec!["D", "B", "A", "C"],
+            idents(&query.descending(true), &index)
+        );
+    }
+
+    #[test]
+    fn index_lookups() {
+        let (airports, runways) = data();
+        let index = AirportIndex::with_runways(&airports, &runways);
+        assert_eq!(6, index.len());
+        assert_eq!(Some("KJFK"), index.get(5).map(Airport::ident));
+        assert_eq!(Some(4), index.get_by_ident("LFPG").map(Airport::id));
+        assert_eq!(2, index.runways(6).len());
+        assert!(index.runways(7).is_empty());
+        let nearest = index
+            .nearest(&Coordinate::new(51.47, -0.46), 2)
+            .into_iter()
+            .map(|(a, _)| a.ident())
+            .collect::<Vec<_>>();
+        assert_eq!(vec!["EGLL", "EGLW"], nearest);
+    }
+
+    #[test]
+    fn param_filters() {
+        let (airports, runways) = data();
+        let index = AirportIndex::with_runways(&airports, &runways);
+        assert_eq!(vec!["EGLW"], params(&[("type", "heliport")], &index));
+        assert_eq!(
+            vec!["EGLW", "XX01"],
+            params(&[("type", "heliport, small_airport")], &index)
+        );
+        assert_eq!(vec!["KJFK", "XX01"], params(&[("continent", "NA")], &index));
+        assert_eq!(vec!["LFPG"], params(&[("country", "fr")], &index));
+        assert_eq!(vec!["KJFK", "XX01"], params(&[("region", "us-ny")], &index));
+        assert_eq!(
+            vec!["EGLW", "XX01"],
+            params(&[("scheduled", "false")], &index)
+        );
+        assert_eq!(vec!["EGLW", "XX01"], params(&[("iata", "false")], &index));
+        assert_eq!(
+            vec!["EGLL", "EGKK", "EGLW", "LFPG", "KJFK"],
+            params(&[("paved", "true")], &index)
+        );
+        assert_eq!(
+            vec!["EGLL", "EGKK", "LFPG", "KJFK"],
+            params(&[("lighted", "true")], &index)
+        );
+        assert_eq!(
+            vec!["EGLL", "LFPG", "KJFK"],
+            params(&[("min_runway", "12000")], &index)
+        );
+        assert_eq!(
+            vec!["EGLL", "EGKK"],
+            params(&[("min_elevation", "83"), ("max_elevation", "202")], &index)
+        );
+        assert_eq!(
+            vec!["EGLW", "KJFK"],
+            params(&[("max_elevation", "82")], &index)
+        );
+        assert_eq!(
+            vec!["EGLL", "EGKK", "EGLW"],
+            params(&[("bbox", "51,-1,52,0")], &index)
+        );
+        assert_eq!(
+            vec!["EGLL", "EGLW"],
+            params(&[("near", "51.47,-0.46,20")], &index)
+        );
+    }
+
+    #[test]
+    fn param_sort_and_pagination() {
+        let (airports, runways) = data();
+        let index = AirportIndex::with_runways(&airports, &runways);
+        let country = ("country", "GB");
+        assert_eq!(
+            vec!["EGKK", "EGLL", "EGLW"],
+            params(&[country, ("sort", "ident")], &index)
+        );
+        assert_eq!(
+            vec!["EGLW", "EGLL", "EGKK"],
+            params(&[country, ("sort", "name"), ("order", "desc")], &index)
+        );
+        assert_eq!(
+            vec!["EGLW", "EGLL", "EGKK"],
+            params(&[country, ("sort", "elevation"), ("order", "asc")], &index)
+        );
+        assert_eq!(
+            vec!["EGLW", "EGKK", "EGLL"],
+            params(&[country, ("sort", "longest_runway")], &index)
+        );
+        assert_eq!(
+            vec!["EGKK", "EGLL"],
+            params(
+                &[country, ("sort", "id"), ("order", "desc"), ("offset", "1")],
+                &index
+            )
+        );
+        assert_eq!(vec!["EGLL"], params(&[country, ("limit", "1")], &index));
+    }
+
+    #[test]
+    fn param_sort_by_distance_in_any_order() {
+        let (airports, runways) = data();
+        let index = AirportIndex::with_runways(&airports, &runways);
+        let near = ("near", "51.2,-0.17,40");
+        let sort = ("sort", "distance");
+        assert_eq!(vec!["EGKK", "EGLW", "EGLL"], params(&[near, sort], &index));
+        assert_eq!(vec!["EGKK", "EGLW", "EGLL"], params(&[sort, near], &index));
+        assert_eq!(
+            vec!["EGLL", "EGKK", "EGLW"],
+            params(&[sort, ("sort", "id"), near], &index)
+        );
+    }
+
+    #[test]
+    fn param_errors() {
+        let missing_near = AirportQuery::new().params([("sort", "distance")]);
+        assert_eq!(
+            Some(QueryParamError::MissingParam("near".to_string())),
+            missing_near.err()
+        );
+        let missing_near = AirportQuery::new().param("sort", "distance");
+        assert_eq!(
+            Some(QueryParamError::MissingParam("near".to_string())),
+            missing_near.err()
+        );
+        let invalid = |key: &str, value: &str| {
+            Some(QueryParamError::InvalidValue(
+                key.to_string(),
+                value.to_string(),
+            ))
+        };
+        for (key, value) in [
+            ("type", "airstrip"),
+            ("continent", "XX"),
+            ("scheduled", "yes"),
+            ("min_runway", "long"),
+            ("bbox", "1,2,3"),
+            ("near", "1,2"),
+            ("sort", "size"),
+            ("order", "up"),
+            ("limit", "-1"),
+        ] {
+            assert_eq!(
+                invalid(key, value),
+                AirportQuery::new().param(key, value).err()
+            );
+        }
+        assert_eq!(
+            Some(QueryParamError::UnknownParam("colour".to_string())),
+            AirportQuery::new().param("colour", "red").err()
+        );
+    }
+}
@@ -0,0 +1,305 @@
+//! Contains a model of the reception range of navaids, based on the standard service volumes of
+//! the FAA Aeronautical Information Manual (section 1-1-8), and an index for finding the navaids
+//! receivable at a position.
+//!
+//! The service volume class of a navaid is taken from its [`usage_type`](Navaid::usage_type()),
+//! or its [`power`](Navaid::power()) if the usage type is not known. Altitudes are in feet above
+//! mean sea level and converted to heights above the navaid using its elevation (sea level if
+//! unknown). Reception of VHF and UHF navaids (VOR, DME, TACAN) is also limited to the radio
+//! line of sight, which is what limits reception below 1000 ft above the navaid.
+//!
+//! # Examples
+//! ```no_run
+//! use ourairports::geo::Coordinate;
+//! use ourairports::navaids::*;
+//! use ourairports::reception::NavaidIndex;
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!     let navaids = get_navaids_csv()?;
+//!     let index = NavaidIndex::new(&navaids);
+//!
+//!     // navaids receivable over London at 5000 ft
+//!     for (navaid, distance) in index.receivable(&Coordinate::new(51.5, -0.1), 5000.0) {
+//!         println!("{} {} {:.0} nm", navaid.ident(), navaid.frequency_khz(), distance);
+//!     }
+//! #    Ok(())
+//! # }
+//! ```
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::fix::{provides_radial_dme, RadialDme};
+use crate::geo::{Coordinate, SpatialIndex};
+use crate::navaids::{Navaid, NavaidPower, NavaidType, UsageType};
+use crate::Id;
+
+/// Largest range of any service volume in nautical miles.
+const MAX_RANGE_NM: f64 = 130.0;
+
+/// Standard service volume class of a navaid.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum ServiceVolumeClass {
+    /// Terminal VOR, DME or TACAN: 25 nm up to 12,000 ft above the navaid.
+    Terminal,
+    /// Low altitude VOR, DME or TACAN: 40 nm up to 18,000 ft above the navaid.
+    Low,
+    /// High altitude VOR, DME or TACAN: 40 nm up to 14,500 ft, 100 nm up to 18,000 ft, 130 nm
+    /// up to 45,000 ft and 100 nm up to 60,000 ft above the navaid.
+    High,
+    /// NDB compass locator: 15 nm.
+    CompassLocator,
+    /// Medium-high power NDB (less than 50 W): 25 nm.
+    NdbMediumHigh,
+    /// High power NDB (50 to 1999 W): 50 nm.
+    NdbHigh,
+    /// Very high power NDB (2000 W or more): 75 nm.
+    NdbHighHigh,
+}
+
+impl ServiceVolumeClass {
+    /// Returns the service volume class of `navaid`.
+    ///
+    /// Navaids with neither a usage type nor a power are assumed to be in the
+    /// [`Low`](Self::Low) or [`NdbMediumHigh`](Self::NdbMediumHigh) class.
+    pub fn of(navaid: &Navaid) -> Self {
+        let ndb = matches!(navaid.navaid_type(), NavaidType::Ndb | NavaidType::NdbDme);
+        match (ndb, navaid.usage_type(), navaid.power()) {
+            (true, Some(UsageType::Term), _) => ServiceVolumeClass::CompassLocator,
+            (true, _, Some(NavaidPower::Medium)) => ServiceVolumeClass::NdbHigh,
+            (true, _, Some(NavaidPower::High)) => ServiceVolumeClass::NdbHighHigh,
+            (true, _, _) => ServiceVolumeClass::NdbMediumHigh,
+            (false, Some(UsageType::Term), _) => ServiceVolumeClass::Terminal,
+            (false, Some(UsageType::Hi | UsageType::Both), _) => ServiceVolumeClass::High,
+            (false, Some(UsageType::Lo | UsageType::Rnav), _) => ServiceVolumeClass::Low,
+            (false, None, Some(NavaidPower::Low)) => ServiceVolumeClass::Terminal,
+            (false, None, Some(NavaidPower::High)) => ServiceVolumeClass::High,
+            (false, None, _) => ServiceVolumeClass::Low,
+        }
+    }
+    /// `true` for NDB classes, whose ground wave is not limited to the line of sight.
+    pub fn is_ndb(&self) -> bool {
+        matches!(
+            self,
+            ServiceVolumeClass::CompassLocator
+                | ServiceVolumeClass::NdbMediumHigh
+                | ServiceVolumeClass::NdbHigh
+                | ServiceVolumeClass::NdbHighHigh
+        )
+    }
+    /// Service volume range in nautical miles at `height_ft` above the navaid, ignoring the line
+    /// of sight. Returns `None` above the service volume.
+    pub fn range_nm(&self, height_ft: f64) -> Option<f64> {
+        match self {
+            ServiceVolumeClass::Terminal => (height_ft <= 12_000.0).then_some(25.0),
+            ServiceVolumeClass::Low => (height_ft <= 18_000.0).then_some(40.0),
+            ServiceVolumeClass::High => match height_ft {
+                h if h <= 14_500.0 => Some(40.0),
+                h if h <= 18_000.0 => Some(100.0),
+                h if h <= 45_000.0 => Some(130.0),
+                h if h <= 60_000.0 => Some(100.0),
+                _ => None,
+            },
+            ServiceVolumeClass::CompassLocator => Some(15.0),
+            ServiceVolumeClass::NdbMediumHigh => Some(25.0),
+            ServiceVolumeClass::NdbHigh => Some(50.0),
+            ServiceVolumeClass::NdbHighHigh => Some(75.0),
+        }
+    }
+}
+
+/// Distance to the radio horizon in nautical miles between a receiver and a transmitter at the
+/// given heights in feet, allowing for standard atmospheric refraction.
+pub fn radio_line_of_sight_nm(receiver_height_ft: f64, transmitter_height_ft: f64) -> f64 {
+    1.23 * (receiver_height_ft.max(0.0).sqrt() + transmitter_height_ft.max(0.0).sqrt())
+}
+
+/// A spatial index of navaids.
+///
+/// The index borrows the data it was built from.
+#[derive(Debug, Clone)]
+pub struct NavaidIndex<'a> {
+    spatial: SpatialIndex<&'a Navaid>,
+}
+
+impl<'a> NavaidIndex<'a> {
+    /// Builds an index over `navaids` with a known position.
+    pub fn new(navaids: &'a BTreeMap<Id, Navaid>) -> Self {
+        NavaidIndex {
+            spatial: navaids
+                .values()
+                .filter_map(|navaid| Some((navaid.coordinate()?, navaid)))
+                .collect(),
+        }
+    }
+    /// Returns the number of navaids in the index.
+    pub fn len(&self) -> usize {
+        self.spatial.len()
+    }
+    /// Returns `true` if the index contains no navaids.
+    pub fn is_empty(&self) -> bool {
+        self.spatial.is_empty()
+    }
+    /// Returns the `n` navaids nearest to `coordinate`, together with their distance in nautical
+    /// miles, sorted by increasing distance.
+    pub fn nearest(&self, coordinate: &Coordinate, n: usize) -> Vec<(&'a Navaid, f64)> {
+        self.spatial
+            .nearest(coordinate, n)
+            .into_iter()
+            .map(|(navaid, distance)| (*navaid, distance))
+            .collect()
+    }
+    /// Returns all navaids within `radius_nm` nautical miles of `coordinate`, together with their
+    /// distance, sorted by increasing distance.
+    pub fn within_radius(&self, coordinate: &Coordinate, radius_nm: f64) -> Vec<(&'a Navaid, f64)> {
+        self.spatial
+            .within_radius(coordinate, radius_nm)
+            .into_iter()
+            .map(|(navaid, distance)| (*navaid, distance))
+            .collect()
+    }
+    /// Returns the navaid nearest to `coordinate` for which `predicate` returns `true`, together
+    /// with its distance in nautical miles.
+    pub fn nearest_where(
+        &self,
+        coordinate: &Coordinate,
+        mut predicate: impl FnMut(&Navaid) -> bool,
+    ) -> Option<(&'a Navaid, f64)> {
+        let mut n = 16;
+        loop {
+            let found = self.nearest(coordinate, n);
+            let exhausted = found.len() < n;
+            if let Some(navaid) = found.into_iter().find(|(navaid, _)| predicate(navaid)) {
+                return Some(navaid);
+            }
+            if exhausted {
+                return None;
+            }
+            n *= 4;
+        }
+    }
+    /// Returns the navaid nearest to `coordinate` which provides radials and DME and has a known
+    /// variation, together with the radial and DME distance of `coordinate` from it.
+    pub fn nearest_radial_dme(&self, coordinate: &Coordinate) -> Option<(&'a Navaid, RadialDme)> {
+        let (navaid, _) = self.nearest_where(coordinate, |navaid| {
+            provides_radial_dme(navaid) && navaid.radial_variation_deg().is_some()
+        })?;
+        Some((navaid, navaid.radial_dme_to(coordinate)?))
+    }
+    /// Returns all navaids expected to be receivable at `coordinate` and `altitude_ft` above
+    /// mean sea level (see [`Navaid::covers()`]), together with their distance in nautical
+    /// miles, sorted by increasing distance.
+    pub fn receivable(&self, coordinate: &Coordinate, altitude_ft: f64) -> Vec<(&'a Navaid, f64)> {
+        let mut found = self.within_radius(coordinate, MAX_RANGE_NM);
+        found.retain(|(navaid, distance)| {
+            navaid
+                .reception_range_nm(altitude_ft)
+                .is_some_and(|range| *distance <= range)
+        });
+        found
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{self, navaid};
+
+    #[test]
+    fn service_volume_boundaries() {
+        let high = ServiceVolumeClass::High;
+        for (height_ft, range_nm) in [
+            (0.0, Some(40.0)),
+            (14_500.0, Some(40.0)),
+            (14_501.0, Some(100.0)),
+            (18_000.0, Some(100.0)),
+            (18_001.0, Some(130.0)),
+            (45_000.0, Some(130.0)),
+            (45_001.0, Some(100.0)),
+            (60_000.0, Some(100.0)),
+            (60_001.0, None),
+        ] {
+            assert_eq!(range_nm, high.range_nm(height_ft), "{} ft", height_ft);
+        }
+        assert_eq!(Some(25.0), ServiceVolumeClass::Terminal.range_nm(12_000.0));
+        assert_eq!(None, ServiceVolumeClass::Terminal.range_nm(12_001.0));
+        assert_eq!(Some(40.0), ServiceVolumeClass::Low.range_nm(18_000.0));
+        assert_eq!(None, ServiceVolumeClass::Low.range_nm(18_001.0));
+        assert_eq!(
+            Some(75.0),
+            ServiceVolumeClass::NdbHighHigh.range_nm(60_001.0)
+        );
+    }
+
+    #[test]
+    fn classes_and_line_of_sight() {
+        let navaid = |id, navaid_type, usage_type, power| {
+            navaid(id, "X", navaid_type)
+                .set("usageType", usage_type)
+                .set("power", power)
+                .set("elevation_ft", 1000)
+        };
+        let navaids = test_support::navaids([
+            navaid(1, "VOR-DME", "HI", ""),
+            navaid(2, "VOR", "", "HIGH"),
+            navaid(3, "VORTAC", "TERMINAL", "HIGH"),
+            navaid(4, "TACAN", "", ""),
+            navaid(5, "NDB", "TERM", "HIGH"),
+            navaid(6, "NDB", "", "MEDIUM"),
+            navaid(7, "NDB-DME", "HI", ""),
+        ]);
+        let classes = navaids
+            .values()
+            .map(ServiceVolumeClass::of)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                ServiceVolumeClass::High,
+                ServiceVolumeClass::High,
+                ServiceVolumeClass::Terminal,
+                ServiceVolumeClass::Low,
+                ServiceVolumeClass::CompassLocator,
+                ServiceVolumeClass::NdbHigh,
+                ServiceVolumeClass::NdbMediumHigh,
+            ],
+            classes
+        );
+
+        // 1000 ft above the navaid, the radio horizon is about 38.9 nm
+        let vor = &navaids[&1];
+        let range = vor.reception_range_nm(2000.0).unwrap();
+        assert!((range - radio_line_of_sight_nm(1000.0, 0.0)).abs() < 1e-9);
+        assert!((range - 38.9).abs() < 0.1);
+        assert_eq!(Some(40.0), vor.reception_range_nm(15_500.0));
+        assert_eq!(Some(100.0), vor.reception_range_nm(15_501.0));
+        assert_eq!(None, vor.reception_range_nm(61_001.0));
+        assert_eq!(None, vor.reception_range_nm(999.0));
+        // NDBs are not limited to the line of sight
+        assert_eq!(Some(50.0), navaids[&6].reception_range_nm(1000.0));
+    }
+
+    #[test]
+    fn receivable_navaids() {
+        let navaids = test_support::navaids([
+            navaid(1, "NEAR", "VOR").set("usageType", "HI"),
+            navaid(2, "FAR", "VOR")
+                .set("usageType", "HI")
+                .position(1.5, 0.0),
+            navaid(3, "NDB", "NDB").position(0.3, 0.0),
+        ]);
+        let index = NavaidIndex::new(&navaids);
+        let idents = |altitude_ft| {
+            index
+                .receivable(&Coordinate::new(0.0, 0.0), altitude_ft)
+                .into_iter()
+                .map(|(navaid, _)| navaid.ident())
+                .collect::<Vec<_>>()
+        };
+        // FAR is 90 nm away
+        assert_eq!(vec!["NEAR", "NDB"], idents(10_000.0));
+        assert_eq!(vec!["NEAR", "NDB", "FAR"], idents(20_000.0));
+        assert_eq!(vec!["NDB"], idents(61_000.0));
+    }
+}
@@ -21,12 +21,18 @@ use log::debug;
 use std::cmp::Ordering;
 use std::collections::BTreeMap;
 use std::hash::{Hash, Hasher};
+use std::io::Read;
 
 use serde::{Deserialize, Serialize};
+use url::Url;
 
-use crate::{vec_string_from_string, Continent, FetchError, Id, ToJsonString};
+use crate::codes::{CountryCode, RegionCode};
+use crate::{
+    empty_as_none, parse_link, vec_string_from_string, Continent, FetchError, Id, ToJsonString,
+};
 
-const REGIONS_CSV_URL: &str = "https://davidmegginson.github.io/ourairports-data/regions.csv";
+pub(crate) const REGIONS_CSV_URL: &str =
+    "https://davidmegginson.github.io/ourairports-data/regions.csv";
 
 /// Represents a high-level administrative subdivision of a country.
 #[derive(Deserialize, Debug, Clone, Serialize)]
@@ -37,7 +43,8 @@ pub struct Region {
     name: String,
     continent: Continent,
     iso_country: String,
-    wikipedia_link: String,
+    #[serde(with = "empty_as_none")]
+    wikipedia_link: Option<String>,
     #[serde(deserialize_with = "vec_string_from_string")]
     keywords: Vec<String>,
 }
@@ -80,9 +87,32 @@ impl Region {
     pub fn iso_country(&self) -> &str {
         &self.iso_country
     }
+    /// Parsed [`Self::code()`]. Returns `None` if it is not a valid region code.
+    pub fn region_code(&self) -> Option<RegionCode> {
+        self.code.parse().ok()
+    }
+    /// Parsed [`Self::iso_country()`]. Returns `None` if it is not a valid country code.
+    pub fn country_code(&self) -> Option<CountryCode> {
+        self.iso_country.parse().ok()
+    }
+    /// `false` if [`Self::code()`] is not [`Self::iso_country()`] and [`Self::local_code()`]
+    /// joined by a hyphen.
+    pub fn has_consistent_code(&self) -> bool {
+        self.code == format!("{}-{}", self.iso_country, self.local_code)
+    }
     /// A link to the Wikipedia article describing the subdivision.
+    pub fn wikipedia_link_opt(&self) -> Option<&str> {
+        self.wikipedia_link.as_deref()
+    }
+    /// Same as [`Self::wikipedia_link_opt()`], but returns an empty string if not available.
+    #[deprecated(since = "0.2.0", note = "use `wikipedia_link_opt()` instead")]
     pub fn wikipedia_link(&self) -> &str {
-        &self.wikipedia_link
+        self.wikipedia_link.as_deref().unwrap_or_default()
+    }
+    /// The Wikipedia article about the subdivision as an `http` or `https` URL. Returns `None` if there
+    /// is no article or the link is not a valid URL.
+    pub fn wikipedia_url(&self) -> Option<Url> {
+        parse_link(self.wikipedia_link.as_deref())
     }
     /// A list of keywords to assist with search. May include former names for the region, and/or
     /// the region name in other languages. Each item represents one keyword.
@@ -129,9 +159,19 @@ pub fn get_regions_csv() -> Result<BTreeMap<Id, Region>, FetchError> {
     // get data
     debug!("getting data");
     let content = crate::web_request_blocking(REGIONS_CSV_URL)?;
+    Ok(read_regions_csv(content.as_bytes())?)
+}
+
+/// Reads [`Region`] records in the OurAirports `regions.csv` format from `reader`, returning a
+/// [`BTreeMap`] with its ID as the key, sorted according to its keys.
+///
+/// # Errors
+/// Returns [`csv::Error`] if the data cannot be read or there's something wrong with the
+/// de serialization process.
+pub fn read_regions_csv<R: Read>(reader: R) -> Result<BTreeMap<Id, Region>, csv::Error> {
     // initialise csv reader & return value
     debug!("initialising CSV reader");
-    let mut rdr = csv::Reader::from_reader(content.as_bytes());
+    let mut rdr = csv::Reader::from_reader(reader);
     debug!("parsing and deserializing data");
     let mut map = BTreeMap::new();
     for result in rdr.deserialize() {
@@ -0,0 +1,609 @@
+//! Contains a route network over navaids, for planning conventional (VOR-to-VOR) routes between
+//! airports without an airways database.
+//!
+//! Two navaids are connected if their service volumes (see [`crate::reception`]) overlap at the
+//! cruise altitude, so that the next navaid can be received before the previous one is lost, or
+//! alternatively if they are within a fixed distance of each other. Airports are connected to the
+//! navaids whose service volume they are in. Routes are the shortest paths through this network.
+//!
+//! Only navaids for the chosen [`AltitudeStructure`] are used, based on their
+//! [`usage_type`](Navaid::usage_type()). Magnetic courses and radials use the
+//! [radial variation](Navaid::radial_variation_deg()) of navaids, and the
+//! [World Magnetic Model](crate::magnetic) elsewhere.
+//!
+//! # Examples
+//! ```no_run
+//! use ourairports::airports::*;
+//! use ourairports::navaids::*;
+//! use ourairports::routing::*;
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!     let airports = get_airports_csv()?;
+//!     let navaids = get_navaids_csv()?;
+//!     let graph = NavaidGraph::new(&navaids, RouteOptions::low().altitude_ft(6000.0));
+//!
+//!     // Oxford to Southend
+//!     let oxford = airports.values().find(|a| a.ident() == "EGTK").unwrap();
+//!     let southend = airports.values().find(|a| a.ident() == "EGMC").unwrap();
+//!     let route = graph.route(oxford, southend).unwrap();
+//!     for leg in route.legs() {
+//!         println!(
+//!             "{} -> {}: {:.0} nm, {:03.0} M",
+//!             leg.from().ident(),
+//!             leg.to().ident(),
+//!             leg.distance_nm(),
+//!             leg.magnetic_course_deg()
+//!         );
+//!     }
+//! #    Ok(())
+//! # }
+//! ```
+
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BinaryHeap, HashMap};
+
+use chrono::{NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::airports::Airport;
+use crate::geo::{normalize_bearing, Coordinate, SpatialIndex};
+use crate::magnetic::magnetic_variation_deg;
+use crate::navaids::{Navaid, NavaidType, UsageType};
+use crate::Id;
+
+/// Default cruise altitude for routes in the low altitude structure, in feet.
+const DEFAULT_LOW_ALTITUDE_FT: f64 = 8000.0;
+
+/// Default cruise altitude for routes in the high altitude structure, in feet.
+const DEFAULT_HIGH_ALTITUDE_FT: f64 = 35_000.0;
+
+/// Altitude structure a route is planned in.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum AltitudeStructure {
+    /// Below flight level 180: navaids for low altitude, both or terminal use.
+    Low,
+    /// At or above flight level 180: navaids for high altitude or both uses.
+    High,
+}
+
+impl AltitudeStructure {
+    /// `true` if a navaid with the given usage type can be used in this structure. Navaids with
+    /// an unknown usage type can be used in both structures.
+    pub fn allows(&self, usage_type: Option<&UsageType>) -> bool {
+        match (self, usage_type) {
+            (_, None) | (_, Some(UsageType::Both)) => true,
+            (AltitudeStructure::Low, Some(usage)) => {
+                matches!(usage, UsageType::Lo | UsageType::Term)
+            }
+            (AltitudeStructure::High, Some(usage)) => usage == &UsageType::Hi,
+        }
+    }
+}
+
+/// Options for building a [`NavaidGraph`].
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct RouteOptions {
+    structure: AltitudeStructure,
+    altitude_ft: f64,
+    max_leg_nm: Option<f64>,
+    include_ndbs: bool,
+    date: Option<NaiveDate>,
+}
+
+impl RouteOptions {
+    /// Options for the low altitude structure at 8000 ft.
+    pub fn low() -> Self {
+        RouteOptions {
+            structure: AltitudeStructure::Low,
+            altitude_ft: DEFAULT_LOW_ALTITUDE_FT,
+            max_leg_nm: None,
+            include_ndbs: false,
+            date: None,
+        }
+    }
+    /// Options for the high altitude structure at 35,000 ft.
+    pub fn high() -> Self {
+        RouteOptions {
+            structure: AltitudeStructure::High,
+            altitude_ft: DEFAULT_HIGH_ALTITUDE_FT,
+            ..Self::low()
+        }
+    }
+    /// Cruise altitude above mean sea level in feet, used for the service volumes.
+    pub fn altitude_ft(mut self, altitude_ft: f64) -> Self {
+        self.altitude_ft = altitude_ft;
+        self
+    }
+    /// Connects navaids (and airports to navaids) which are at most `max_leg_nm` nautical miles
+    /// apart, instead of those whose service volumes overlap.
+    pub fn max_leg_nm(mut self, max_leg_nm: f64) -> Self {
+        self.max_leg_nm = Some(max_leg_nm);
+        self
+    }
+    /// Whether NDBs are also used. Only VORs, VOR-DMEs and VORTACs are used by default.
+    pub fn include_ndbs(mut self, include: bool) -> Self {
+        self.include_ndbs = include;
+        self
+    }
+    /// Date used for the magnetic variation where a navaid's variation is not available.
+    /// Defaults to the current date.
+    pub fn date(mut self, date: NaiveDate) -> Self {
+        self.date = Some(date);
+        self
+    }
+
+    /// `true` if `navaid` can be used for routes with these options.
+    pub fn accepts(&self, navaid: &Navaid) -> bool {
+        let type_allowed = match navaid.navaid_type() {
+            NavaidType::Vor | NavaidType::VorDme | NavaidType::Vortac => true,
+            NavaidType::Ndb | NavaidType::NdbDme => self.include_ndbs,
+            NavaidType::Dme | NavaidType::Tacan => false,
+        };
+        type_allowed
+            && self.structure.allows(navaid.usage_type())
+            && navaid.coordinate().is_some()
+            && navaid.reception_range_nm(self.altitude_ft).is_some()
+    }
+}
+
+impl Default for RouteOptions {
+    fn default() -> Self {
+        Self::low()
+    }
+}
+
+/// A navaid in a [`NavaidGraph`].
+#[derive(Debug, Clone)]
+struct Node<'a> {
+    navaid: &'a Navaid,
+    coordinate: Coordinate,
+    range_nm: f64,
+}
+
+/// A network of navaids connected by legs which can be flown using them.
+///
+/// The graph borrows the navaids it was built from.
+#[derive(Debug, Clone)]
+pub struct NavaidGraph<'a> {
+    options: RouteOptions,
+    nodes: Vec<Node<'a>>,
+    spatial: SpatialIndex<usize>,
+    edges: Vec<Vec<(usize, f64)>>,
+    by_id: HashMap<Id, usize>,
+}
+
+impl<'a> NavaidGraph<'a> {
+    /// Builds the graph from the `navaids` accepted by `options`.
+    pub fn new(navaids: &'a BTreeMap<Id, Navaid>, options: RouteOptions) -> Self {
+        let nodes = navaids
+            .values()
+            .filter(|navaid| options.accepts(navaid))
+            .filter_map(|navaid| {
+                Some(Node {
+                    navaid,
+                    coordinate: navaid.coordinate()?,
+                    range_nm: navaid.reception_range_nm(options.altitude_ft)?,
+                })
+            })
+            .collect::<Vec<_>>();
+        let spatial = nodes
+            .iter()
+            .enumerate()
+            .map(|(i, node)| (node.coordinate, i))
+            .collect::<SpatialIndex<_>>();
+        let max_range_nm = nodes.iter().map(|node| node.range_nm).fold(0.0, f64::max);
+        let edges = nodes
+            .iter()
+            .enumerate()
+            .map(|(i, node)| {
+                let radius = options.max_leg_nm.unwrap_or(node.range_nm + max_range_nm);
+                spatial
+                    .within_radius(&node.coordinate, radius)
+                    .into_iter()
+                    .filter(|&(&j, distance)| {
+                        j != i
+                            && (options.max_leg_nm.is_some()
+                                || distance <= node.range_nm + nodes[j].range_nm)
+                    })
+                    .map(|(&j, distance)| (j, distance))
+                    .collect()
+            })
+            .collect();
+        NavaidGraph {
+            by_id: nodes
+                .iter()
+                .enumerate()
+                .map(|(i, node)| (node.navaid.id(), i))
+                .collect(),
+            options,
+            nodes,
+            spatial,
+            edges,
+        }
+    }
+
+    /// The options the graph was built with.
+    pub fn options(&self) -> &RouteOptions {
+        &self.options
+    }
+    /// Returns the number of navaids in the graph.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+    /// Returns `true` if the graph contains no navaids.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+    /// Returns the number of connections between navaids, counting each direction separately.
+    pub fn edge_count(&self) -> usize {
+        self.edges.iter().map(Vec::len).sum()
+    }
+    /// Returns the navaids connected to `navaid`, together with their distance in nautical miles,
+    /// sorted by increasing distance. Returns an empty vector if `navaid` is not in the graph.
+    pub fn neighbours(&self, navaid: &Navaid) -> Vec<(&'a Navaid, f64)> {
+        self.by_id
+            .get(&navaid.id())
+            .map(|&i| {
+                self.edges[i]
+                    .iter()
+                    .map(|&(j, distance)| (self.nodes[j].navaid, distance))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Returns the shortest route from airport `from` to airport `to` via navaids in the graph,
+    /// or `None` if there is no such route.
+    ///
+    /// Routes always go via at least one navaid.
+    pub fn route(&self, from: &'a Airport, to: &'a Airport) -> Option<NavaidRoute<'a>> {
+        let origin = self.nodes.len();
+        let destination = origin + 1;
+        let origin_links = self.airport_links(&from.coordinate());
+        let destination_links = self
+            .airport_links(&to.coordinate())
+            .into_iter()
+            .collect::<HashMap<_, _>>();
+
+        let mut distances = vec![f64::INFINITY; self.nodes.len() + 2];
+        let mut previous = vec![None; self.nodes.len() + 2];
+        let mut queue = BinaryHeap::new();
+        distances[origin] = 0.0;
+        queue.push(QueueEntry {
+            distance_nm: 0.0,
+            node: origin,
+        });
+        while let Some(QueueEntry { distance_nm, node }) = queue.pop() {
+            if node == destination {
+                break;
+            }
+            if distance_nm > distances[node] {
+                continue;
+            }
+            let links: Box<dyn Iterator<Item = (usize, f64)>> = if node == origin {
+                Box::new(origin_links.iter().copied())
+            } else {
+                Box::new(
+                    self.edges[node].iter().copied().chain(
+                        destination_links
+                            .get(&node)
+                            .map(|&distance| (destination, distance)),
+                    ),
+                )
+            };
+            for (next, leg_nm) in links {
+                let candidate = distance_nm + leg_nm;
+                if candidate < distances[next] {
+                    distances[next] = candidate;
+                    previous[next] = Some(node);
+                    queue.push(QueueEntry {
+                        distance_nm: candidate,
+                        node: next,
+                    });
+                }
+            }
+        }
+
+        let mut path = vec![destination];
+        while let Some(node) = previous[*path.last()?] {
+            path.push(node);
+        }
+        path.reverse();
+        if path.first() != Some(&origin) {
+            return None;
+        }
+        let point = |node: usize| match node {
+            n if n == origin => (Waypoint::Airport(from), from.coordinate()),
+            n if n == destination => (Waypoint::Airport(to), to.coordinate()),
+            n => (
+                Waypoint::Navaid(self.nodes[n].navaid),
+                self.nodes[n].coordinate,
+            ),
+        };
+        let date = self.options.date.unwrap_or_else(|| Utc::now().date_naive());
+        let legs = path
+            .windows(2)
+            .map(|pair| RouteLeg::new(point(pair[0]), point(pair[1]), date))
+            .collect();
+        Some(NavaidRoute { legs })
+    }
+
+    /// Navaids an airport at `coordinate` is connected to, with their distance.
+    fn airport_links(&self, coordinate: &Coordinate) -> Vec<(usize, f64)> {
+        let max_range_nm = self
+            .nodes
+            .iter()
+            .map(|node| node.range_nm)
+            .fold(0.0, f64::max);
+        self.spatial
+            .within_radius(coordinate, self.options.max_leg_nm.unwrap_or(max_range_nm))
+            .into_iter()
+            .filter(|&(&i, distance)| {
+                self.options.max_leg_nm.is_some() || distance <= self.nodes[i].range_nm
+            })
+            .map(|(&i, distance)| (i, distance))
+            .collect()
+    }
+}
+
+/// Entry in the priority queue of the shortest path search, ordered by increasing distance.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct QueueEntry {
+    distance_nm: f64,
+    node: usize,
+}
+
+impl Eq for QueueEntry {}
+
+impl Ord for QueueEntry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .distance_nm
+            .total_cmp(&self.distance_nm)
+            .then(other.node.cmp(&self.node))
+    }
+}
+
+impl PartialOrd for QueueEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// The start or end of a [`RouteLeg`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Waypoint<'a> {
+    Airport(&'a Airport),
+    Navaid(&'a Navaid),
+}
+
+impl<'a> Waypoint<'a> {
+    /// Identifier of the airport or navaid.
+    pub fn ident(&self) -> &'a str {
+        match self {
+            Waypoint::Airport(airport) => airport.ident(),
+            Waypoint::Navaid(navaid) => navaid.ident(),
+        }
+    }
+    /// Position of the airport or navaid.
+    pub fn coordinate(&self) -> Option<Coordinate> {
+        match self {
+            Waypoint::Airport(airport) => Some(airport.coordinate()),
+            Waypoint::Navaid(navaid) => navaid.coordinate(),
+        }
+    }
+    /// The navaid, or `None` for an airport.
+    pub fn navaid(&self) -> Option<&'a Navaid> {
+        match self {
+            Waypoint::Airport(_) => None,
+            Waypoint::Navaid(navaid) => Some(navaid),
+        }
+    }
+}
+
+/// A leg of a [`NavaidRoute`] between two waypoints.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RouteLeg<'a> {
+    from: Waypoint<'a>,
+    to: Waypoint<'a>,
+    distance_nm: f64,
+    true_course_deg: f64,
+    magnetic_course_deg: f64,
+    outbound_radial_deg: Option<f64>,
+    inbound_radial_deg: Option<f64>,
+}
+
+impl<'a> RouteLeg<'a> {
+    fn new(
+        (from, from_coordinate): (Waypoint<'a>, Coordinate),
+        (to, to_coordinate): (Waypoint<'a>, Coordinate),
+        date: NaiveDate,
+    ) -> Self {
+        let true_course_deg = from_coordinate.initial_bearing_deg(&to_coordinate);
+        let variation = |point: &Waypoint, coordinate: &Coordinate| match point.navaid() {
+            Some(navaid) => navaid
+                .radial_variation_deg()
+                .unwrap_or_else(|| magnetic_variation_deg(coordinate, date)),
+            None => magnetic_variation_deg(coordinate, date),
+        };
+        let radial = |point: &Waypoint, coordinate: &Coordinate, other: &Coordinate| {
+            point
+                .navaid()
+                .filter(|navaid| has_radials(navaid))
+                .map(|_| {
+                    normalize_bearing(
+                        coordinate.initial_bearing_deg(other) - variation(point, coordinate),
+                    )
+                })
+        };
+        RouteLeg {
+            from,
+            to,
+            distance_nm: from_coordinate.distance_nm(&to_coordinate),
+            true_course_deg,
+            magnetic_course_deg: normalize_bearing(
+                true_course_deg - variation(&from, &from_coordinate),
+            ),
+            outbound_radial_deg: radial(&from, &from_coordinate, &to_coordinate),
+            inbound_radial_deg: radial(&to, &to_coordinate, &from_coordinate),
+        }
+    }
+    /// Start of the leg.
+    pub fn from(&self) -> Waypoint<'a> {
+        self.from
+    }
+    /// End of the leg.
+    pub fn to(&self) -> Waypoint<'a> {
+        self.to
+    }
+    /// Great-circle length of the leg in nautical miles.
+    pub fn distance_nm(&self) -> f64 {
+        self.distance_nm
+    }
+    /// Initial true course of the leg in degrees.
+    pub fn true_course_deg(&self) -> f64 {
+        self.true_course_deg
+    }
+    /// Initial magnetic course of the leg in degrees, using the variation at the start of the
+    /// leg.
+    pub fn magnetic_course_deg(&self) -> f64 {
+        self.magnetic_course_deg
+    }
+    /// Radial of the start navaid the leg is flown outbound on. Returns `None` if the leg does
+    /// not start at a VOR.
+    pub fn outbound_radial_deg(&self) -> Option<f64> {
+        self.outbound_radial_deg
+    }
+    /// Radial of the end navaid the leg is flown inbound on; the course to the navaid is its
+    /// reciprocal. Returns `None` if the leg does not end at a VOR.
+    pub fn inbound_radial_deg(&self) -> Option<f64> {
+        self.inbound_radial_deg
+    }
+}
+
+/// A route between two airports via navaids, as returned by [`NavaidGraph::route()`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct NavaidRoute<'a> {
+    legs: Vec<RouteLeg<'a>>,
+}
+
+impl<'a> NavaidRoute<'a> {
+    /// The legs of the route, in order.
+    pub fn legs(&self) -> &[RouteLeg<'a>] {
+        &self.legs
+    }
+    /// The navaids along the route, in order.
+    pub fn navaids(&self) -> Vec<&'a Navaid> {
+        self.legs.iter().filter_map(|leg| leg.to.navaid()).collect()
+    }
+    /// Total length of the route in nautical miles.
+    pub fn distance_nm(&self) -> f64 {
+        self.legs.iter().map(|leg| leg.distance_nm).sum()
+    }
+}
+
+/// `true` if `navaid` provides radials.
+fn has_radials(navaid: &Navaid) -> bool {
+    matches!(
+        navaid.navaid_type(),
+        NavaidType::Vor | NavaidType::VorDme | NavaidType::Vortac | NavaidType::Tacan
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{self, airport, navaid};
+
+    fn date() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 1, 1).unwrap()
+    }
+
+    /// VOR-DMEs along the equator one degree (60 nm) apart, with BBB for high altitude only, a
+    /// detour via CCC for low altitude only, and a DME which is never used.
+    fn navaids() -> BTreeMap<Id, Navaid> {
+        let vor = |id, ident, latitude, longitude, usage_type| {
+            navaid(id, ident, "VOR-DME")
+                .position(latitude, longitude)
+                .set("usageType", usage_type)
+                .set("magnetic_variation_deg", -10.0)
+        };
+        test_support::navaids([
+            vor(1, "AAA", 0.0, 0.0, "BOTH"),
+            vor(2, "BBB", 0.0, 1.0, "HI"),
+            vor(3, "CCC", 0.5, 1.0, "LO"),
+            vor(4, "DDD", 0.0, 2.0, ""),
+            vor(5, "EEE", 0.0, 1.5, "TERM").set("type", "DME"),
+        ])
+    }
+
+    fn airports() -> BTreeMap<Id, Airport> {
+        test_support::airports([
+            airport(1, "ORIG").position(0.0, -0.5),
+            airport(2, "DEST").position(0.0, 2.5),
+            airport(3, "FAR").position(10.0, 10.0),
+        ])
+    }
+
+    fn idents<'a>(route: &NavaidRoute<'a>) -> Vec<&'a str> {
+        route.navaids().iter().map(|n| n.ident()).collect()
+    }
+
+    #[test]
+    fn altitude_structures() {
+        let navaids = navaids();
+        let airports = airports();
+        let options = |options: RouteOptions| options.altitude_ft(10_000.0).max_leg_nm(80.0);
+
+        let low = NavaidGraph::new(&navaids, options(RouteOptions::low()));
+        assert_eq!(3, low.len());
+        let route = low.route(&airports[&1], &airports[&2]).unwrap();
+        assert_eq!(vec!["AAA", "CCC", "DDD"], idents(&route));
+
+        let high = NavaidGraph::new(&navaids, options(RouteOptions::high()));
+        assert_eq!(3, high.len());
+        let route = high.route(&airports[&1], &airports[&2]).unwrap();
+        assert_eq!(vec!["AAA", "BBB", "DDD"], idents(&route));
+        assert!((route.distance_nm() - 3.0 * 60.04).abs() < 0.1);
+
+        // no navaid within 80 nm
+        assert!(high.route(&airports[&1], &airports[&3]).is_none());
+        // VORs are 60 nm apart
+        let sparse = NavaidGraph::new(&navaids, options(RouteOptions::high()).max_leg_nm(50.0));
+        assert_eq!(0, sparse.edge_count());
+        assert!(sparse.route(&airports[&1], &airports[&2]).is_none());
+    }
+
+    #[test]
+    fn courses_and_radials() {
+        let navaids = navaids();
+        let airports = airports();
+        let graph = NavaidGraph::new(
+            &navaids,
+            RouteOptions::high()
+                .altitude_ft(10_000.0)
+                .max_leg_nm(80.0)
+                .date(date()),
+        );
+        let route = graph.route(&airports[&1], &airports[&2]).unwrap();
+        let legs = route.legs();
+        assert_eq!(4, legs.len());
+
+        // from the airport to AAA, east along the equator, inbound on AAA's 280 radial
+        let variation = magnetic_variation_deg(&airports[&1].coordinate(), date());
+        assert!((legs[0].true_course_deg() - 90.0).abs() < 1e-9);
+        assert!((legs[0].magnetic_course_deg() - normalize_bearing(90.0 - variation)).abs() < 1e-9);
+        assert_eq!(None, legs[0].outbound_radial_deg());
+        assert!((legs[0].inbound_radial_deg().unwrap() - 280.0).abs() < 1e-9);
+
+        // from AAA to BBB on AAA's 100 radial, inbound on BBB's 280 radial
+        assert!((legs[1].magnetic_course_deg() - 100.0).abs() < 1e-9);
+        assert!((legs[1].outbound_radial_deg().unwrap() - 100.0).abs() < 1e-9);
+        assert!((legs[1].inbound_radial_deg().unwrap() - 280.0).abs() < 1e-9);
+
+        // from DDD to the airport on DDD's 100 radial
+        assert!((legs[3].outbound_radial_deg().unwrap() - 100.0).abs() < 1e-9);
+        assert_eq!(None, legs[3].inbound_radial_deg());
+    }
+}
@@ -10,8 +10,8 @@
 //!     // Runway 08L/26R at Vancouver International Airport
 //!     // (ICAO: CYVR, IATA: YVR)
 //!     let example_runway = runways.get(&234512).unwrap();
-//!     assert_eq!("08L", example_runway.le_ident());
-//!     assert_eq!("26R", example_runway.he_ident());
+//!     assert_eq!(Some("08L"), example_runway.le_ident_opt());
+//!     assert_eq!(Some("26R"), example_runway.he_ident_opt());
 //!     assert_eq!("CYVR", example_runway.airport_ident());
 //! #    Ok(())
 //! # }
@@ -19,14 +19,22 @@
 
 use std::cmp::Ordering;
 use std::collections::BTreeMap;
+use std::fmt;
 use std::hash::{Hash, Hasher};
+use std::io::Read;
 
+use chrono::NaiveDate;
 use log::debug;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Serialize, Serializer};
 
-use crate::{bool_from_str, FetchError, Id, ToJsonString};
+use crate::geo::{normalize_bearing, Coordinate, ToGeoJson};
+use crate::geometry::RunwayGeometry;
+use crate::magnetic::{true_to_magnetic_deg, MagneticModel};
+use crate::units::{Elevation, Length};
+use crate::{bool_from_str, empty_as_none, FetchError, Id, ToJsonString};
 
-const RUNWAYS_CSV_URL: &str = "https://davidmegginson.github.io/ourairports-data/runways.csv";
+pub(crate) const RUNWAYS_CSV_URL: &str =
+    "https://davidmegginson.github.io/ourairports-data/runways.csv";
 
 /// Represents a single airport landing surface (runway, helipad, or waterway).
 ///
@@ -43,19 +51,22 @@ pub struct Runway {
     airport_ident: String,
     length_ft: Option<i32>,
     width_ft: Option<i32>,
-    surface: String, // TODO: make this an enum once the vocab is controlled
+    #[serde(with = "empty_as_none")]
+    surface: Option<String>, // TODO: make this an enum once the vocab is controlled
     #[serde(deserialize_with = "bool_from_str")]
     lighted: bool,
     #[serde(deserialize_with = "bool_from_str")]
     closed: bool,
-    le_ident: String,
+    #[serde(with = "empty_as_none")]
+    le_ident: Option<String>,
     le_latitude_deg: Option<f64>,
     le_longitude_deg: Option<f64>,
     le_elevation_ft: Option<i32>,
     #[serde(rename = "le_heading_degT")]
     le_heading_deg_true: Option<f64>,
     le_displaced_threshold_ft: Option<i32>,
-    he_ident: String,
+    #[serde(with = "empty_as_none")]
+    he_ident: Option<String>,
     he_latitude_deg: Option<f64>,
     he_longitude_deg: Option<f64>,
     he_elevation_ft: Option<i32>,
@@ -89,16 +100,52 @@ impl Runway {
     pub fn length_ft(&self) -> Option<i32> {
         self.length_ft
     }
+    /// [`Self::length_ft()`] as a [`Length`]. Returns `None` if not available.
+    pub fn length(&self) -> Option<Length> {
+        self.length_ft.map(|ft| Length::feet(ft.into()))
+    }
     /// Width of the runway surface in feet.
     pub fn width_ft(&self) -> Option<i32> {
         self.width_ft
     }
+    /// [`Self::width_ft()`] as a [`Length`]. Returns `None` if not available.
+    pub fn width(&self) -> Option<Length> {
+        self.width_ft.map(|ft| Length::feet(ft.into()))
+    }
     /// Code for the runway surface type.
     ///
     /// Currently, `surface` is stored as a `String` as the surface type is not a
     /// controlled vocabulary. This may change in the future as the type codes are stadardised.
+    pub fn surface_opt(&self) -> Option<&str> {
+        self.surface.as_deref()
+    }
+    /// Same as [`Self::surface_opt()`], but returns an empty string if not available.
+    #[deprecated(since = "0.2.0", note = "use `surface_opt()` instead")]
     pub fn surface(&self) -> &str {
-        &self.surface
+        self.surface.as_deref().unwrap_or_default()
+    }
+    /// `true` if the surface code indicates a hard, paved surface (asphalt, concrete, bitumen,
+    /// tarmac, etc), `false` otherwise.
+    ///
+    /// As [`surface`](Self::surface_opt()) is not a controlled vocabulary, this is a best-effort guess
+    /// based on the most common codes.
+    pub fn paved(&self) -> bool {
+        const PAVED_PREFIXES: [&str; 8] = ["ASP", "CON", "PEM", "BIT", "TAR", "PAV", "MAC", "CEM"];
+        let surface = self.surface_opt().unwrap_or_default().trim().to_uppercase();
+        PAVED_PREFIXES.iter().any(|p| surface.starts_with(p))
+    }
+    /// `true` if the low-numbered end designator indicates a helipad (e.g. "H1"), `false`
+    /// otherwise.
+    pub fn helipad(&self) -> bool {
+        self.le_designator().is_some_and(|d| d.is_helipad())
+    }
+    /// `true` if the designator (e.g. "18W") or surface code indicates a water runway, `false`
+    /// otherwise.
+    pub fn water(&self) -> bool {
+        self.le_designator().is_some_and(|d| d.is_water())
+            || self
+                .surface_opt()
+                .is_some_and(|s| s.trim().to_uppercase().starts_with("WAT"))
     }
     /// `true` if the surface is lighted at night, `false` otherwise.
     pub fn lighted(&self) -> bool {
@@ -109,8 +156,13 @@ impl Runway {
         self.closed
     }
     /// Identifier for the low-numbered end of the runway.
+    pub fn le_ident_opt(&self) -> Option<&str> {
+        self.le_ident.as_deref()
+    }
+    /// Same as [`Self::le_ident_opt()`], but returns an empty string if not available.
+    #[deprecated(since = "0.2.0", note = "use `le_ident_opt()` instead")]
     pub fn le_ident(&self) -> &str {
-        &self.le_ident
+        self.le_ident.as_deref().unwrap_or_default()
     }
     /// Latitude of the centre of the low-numbered end of the runway, in decimal degrees
     /// (positive is north). Returns `None` if not available.
@@ -127,6 +179,10 @@ impl Runway {
     pub fn le_elevation_ft(&self) -> Option<i32> {
         self.le_elevation_ft
     }
+    /// [`Self::le_elevation_ft()`] as an [`Elevation`]. Returns `None` if not available.
+    pub fn le_elevation(&self) -> Option<Elevation> {
+        self.le_elevation_ft.map(|ft| Elevation::feet(ft.into()))
+    }
     /// Heading of the low-numbered end of the runway in degrees true.
     /// Returns `None` if not available.
     pub fn le_heading_deg_true(&self) -> Option<f64> {
@@ -137,9 +193,19 @@ impl Runway {
     pub fn le_displaced_threshold_ft(&self) -> Option<i32> {
         self.le_displaced_threshold_ft
     }
+    /// [`Self::le_displaced_threshold_ft()`] as a [`Length`]. Returns `None` if not available.
+    pub fn le_displaced_threshold(&self) -> Option<Length> {
+        self.le_displaced_threshold_ft
+            .map(|ft| Length::feet(ft.into()))
+    }
     /// Identifier for the high-numbered end of the runway.
+    pub fn he_ident_opt(&self) -> Option<&str> {
+        self.he_ident.as_deref()
+    }
+    /// Same as [`Self::he_ident_opt()`], but returns an empty string if not available.
+    #[deprecated(since = "0.2.0", note = "use `he_ident_opt()` instead")]
     pub fn he_ident(&self) -> &str {
-        &self.he_ident
+        self.he_ident.as_deref().unwrap_or_default()
     }
     /// Latitude of the centre of the high-numbered end of the runway, in decimal degrees
     /// (positive is north). Returns `None` if not available.
@@ -156,6 +222,10 @@ impl Runway {
     pub fn he_elevation_ft(&self) -> Option<i32> {
         self.he_elevation_ft
     }
+    /// [`Self::he_elevation_ft()`] as an [`Elevation`]. Returns `None` if not available.
+    pub fn he_elevation(&self) -> Option<Elevation> {
+        self.he_elevation_ft.map(|ft| Elevation::feet(ft.into()))
+    }
     /// Heading of the high-numbered end of the runway in degrees true.
     /// Returns `None` if not available.
     pub fn he_heading_deg_true(&self) -> Option<f64> {
@@ -166,8 +236,202 @@ impl Runway {
     pub fn he_displaced_threshold_ft(&self) -> Option<i32> {
         self.he_displaced_threshold_ft
     }
+    /// [`Self::he_displaced_threshold_ft()`] as a [`Length`]. Returns `None` if not available.
+    pub fn he_displaced_threshold(&self) -> Option<Length> {
+        self.he_displaced_threshold_ft
+            .map(|ft| Length::feet(ft.into()))
+    }
+    /// Parsed designator of the low-numbered end of the runway. Returns `None` if
+    /// [`le_ident`](Self::le_ident_opt()) is not available.
+    pub fn le_designator(&self) -> Option<RunwayDesignator> {
+        self.low_end().designator()
+    }
+    /// Parsed designator of the high-numbered end of the runway. Returns `None` if
+    /// [`he_ident`](Self::he_ident_opt()) is not available.
+    pub fn he_designator(&self) -> Option<RunwayDesignator> {
+        self.high_end().designator()
+    }
+    /// `false` if the designators of both ends are known and are not reciprocal (e.g. "09"/"26"),
+    /// `true` otherwise.
+    ///
+    /// Single-ended runways, helipads and unrecognised designators are always consistent.
+    pub fn has_consistent_designators(&self) -> bool {
+        match (self.le_designator(), self.he_designator()) {
+            (Some(low), Some(high)) => match low.reciprocal() {
+                Some(reciprocal) => reciprocal == high,
+                None => true,
+            },
+            _ => true,
+        }
+    }
+    /// The low-numbered end of the runway (e.g. 09).
+    pub fn low_end(&self) -> RunwayEnd<'_> {
+        RunwayEnd {
+            runway: self,
+            high: false,
+        }
+    }
+    /// The high-numbered end of the runway (e.g. 27).
+    pub fn high_end(&self) -> RunwayEnd<'_> {
+        RunwayEnd {
+            runway: self,
+            high: true,
+        }
+    }
+    /// Both ends of the runway, low-numbered end first.
+    pub fn ends(&self) -> [RunwayEnd<'_>; 2] {
+        [self.low_end(), self.high_end()]
+    }
+}
+
+/// One end of a [`Runway`], giving access to the `le_` or `he_` fields without having to
+/// handle both ends separately.
+#[derive(Debug, Clone, Copy)]
+pub struct RunwayEnd<'a> {
+    runway: &'a Runway,
+    high: bool,
+}
+
+impl<'a> RunwayEnd<'a> {
+    /// The runway this end belongs to.
+    pub fn runway(&self) -> &'a Runway {
+        self.runway
+    }
+    /// `true` if this is the high-numbered end of the runway, `false` if it is the low-numbered
+    /// end.
+    pub fn is_high_end(&self) -> bool {
+        self.high
+    }
+    /// The other end of the runway.
+    pub fn opposite(&self) -> RunwayEnd<'a> {
+        RunwayEnd {
+            runway: self.runway,
+            high: !self.high,
+        }
+    }
+    /// Identifier for this end of the runway.
+    pub fn ident(&self) -> &'a str {
+        if self.high {
+            self.runway.he_ident.as_deref().unwrap_or_default()
+        } else {
+            self.runway.le_ident.as_deref().unwrap_or_default()
+        }
+    }
+    /// Parsed designator of this end of the runway. Returns `None` if the identifier is empty.
+    pub fn designator(&self) -> Option<RunwayDesignator> {
+        let ident = self.ident().trim();
+        (!ident.is_empty()).then(|| RunwayDesignator::parse(ident))
+    }
+    /// Latitude of the centre of this end of the runway, in decimal degrees (positive is north).
+    /// Returns `None` if not available.
+    pub fn latitude_deg(&self) -> Option<f64> {
+        if self.high {
+            self.runway.he_latitude_deg()
+        } else {
+            self.runway.le_latitude_deg()
+        }
+    }
+    /// Longitude of the centre of this end of the runway, in decimal degrees (positive is east).
+    /// Returns `None` if not available.
+    pub fn longitude_deg(&self) -> Option<f64> {
+        if self.high {
+            self.runway.he_longitude_deg()
+        } else {
+            self.runway.le_longitude_deg()
+        }
+    }
+    /// Elevation above MSL of this end of the runway in feet. Returns `None` if not available.
+    pub fn elevation_ft(&self) -> Option<i32> {
+        if self.high {
+            self.runway.he_elevation_ft()
+        } else {
+            self.runway.le_elevation_ft()
+        }
+    }
+    /// [`Self::elevation_ft()`] as an [`Elevation`]. Returns `None` if not available.
+    pub fn elevation(&self) -> Option<Elevation> {
+        self.elevation_ft().map(|ft| Elevation::feet(ft.into()))
+    }
+    /// Heading of this end of the runway in degrees true, i.e. the direction of an aircraft
+    /// taking off from or landing on this end. Returns `None` if not available.
+    pub fn heading_deg_true(&self) -> Option<f64> {
+        if self.high {
+            self.runway.he_heading_deg_true()
+        } else {
+            self.runway.le_heading_deg_true()
+        }
+    }
+    /// Magnetic variation at this end of the runway on `date` in degrees (positive east), from
+    /// the embedded [World Magnetic Model](crate::magnetic). Returns `None` if the position of
+    /// this end is not available.
+    pub fn magnetic_variation(&self, date: NaiveDate) -> Option<f64> {
+        let coordinate = Coordinate::new(self.latitude_deg()?, self.longitude_deg()?);
+        let elevation_ft = f64::from(self.elevation_ft().unwrap_or(0));
+        Some(
+            MagneticModel::WMM2025
+                .field(&coordinate, elevation_ft, date)
+                .declination_deg(),
+        )
+    }
+    /// Magnetic heading of this end of the runway on `date` in degrees, converted from
+    /// [`heading_deg_true()`](Self::heading_deg_true()) with the
+    /// [`magnetic_variation()`](Self::magnetic_variation()) at this end. Returns `None` if either
+    /// is not available.
+    pub fn heading_deg_magnetic(&self, date: NaiveDate) -> Option<f64> {
+        Some(true_to_magnetic_deg(
+            self.heading_deg_true()?,
+            self.magnetic_variation(date)?,
+        ))
+    }
+    /// Length of the displaced threshold for this end of the runway, in feet.
+    /// Returns `None` if not available.
+    pub fn displaced_threshold_ft(&self) -> Option<i32> {
+        if self.high {
+            self.runway.he_displaced_threshold_ft()
+        } else {
+            self.runway.le_displaced_threshold_ft()
+        }
+    }
+    /// [`Self::displaced_threshold_ft()`] as a [`Length`]. Returns `None` if not available.
+    pub fn displaced_threshold(&self) -> Option<Length> {
+        self.displaced_threshold_ft()
+            .map(|ft| Length::feet(ft.into()))
+    }
+    /// Estimated take-off run available (TORA) from this end, in feet: the full runway length.
+    /// Returns `None` if the length is not available.
+    ///
+    /// This is an approximation from the runway length only; published declared distances
+    /// may be shorter.
+    pub fn tora_ft(&self) -> Option<i32> {
+        self.runway.length_ft()
+    }
+    /// [`Self::tora_ft()`] as a [`Length`]. Returns `None` if not available.
+    pub fn tora(&self) -> Option<Length> {
+        self.tora_ft().map(|ft| Length::feet(ft.into()))
+    }
+    /// Estimated landing distance available (LDA) on this end, in feet: the runway length minus
+    /// the displaced threshold of this end. Returns `None` if the length is not available.
+    ///
+    /// This is an approximation from the runway length and displaced threshold only; published
+    /// declared distances may be shorter.
+    pub fn lda_ft(&self) -> Option<i32> {
+        let length = self.runway.length_ft()?;
+        Some((length - self.displaced_threshold_ft().unwrap_or(0)).max(0))
+    }
+    /// [`Self::lda_ft()`] as a [`Length`]. Returns `None` if not available.
+    pub fn lda(&self) -> Option<Length> {
+        self.lda_ft().map(|ft| Length::feet(ft.into()))
+    }
+}
+
+impl PartialEq for RunwayEnd<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.runway == other.runway && self.high == other.high
+    }
 }
 
+impl Eq for RunwayEnd<'_> {}
+
 impl PartialEq for Runway {
     fn eq(&self, other: &Self) -> bool {
         self.id == other.id
@@ -196,6 +460,285 @@ impl Hash for Runway {
 
 impl ToJsonString for Runway {}
 
+/// The runway surface, see [`RunwayGeometry`]. Returns `None` if the position of either end is
+/// not available.
+impl ToGeoJson for Runway {
+    fn to_geojson_feature(&self) -> Option<serde_json::Value> {
+        RunwayGeometry::new(self)?.to_geojson_feature()
+    }
+}
+
+/// Parsed identifier of a runway end, e.g. "08L", "H1", "NE" or "ALL".
+///
+/// The [`Display`](fmt::Display) form is the canonical identifier, e.g. "9L" is displayed as
+/// "09L".
+///
+/// # Examples
+/// ```
+/// use ourairports::runways::*;
+///
+/// let designator = RunwayDesignator::parse("9L");
+/// assert_eq!(Some(9), designator.number());
+/// assert_eq!(Some(RunwaySuffix::Left), designator.suffix());
+/// assert_eq!(Some(90.0), designator.magnetic_heading_deg());
+/// assert_eq!("27R", designator.reciprocal().unwrap().to_string());
+///
+/// assert!(RunwayDesignator::parse("H1").is_helipad());
+/// assert!(RunwayDesignator::parse("36W").is_water());
+/// assert_eq!(
+///     Some(RunwayDesignator::Cardinal(CompassPoint::SW)),
+///     RunwayDesignator::parse("NE").reciprocal()
+/// );
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub enum RunwayDesignator {
+    /// Runway numbered by its magnetic heading in tens of degrees, from 1 to 36.
+    Numbered {
+        number: u8,
+        suffix: Option<RunwaySuffix>,
+    },
+    /// Helipad, optionally numbered (e.g. "H1").
+    Helipad(Option<u16>),
+    /// Runway designated by a compass point (e.g. "N" or "NE").
+    Cardinal(CompassPoint),
+    /// Area usable in all directions, e.g. a water landing area.
+    All,
+    /// Designator which is not recognised.
+    Unknown(String),
+}
+
+impl RunwayDesignator {
+    /// Parses a runway end identifier. Never fails; unrecognised identifiers become
+    /// [`RunwayDesignator::Unknown`].
+    pub fn parse(ident: &str) -> Self {
+        let upper = ident.trim().to_uppercase();
+        if upper == "ALL" {
+            return RunwayDesignator::All;
+        }
+        if let Some(point) = CompassPoint::parse(&upper) {
+            return RunwayDesignator::Cardinal(point);
+        }
+        if let Some(number) = upper.strip_prefix('H') {
+            if number.is_empty() {
+                return RunwayDesignator::Helipad(None);
+            }
+            if number.bytes().all(|b| b.is_ascii_digit()) {
+                if let Ok(number) = number.parse() {
+                    return RunwayDesignator::Helipad(Some(number));
+                }
+            }
+        }
+        let digits = upper.bytes().take_while(u8::is_ascii_digit).count();
+        if (1..=2).contains(&digits) {
+            let (number, suffix) = upper.split_at(digits);
+            let suffix = match suffix {
+                "" => Some(None),
+                _ => RunwaySuffix::parse(suffix).map(Some),
+            };
+            if let (Ok(number @ 1..=36), Some(suffix)) = (number.parse(), suffix) {
+                return RunwayDesignator::Numbered { number, suffix };
+            }
+        }
+        RunwayDesignator::Unknown(ident.to_string())
+    }
+    /// Runway number from 1 to 36, if numbered.
+    pub fn number(&self) -> Option<u8> {
+        match self {
+            RunwayDesignator::Numbered { number, .. } => Some(*number),
+            _ => None,
+        }
+    }
+    /// Suffix of a numbered runway, if any.
+    pub fn suffix(&self) -> Option<RunwaySuffix> {
+        match self {
+            RunwayDesignator::Numbered { suffix, .. } => *suffix,
+            _ => None,
+        }
+    }
+    /// `true` if this is a helipad.
+    pub fn is_helipad(&self) -> bool {
+        matches!(self, RunwayDesignator::Helipad(_))
+    }
+    /// `true` if this is a water runway (a numbered runway with the `W` suffix).
+    pub fn is_water(&self) -> bool {
+        self.suffix() == Some(RunwaySuffix::Water)
+    }
+    /// Estimated magnetic heading in degrees, from the runway number or compass point.
+    ///
+    /// Runway numbers are rounded to the nearest 10°, so the actual heading may differ by up to
+    /// 5° (more for runways renumbered less often than the magnetic variation changes).
+    pub fn magnetic_heading_deg(&self) -> Option<f64> {
+        match self {
+            RunwayDesignator::Numbered { number, .. } => {
+                Some(normalize_bearing(f64::from(*number) * 10.0))
+            }
+            RunwayDesignator::Cardinal(point) => Some(point.heading_deg()),
+            _ => None,
+        }
+    }
+    /// Designator of the opposite end of the runway, e.g. "27R" for "09L". Returns `None` for
+    /// helipads, [`RunwayDesignator::All`] and unrecognised designators.
+    pub fn reciprocal(&self) -> Option<RunwayDesignator> {
+        match self {
+            RunwayDesignator::Numbered { number, suffix } => Some(RunwayDesignator::Numbered {
+                number: (number + 17) % 36 + 1,
+                suffix: suffix.map(|s| s.reciprocal()),
+            }),
+            RunwayDesignator::Cardinal(point) => {
+                Some(RunwayDesignator::Cardinal(point.reciprocal()))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for RunwayDesignator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RunwayDesignator::Numbered { number, suffix } => {
+                write!(f, "{:02}", number)?;
+                match suffix {
+                    Some(suffix) => write!(f, "{}", suffix.code()),
+                    None => Ok(()),
+                }
+            }
+            RunwayDesignator::Helipad(Some(number)) => write!(f, "H{}", number),
+            RunwayDesignator::Helipad(None) => write!(f, "H"),
+            RunwayDesignator::Cardinal(point) => write!(f, "{}", point.code()),
+            RunwayDesignator::All => write!(f, "ALL"),
+            RunwayDesignator::Unknown(ident) => write!(f, "{}", ident),
+        }
+    }
+}
+
+impl Serialize for RunwayDesignator {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+/// Suffix of a numbered runway designator.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum RunwaySuffix {
+    /// Left of parallel runways (`L`).
+    #[serde(rename = "L")]
+    Left,
+    /// Centre of parallel runways (`C`).
+    #[serde(rename = "C")]
+    Centre,
+    /// Right of parallel runways (`R`).
+    #[serde(rename = "R")]
+    Right,
+    /// Water runway (`W`).
+    #[serde(rename = "W")]
+    Water,
+    /// Glider runway (`G`).
+    #[serde(rename = "G")]
+    Glider,
+    /// Ultralight runway (`U`).
+    #[serde(rename = "U")]
+    Ultralight,
+}
+
+impl RunwaySuffix {
+    fn parse(code: &str) -> Option<Self> {
+        Some(match code {
+            "L" => RunwaySuffix::Left,
+            "C" => RunwaySuffix::Centre,
+            "R" => RunwaySuffix::Right,
+            "W" => RunwaySuffix::Water,
+            "G" => RunwaySuffix::Glider,
+            "U" => RunwaySuffix::Ultralight,
+            _ => return None,
+        })
+    }
+    /// The one-letter code of the suffix.
+    pub fn code(&self) -> char {
+        match self {
+            RunwaySuffix::Left => 'L',
+            RunwaySuffix::Centre => 'C',
+            RunwaySuffix::Right => 'R',
+            RunwaySuffix::Water => 'W',
+            RunwaySuffix::Glider => 'G',
+            RunwaySuffix::Ultralight => 'U',
+        }
+    }
+    /// Suffix of the opposite end of the runway: left and right are swapped.
+    pub fn reciprocal(&self) -> Self {
+        match self {
+            RunwaySuffix::Left => RunwaySuffix::Right,
+            RunwaySuffix::Right => RunwaySuffix::Left,
+            other => *other,
+        }
+    }
+}
+
+/// One of the 16 points of the compass, used as a runway designator.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum CompassPoint {
+    N,
+    NNE,
+    NE,
+    ENE,
+    E,
+    ESE,
+    SE,
+    SSE,
+    S,
+    SSW,
+    SW,
+    WSW,
+    W,
+    WNW,
+    NW,
+    NNW,
+}
+
+impl CompassPoint {
+    /// All points, clockwise from north.
+    const ALL: [CompassPoint; 16] = [
+        CompassPoint::N,
+        CompassPoint::NNE,
+        CompassPoint::NE,
+        CompassPoint::ENE,
+        CompassPoint::E,
+        CompassPoint::ESE,
+        CompassPoint::SE,
+        CompassPoint::SSE,
+        CompassPoint::S,
+        CompassPoint::SSW,
+        CompassPoint::SW,
+        CompassPoint::WSW,
+        CompassPoint::W,
+        CompassPoint::WNW,
+        CompassPoint::NW,
+        CompassPoint::NNW,
+    ];
+
+    fn index(&self) -> usize {
+        *self as usize
+    }
+    fn parse(code: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|point| point.code() == code)
+    }
+    /// The abbreviation of the point, e.g. "NE".
+    pub fn code(&self) -> &'static str {
+        const CODES: [&str; 16] = [
+            "N", "NNE", "NE", "ENE", "E", "ESE", "SE", "SSE", "S", "SSW", "SW", "WSW", "W", "WNW",
+            "NW", "NNW",
+        ];
+        CODES[self.index()]
+    }
+    /// Heading of the point in degrees, e.g. 45 for north-east.
+    pub fn heading_deg(&self) -> f64 {
+        self.index() as f64 * 22.5
+    }
+    /// The opposite point, e.g. south-west for north-east.
+    pub fn reciprocal(&self) -> Self {
+        Self::ALL[(self.index() + 8) % 16]
+    }
+}
+
 /// Returns a [`BTreeMap`] of all [`Runway`] in the latest OurAirports `runways.csv`
 /// with its ID as the key, sorted according to its keys.
 ///
@@ -206,9 +749,19 @@ pub fn get_runways_csv() -> Result<BTreeMap<Id, Runway>, FetchError> {
     // get data
     debug!("getting data");
     let content = crate::web_request_blocking(RUNWAYS_CSV_URL)?;
+    Ok(read_runways_csv(content.as_bytes())?)
+}
+
+/// Reads [`Runway`] records in the OurAirports `runways.csv` format from `reader`, returning a
+/// [`BTreeMap`] with its ID as the key, sorted according to its keys.
+///
+/// # Errors
+/// Returns [`csv::Error`] if the data cannot be read or there's something wrong with the
+/// de serialization process.
+pub fn read_runways_csv<R: Read>(reader: R) -> Result<BTreeMap<Id, Runway>, csv::Error> {
     // initialise csv reader & return value
     debug!("initialising CSV reader");
-    let mut rdr = csv::Reader::from_reader(content.as_bytes());
+    let mut rdr = csv::Reader::from_reader(reader);
     debug!("parsing and deserializing data");
     let mut map = BTreeMap::new();
     for result in rdr.deserialize() {
@@ -217,3 +770,106 @@ pub fn get_runways_csv() -> Result<BTreeMap<Id, Runway>, FetchError> {
     }
     Ok(map)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{self, runway};
+
+    #[test]
+    fn parse_designators() {
+        let numbered = |number, suffix| RunwayDesignator::Numbered { number, suffix };
+        assert_eq!(
+            numbered(8, Some(RunwaySuffix::Left)),
+            RunwayDesignator::parse("08L")
+        );
+        assert_eq!(
+            numbered(9, Some(RunwaySuffix::Right)),
+            RunwayDesignator::parse(" 9r ")
+        );
+        assert_eq!(
+            numbered(36, Some(RunwaySuffix::Water)),
+            RunwayDesignator::parse("36W")
+        );
+        assert_eq!(numbered(1, None), RunwayDesignator::parse("01"));
+        assert_eq!(
+            RunwayDesignator::Helipad(Some(1)),
+            RunwayDesignator::parse("H1")
+        );
+        assert_eq!(
+            RunwayDesignator::Helipad(None),
+            RunwayDesignator::parse("H")
+        );
+        assert_eq!(
+            RunwayDesignator::Cardinal(CompassPoint::NE),
+            RunwayDesignator::parse("NE")
+        );
+        assert_eq!(RunwayDesignator::All, RunwayDesignator::parse("all"));
+        for ident in [
+            "", "0", "00", "37", "123", "08X", "08LL", "H1A", "HX", "XYZ",
+        ] {
+            assert_eq!(
+                RunwayDesignator::Unknown(ident.to_string()),
+                RunwayDesignator::parse(ident)
+            );
+        }
+        assert_eq!("09L", RunwayDesignator::parse("9l").to_string());
+        assert_eq!("H1", RunwayDesignator::parse("h1").to_string());
+        assert_eq!("NE", RunwayDesignator::parse("ne").to_string());
+        // headings are normalised to [0, 360)
+        assert_eq!(
+            Some(0.0),
+            RunwayDesignator::parse("36").magnetic_heading_deg()
+        );
+        assert_eq!(None, RunwayDesignator::parse("H1").magnetic_heading_deg());
+    }
+
+    #[test]
+    fn reciprocal_designators() {
+        let reciprocal = |ident| {
+            RunwayDesignator::parse(ident)
+                .reciprocal()
+                .map(|d| d.to_string())
+        };
+        for (ident, expected) in [
+            ("18", "36"),
+            ("36", "18"),
+            ("01", "19"),
+            ("19", "01"),
+            ("08L", "26R"),
+            ("26R", "08L"),
+            ("17C", "35C"),
+            ("36W", "18W"),
+            ("N", "S"),
+            ("NNE", "SSW"),
+            ("WNW", "ESE"),
+        ] {
+            assert_eq!(Some(expected.to_string()), reciprocal(ident), "{}", ident);
+        }
+        for ident in ["H1", "ALL", "XYZ"] {
+            assert_eq!(None, reciprocal(ident));
+        }
+    }
+
+    #[test]
+    fn consistent_designators() {
+        let runway = |id, low, high| runway(id, 1).set("le_ident", low).set("he_ident", high);
+        let runways = test_support::runways([
+            runway(1, "09", "27"),
+            runway(2, "09", "26"),
+            runway(3, "18", "36"),
+            runway(4, "09L", "27L"),
+            runway(5, "H1", ""),
+            runway(6, "09", ""),
+            runway(7, "N", "S"),
+            runway(8, "N", "E"),
+            runway(9, "ALL", "XYZ"),
+        ]);
+        let inconsistent = runways
+            .values()
+            .filter(|r| !r.has_consistent_designators())
+            .map(Runway::id)
+            .collect::<Vec<_>>();
+        assert_eq!(vec![2, 4, 8], inconsistent);
+    }
+}
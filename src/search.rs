@@ -0,0 +1,487 @@
+//! Contains a ranked full-text search index over airports.
+//!
+//! The index covers the airport name, municipality, keywords, identifier and IATA code, as well
+//! as the names of the region and country the airport is located in. Matching is
+//! case- and diacritic-insensitive, tolerates small typos and supports prefix matching, so it can
+//! be used for autocomplete.
+//!
+//! # Examples
+//! ```no_run
+//! use ourairports::airports::*;
+//! use ourairports::search::*;
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!     let airports = get_airports_csv()?;
+//!     let index = SearchIndex::new(&airports);
+//!
+//!     // typo in "Heathrow"
+//!     let results = index.search("londn heathrw", 5);
+//!     assert_eq!("EGLL", results[0].airport().ident());
+//!
+//!     // prefix matching for autocomplete
+//!     let results = index.search("heathr", 5);
+//!     assert_eq!("EGLL", results[0].airport().ident());
+//! #    Ok(())
+//! # }
+//! ```
+
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap};
+
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
+
+use crate::airports::{Airport, AirportType};
+use crate::countries::Country;
+use crate::regions::Region;
+use crate::Id;
+
+/// Fields of an airport that are covered by a [`SearchIndex`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum SearchField {
+    /// [`Airport::ident()`]
+    Ident,
+    /// [`Airport::iata_code_opt()`]
+    IataCode,
+    /// [`Airport::name()`]
+    Name,
+    /// [`Airport::municipality_opt()`]
+    Municipality,
+    /// [`Airport::keywords()`]
+    Keywords,
+    /// The name of the region given by [`Airport::iso_region()`]
+    Region,
+    /// The name of the country given by [`Airport::iso_country()`]
+    Country,
+}
+
+impl SearchField {
+    /// Relative weight of a match in this field. Codes rank above names, which rank above the
+    /// broader location of the airport.
+    fn weight(&self) -> f64 {
+        match self {
+            SearchField::Ident | SearchField::IataCode => 3.0,
+            SearchField::Name => 2.0,
+            SearchField::Municipality => 1.5,
+            SearchField::Keywords => 1.0,
+            SearchField::Region | SearchField::Country => 0.5,
+        }
+    }
+}
+
+/// A single airport matched by [`SearchIndex::search()`].
+#[derive(Debug, Clone)]
+pub struct SearchResult<'a> {
+    airport: &'a Airport,
+    score: f64,
+    matched_fields: Vec<SearchField>,
+}
+
+impl<'a> SearchResult<'a> {
+    /// The matched airport.
+    pub fn airport(&self) -> &'a Airport {
+        self.airport
+    }
+    /// Relevance score of the match. Higher is better. Scores are only meaningful relative to
+    /// other results of the same query.
+    pub fn score(&self) -> f64 {
+        self.score
+    }
+    /// The fields that contributed to the match, sorted and without duplicates.
+    pub fn matched_fields(&self) -> &[SearchField] {
+        &self.matched_fields
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd)]
+struct Posting {
+    airport: usize,
+    field: SearchField,
+}
+
+/// A ranked search index over a set of [`Airport`].
+///
+/// The index borrows the airports it was built from, so results can be returned as references.
+#[derive(Debug, Clone)]
+pub struct SearchIndex<'a> {
+    airports: Vec<&'a Airport>,
+    terms: BTreeMap<String, Vec<Posting>>,
+}
+
+impl<'a> SearchIndex<'a> {
+    /// Builds an index over `airports`, without region and country names.
+    pub fn new(airports: &'a BTreeMap<Id, Airport>) -> Self {
+        Self::build(airports, &HashMap::new(), &HashMap::new())
+    }
+
+    /// Builds an index over `airports`, also indexing the names of the region and country of
+    /// each airport.
+    pub fn with_regions_and_countries(
+        airports: &'a BTreeMap<Id, Airport>,
+        regions: &BTreeMap<Id, Region>,
+        countries: &BTreeMap<Id, Country>,
+    ) -> Self {
+        let region_names = regions
+            .values()
+            .map(|r| (r.code(), r.name()))
+            .collect::<HashMap<_, _>>();
+        let country_names = countries
+            .values()
+            .map(|c| (c.code(), c.name()))
+            .collect::<HashMap<_, _>>();
+        Self::build(airports, &region_names, &country_names)
+    }
+
+    fn build(
+        airports: &'a BTreeMap<Id, Airport>,
+        region_names: &HashMap<&str, &str>,
+        country_names: &HashMap<&str, &str>,
+    ) -> Self {
+        let mut terms: BTreeMap<String, Vec<Posting>> = BTreeMap::new();
+        let airports = airports.values().collect::<Vec<_>>();
+        for (i, airport) in airports.iter().enumerate() {
+            let mut add = |text: &str, field: SearchField| {
+                for token in tokenize(text) {
+                    terms
+                        .entry(token)
+                        .or_default()
+                        .push(Posting { airport: i, field });
+                }
+            };
+            add(airport.ident(), SearchField::Ident);
+            if let Some(iata_code) = airport.iata_code_opt() {
+                add(iata_code, SearchField::IataCode);
+            }
+            add(airport.name(), SearchField::Name);
+            if let Some(municipality) = airport.municipality_opt() {
+                add(municipality, SearchField::Municipality);
+            }
+            for keyword in airport.keywords() {
+                add(keyword, SearchField::Keywords);
+            }
+            if let Some(name) = region_names.get(airport.iso_region()) {
+                add(name, SearchField::Region);
+            }
+            if let Some(name) = country_names.get(airport.iso_country()) {
+                add(name, SearchField::Country);
+            }
+        }
+        for postings in terms.values_mut() {
+            postings.sort_unstable();
+            postings.dedup();
+        }
+        SearchIndex { airports, terms }
+    }
+
+    /// Returns the number of airports in the index.
+    pub fn len(&self) -> usize {
+        self.airports.len()
+    }
+
+    /// Returns `true` if the index contains no airports.
+    pub fn is_empty(&self) -> bool {
+        self.airports.is_empty()
+    }
+
+    /// Searches the index and returns at most `limit` results, best match first.
+    ///
+    /// Every word of `query` must match an indexed word, either exactly, as a prefix, or within a
+    /// small edit distance that grows with the word length. Typo-tolerant matches must start with
+    /// the same letter as the query word, or with its first two letters swapped. Results with
+    /// equal scores are ordered by the importance of the airport type, larger airports first, then
+    /// by airport ID.
+    pub fn search(&self, query: &str, limit: usize) -> Vec<SearchResult<'a>> {
+        let tokens = tokenize(query);
+        if tokens.is_empty() || limit == 0 {
+            return vec![];
+        }
+
+        // airport index -> (score, matched fields)
+        let mut hits: HashMap<usize, (f64, Vec<SearchField>)> = HashMap::new();
+        for (n, token) in tokens.iter().enumerate() {
+            // best score of each airport for this query word
+            let mut token_hits: HashMap<usize, (f64, SearchField)> = HashMap::new();
+            for (postings, quality) in self.matching_terms(token) {
+                for posting in postings {
+                    let score = quality * posting.field.weight();
+                    let entry = token_hits
+                        .entry(posting.airport)
+                        .or_insert((0.0, posting.field));
+                    if score > entry.0 {
+                        *entry = (score, posting.field);
+                    }
+                }
+            }
+            if n == 0 {
+                hits = token_hits
+                    .into_iter()
+                    .map(|(i, (score, field))| (i, (score, vec![field])))
+                    .collect();
+            } else {
+                hits.retain(|i, _| token_hits.contains_key(i));
+                for (i, (score, fields)) in hits.iter_mut() {
+                    let (token_score, field) = token_hits[i];
+                    *score += token_score;
+                    fields.push(field);
+                }
+            }
+            if hits.is_empty() {
+                return vec![];
+            }
+        }
+
+        let mut results = hits
+            .into_iter()
+            .map(|(i, (score, mut matched_fields))| {
+                let airport = self.airports[i];
+                matched_fields.sort_unstable();
+                matched_fields.dedup();
+                SearchResult {
+                    airport,
+                    score,
+                    matched_fields,
+                }
+            })
+            .collect::<Vec<_>>();
+        results.sort_by(|a, b| {
+            b.score
+                .partial_cmp(&a.score)
+                .unwrap_or(Ordering::Equal)
+                .then_with(|| {
+                    importance(b.airport.airport_type()).cmp(&importance(a.airport.airport_type()))
+                })
+                .then_with(|| a.airport.id().cmp(&b.airport.id()))
+        });
+        results.truncate(limit);
+        results
+    }
+
+    /// Returns the postings of all indexed terms matching `token`, together with the quality of
+    /// the match between 0 and 1.
+    fn matching_terms(&self, token: &str) -> Vec<(&[Posting], f64)> {
+        let token_len = token.chars().count();
+        let max_distance = match token_len {
+            0..=3 => 0,
+            4..=6 => 1,
+            _ => 2,
+        };
+        let mut matches = vec![];
+        for (term, postings) in self.terms.range(token.to_string()..) {
+            if !term.starts_with(token) {
+                break;
+            }
+            let quality = if term.len() == token.len() {
+                1.0
+            } else {
+                // prefer prefixes that cover more of the term
+                0.6 + 0.2 * token_len as f64 / term.chars().count() as f64
+            };
+            matches.push((postings.as_slice(), quality));
+        }
+        if max_distance > 0 {
+            // only consider terms starting with the first letter of the token, or with its second
+            // letter to allow for a swap of the first two letters
+            let mut first_chars = token.chars().take(2).collect::<Vec<_>>();
+            first_chars.dedup();
+            for first_char in first_chars {
+                let first_char = first_char.to_string();
+                for (term, postings) in self.terms.range(first_char.clone()..) {
+                    if !term.starts_with(&first_char) {
+                        break;
+                    }
+                    if term.starts_with(token) {
+                        continue;
+                    }
+                    let term_len = term.chars().count();
+                    if term_len.abs_diff(token_len) > max_distance {
+                        continue;
+                    }
+                    if let Some(distance) = edit_distance(token, term, max_distance) {
+                        matches.push((postings.as_slice(), 0.7 - 0.2 * (distance - 1) as f64));
+                    }
+                }
+            }
+        }
+        matches
+    }
+}
+
+/// Relative importance of an airport type, used as a tie-breaker when ranking.
+fn importance(airport_type: &AirportType) -> u8 {
+    match airport_type {
+        AirportType::LargeAirport => 3,
+        AirportType::MediumAirport => 2,
+        AirportType::SmallAirport | AirportType::SeaplaneBase => 1,
+        AirportType::Heliport | AirportType::BalloonPort | AirportType::ClosedAirport => 0,
+    }
+}
+
+/// Lowercases `text` and strips diacritics.
+fn normalize(text: &str) -> String {
+    let mut normalized = String::with_capacity(text.len());
+    for c in text.nfd().filter(|c| !is_combining_mark(*c)) {
+        // letters which do not decompose into a base letter and a combining mark
+        match c {
+            'ß' => normalized.push_str("ss"),
+            'æ' | 'Æ' => normalized.push_str("ae"),
+            'œ' | 'Œ' => normalized.push_str("oe"),
+            'þ' | 'Þ' => normalized.push_str("th"),
+            'ø' | 'Ø' => normalized.push('o'),
+            'ł' | 'Ł' => normalized.push('l'),
+            'đ' | 'Đ' | 'ð' | 'Ð' => normalized.push('d'),
+            'ı' => normalized.push('i'),
+            c => normalized.extend(c.to_lowercase()),
+        }
+    }
+    normalized
+}
+
+fn tokenize(text: &str) -> Vec<String> {
+    normalize(text)
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Optimal string alignment distance between `a` and `b` (Levenshtein distance which also counts
+/// transpositions of adjacent characters as a single edit), or `None` if it exceeds `max`.
+fn edit_distance(a: &str, b: &str, max: usize) -> Option<usize> {
+    let a = a.chars().collect::<Vec<_>>();
+    let b = b.chars().collect::<Vec<_>>();
+    let mut prev2 = vec![0; b.len() + 1];
+    let mut prev = (0..=b.len()).collect::<Vec<_>>();
+    let mut curr = vec![0; b.len() + 1];
+    for i in 1..=a.len() {
+        curr[0] = i;
+        let mut row_min = curr[0];
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            curr[j] = (prev[j] + 1).min(curr[j - 1] + 1).min(prev[j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                curr[j] = curr[j].min(prev2[j - 2] + 1);
+            }
+            row_min = row_min.min(curr[j]);
+        }
+        if row_min > max {
+            return None;
+        }
+        std::mem::swap(&mut prev2, &mut prev);
+        std::mem::swap(&mut prev, &mut curr);
+    }
+    Some(prev[b.len()]).filter(|d| *d <= max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{self, airport};
+
+    fn airports() -> BTreeMap<Id, Airport> {
+        let london = |id, ident, airport_type, name| {
+            airport(id, ident)
+                .set("type", airport_type)
+                .set("name", name)
+                .set("iso_country", "GB")
+                .set("iso_region", "GB-ENG")
+                .set("municipality", "London")
+        };
+        test_support::airports([
+            london(1, "EGLW", "heliport", "London Heliport"),
+            london(2, "EGLL", "large_airport", "London Heathrow Airport")
+                .set("iata_code", "LHR")
+                .set("keywords", "LON, Londres"),
+            london(3, "EGKK", "large_airport", "London Gatwick Airport")
+                .set("iata_code", "LGW")
+                .set("keywords", "LON"),
+            airport(4, "GB-0001")
+                .set("name", "Heathfield Strip")
+                .set("municipality", "Heathfield"),
+            airport(5, "EDDM")
+                .set("type", "large_airport")
+                .set("name", "München Flughafen")
+                .set("municipality", "München")
+                .set("iata_code", "MUC"),
+        ])
+    }
+
+    fn idents<'a>(results: &[SearchResult<'a>]) -> Vec<&'a str> {
+        results.iter().map(|r| r.airport().ident()).collect()
+    }
+
+    #[test]
+    fn codes_rank_above_names() {
+        let airports = airports();
+        let index = SearchIndex::new(&airports);
+        let results = index.search("lhr", 5);
+        assert_eq!(vec!["EGLL"], idents(&results));
+        assert_eq!(&[SearchField::IataCode], results[0].matched_fields());
+    }
+
+    #[test]
+    fn equal_scores_are_ordered_by_importance_then_id() {
+        let airports = airports();
+        let index = SearchIndex::new(&airports);
+        let results = index.search("london", 5);
+        assert_eq!(vec!["EGLL", "EGKK", "EGLW"], idents(&results));
+        assert!(results.windows(2).all(|w| w[0].score() == w[1].score()));
+    }
+
+    #[test]
+    fn longer_prefix_coverage_ranks_higher() {
+        let airports = airports();
+        let index = SearchIndex::new(&airports);
+        let results = index.search("heath", 5);
+        assert_eq!(vec!["EGLL", "GB-0001"], idents(&results));
+        assert!(results[0].score() > results[1].score());
+    }
+
+    #[test]
+    fn exact_match_ranks_above_typo() {
+        let airports = airports();
+        let index = SearchIndex::new(&airports);
+        let exact = index.search("gatwick", 1);
+        let typo = index.search("gatwikc", 1);
+        assert_eq!(vec!["EGKK"], idents(&exact));
+        assert_eq!(vec!["EGKK"], idents(&typo));
+        assert!(exact[0].score() > typo[0].score());
+    }
+
+    #[test]
+    fn typos_must_keep_the_first_letter() {
+        let airports = airports();
+        let index = SearchIndex::new(&airports);
+        assert_eq!(vec!["EGLL"], idents(&index.search("londn heathrw", 5)));
+        assert_eq!(vec!["EGKK"], idents(&index.search("agtwick", 5)));
+        assert!(index.search("xatwick", 5).is_empty());
+    }
+
+    #[test]
+    fn every_word_must_match() {
+        let airports = airports();
+        let index = SearchIndex::new(&airports);
+        let results = index.search("london lgw", 5);
+        assert_eq!(vec!["EGKK"], idents(&results));
+        assert_eq!(
+            &[SearchField::IataCode, SearchField::Name],
+            results[0].matched_fields()
+        );
+        assert!(index.search("london munich", 5).is_empty());
+    }
+
+    #[test]
+    fn diacritics_and_case_are_ignored() {
+        let airports = airports();
+        let index = SearchIndex::new(&airports);
+        assert_eq!(vec!["EDDM"], idents(&index.search("MUNCHEN", 5)));
+    }
+
+    #[test]
+    fn limit_truncates_results() {
+        let airports = airports();
+        let index = SearchIndex::new(&airports);
+        assert_eq!(vec!["EGLL"], idents(&index.search("london", 1)));
+        assert!(index.search("london", 0).is_empty());
+        assert!(index.search("  ", 5).is_empty());
+    }
+}
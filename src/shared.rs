@@ -0,0 +1,292 @@
+//! Contains a handle to a [`Dataset`] which can be replaced while it is being read, for
+//! long-running processes which need to refresh their data.
+//!
+//! Readers take a snapshot with [`SharedDataset::load()`], which never blocks. A refresh loads the
+//! new data in full before swapping it in, so readers see either the old or the new data, never a
+//! mix of both. Hooks registered with [`SharedDataset::on_refresh()`] receive the
+//! [`DatasetDiff`] between the old and the new data after each refresh.
+//!
+//! # Examples
+//! ```
+//! use std::sync::atomic::{AtomicUsize, Ordering};
+//! use std::sync::Arc;
+//! use ourairports::dataset::Dataset;
+//! use ourairports::shared::SharedDataset;
+//!
+//! let shared = SharedDataset::new(Dataset::default());
+//! let refreshes = Arc::new(AtomicUsize::new(0));
+//! let counter = refreshes.clone();
+//! shared.on_refresh(move |diff| {
+//!     assert!(diff.is_empty());
+//!     counter.fetch_add(1, Ordering::SeqCst);
+//! });
+//!
+//! let snapshot = shared.load();
+//! shared.store(Dataset::default());
+//! assert_eq!(1, refreshes.load(Ordering::SeqCst));
+//! // the old snapshot is still valid
+//! assert!(snapshot.airports().is_empty());
+//! ```
+//!
+//! Refreshing in the background:
+//! ```no_run
+//! use std::time::Duration;
+//! use ourairports::dataset::Dataset;
+//! use ourairports::shared::SharedDataset;
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!     let shared = SharedDataset::new(Dataset::read_dir("ourairports-data")?);
+//!     // reload when the files change, checking every minute
+//!     let _watcher = shared.watch_dir("ourairports-data", Duration::from_secs(60));
+//!     // or fetch from OurAirports once a day
+//!     let _fetcher = shared.refresh_periodically(Duration::from_secs(24 * 60 * 60));
+//! #    Ok(())
+//! # }
+//! ```
+
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, SystemTime};
+
+use arc_swap::ArcSwap;
+use log::{debug, info, warn};
+
+use crate::dataset::{Dataset, DatasetDiff, DatasetKind};
+use crate::FetchError;
+
+type RefreshHook = Arc<dyn Fn(&DatasetDiff) + Send + Sync>;
+
+/// A shared, atomically replaceable [`Dataset`].
+///
+/// Cloning the handle is cheap; all clones share the same data and hooks.
+#[derive(Clone)]
+pub struct SharedDataset {
+    current: Arc<ArcSwap<Dataset>>,
+    hooks: Arc<Mutex<Vec<RefreshHook>>>,
+    // serialises refreshes, so each diff is computed against the data it replaces
+    refresh_lock: Arc<Mutex<()>>,
+}
+
+impl fmt::Debug for SharedDataset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SharedDataset")
+            .field("current", &self.current)
+            .finish_non_exhaustive()
+    }
+}
+
+impl SharedDataset {
+    /// Creates a handle sharing `dataset`.
+    pub fn new(dataset: Dataset) -> Self {
+        SharedDataset {
+            current: Arc::new(ArcSwap::from_pointee(dataset)),
+            hooks: Arc::new(Mutex::new(vec![])),
+            refresh_lock: Arc::new(Mutex::new(())),
+        }
+    }
+
+    /// Returns a snapshot of the current data. Never blocks, even during a refresh.
+    ///
+    /// The snapshot is not affected by later refreshes; call `load()` again to see new data.
+    pub fn load(&self) -> Arc<Dataset> {
+        self.current.load_full()
+    }
+
+    /// Registers `hook` to be called with the differences between the old and the new data after
+    /// each refresh, on the thread performing the refresh.
+    pub fn on_refresh(&self, hook: impl Fn(&DatasetDiff) + Send + Sync + 'static) {
+        self.hooks
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(Arc::new(hook));
+    }
+
+    /// Replaces the current data with `dataset`, calls the refresh hooks and returns the
+    /// differences between the old and the new data.
+    ///
+    /// Hooks may call any method of the handle, including `store()`. If several threads store
+    /// data at the same time, their hooks may run concurrently and out of order.
+    pub fn store(&self, dataset: Dataset) -> DatasetDiff {
+        let guard = self.refresh_lock.lock().unwrap_or_else(|e| e.into_inner());
+        let old = self.current.swap(Arc::new(dataset));
+        let diff = old.diff(&self.current.load());
+        drop(guard);
+        // call the hooks without holding any lock, so they can store data or register hooks
+        let hooks = self.hooks.lock().unwrap_or_else(|e| e.into_inner()).clone();
+        for hook in hooks {
+            hook(&diff);
+        }
+        diff
+    }
+
+    /// Fetches the latest data from OurAirports and stores it. The current data is kept if the
+    /// fetch fails.
+    ///
+    /// # Errors
+    /// Returns [`FetchError`] if the data cannot be fetched or there's something wrong
+    /// with the de serialization process.
+    pub fn refresh_from_network(&self) -> Result<DatasetDiff, FetchError> {
+        Ok(self.store(Dataset::fetch()?))
+    }
+
+    /// Reads the data from CSV files in `dir` (see [`Dataset::read_dir()`]) and stores it. The
+    /// current data is kept if reading fails.
+    ///
+    /// # Errors
+    /// Returns [`FetchError`] if a file cannot be read or there's something wrong
+    /// with the de serialization process.
+    pub fn refresh_from_dir(&self, dir: impl AsRef<Path>) -> Result<DatasetDiff, FetchError> {
+        Ok(self.store(Dataset::read_dir(dir)?))
+    }
+
+    /// Starts a background thread which fetches the latest data from OurAirports every
+    /// `interval`. Failed fetches are logged and retried at the next interval.
+    ///
+    /// The thread stops when the returned [`RefreshHandle`] is dropped.
+    pub fn refresh_periodically(&self, interval: Duration) -> RefreshHandle {
+        let shared = self.clone();
+        RefreshHandle::spawn(interval, move || {
+            info!("refreshing data from OurAirports");
+            if let Err(e) = shared.refresh_from_network() {
+                warn!("failed to refresh data: {}", e);
+            }
+        })
+    }
+
+    /// Starts a background thread which checks the modification times of the CSV files in `dir`
+    /// every `poll_interval`, and reloads the data once any of them changed and all of them stayed
+    /// unchanged for a whole interval, so files which are still being written are not read.
+    /// Failed reloads are logged and retried at the next interval.
+    ///
+    /// The thread stops when the returned [`RefreshHandle`] is dropped.
+    pub fn watch_dir(&self, dir: impl Into<PathBuf>, poll_interval: Duration) -> RefreshHandle {
+        let shared = self.clone();
+        let dir = dir.into();
+        let mut loaded = modification_times(&dir);
+        // modification times seen at the previous check, if they differed from `loaded`
+        let mut changed = None;
+        RefreshHandle::spawn(poll_interval, move || {
+            let current = modification_times(&dir);
+            if current == loaded {
+                changed = None;
+                return;
+            }
+            if changed.as_ref() != Some(&current) {
+                debug!(
+                    "data in {} changed, waiting for writes to finish",
+                    dir.display()
+                );
+                changed = Some(current);
+                return;
+            }
+            info!("reloading data from {}", dir.display());
+            match shared.refresh_from_dir(&dir) {
+                Ok(_) => {
+                    loaded = current;
+                    changed = None;
+                }
+                Err(e) => warn!("failed to reload data: {}", e),
+            }
+        })
+    }
+}
+
+fn modification_times(dir: &Path) -> Vec<Option<SystemTime>> {
+    DatasetKind::ALL
+        .iter()
+        .map(|kind| {
+            fs::metadata(dir.join(kind.file_name()))
+                .and_then(|m| m.modified())
+                .ok()
+        })
+        .collect()
+}
+
+/// Handle to a background refresh thread started by [`SharedDataset`].
+///
+/// Dropping the handle stops the thread after its current refresh, without waiting for it.
+#[derive(Debug)]
+pub struct RefreshHandle {
+    stop: Option<Sender<()>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl RefreshHandle {
+    fn spawn(interval: Duration, mut refresh: impl FnMut() + Send + 'static) -> Self {
+        let (stop, stopped) = mpsc::channel();
+        let thread = thread::spawn(move || loop {
+            match stopped.recv_timeout(interval) {
+                Err(RecvTimeoutError::Timeout) => refresh(),
+                Ok(()) | Err(RecvTimeoutError::Disconnected) => {
+                    debug!("stopping refresh thread");
+                    return;
+                }
+            }
+        });
+        RefreshHandle {
+            stop: Some(stop),
+            thread: Some(thread),
+        }
+    }
+
+    /// Stops the background thread and waits for it to finish.
+    pub fn stop(mut self) {
+        self.stop.take();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Drop for RefreshHandle {
+    fn drop(&mut self) {
+        // disconnecting the channel stops the thread
+        self.stop.take();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    #[test]
+    fn hooks_can_register_hooks() {
+        let shared = SharedDataset::new(Dataset::default());
+        let calls = Arc::new(AtomicUsize::new(0));
+        let handle = shared.clone();
+        let counter = calls.clone();
+        shared.on_refresh(move |_| {
+            let counter = counter.clone();
+            handle.on_refresh(move |_| {
+                counter.fetch_add(1, Ordering::SeqCst);
+            });
+        });
+        shared.store(Dataset::default());
+        assert_eq!(0, calls.load(Ordering::SeqCst));
+        shared.store(Dataset::default());
+        assert_eq!(1, calls.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn hooks_can_load_and_store() {
+        let shared = SharedDataset::new(Dataset::default());
+        let calls = Arc::new(AtomicUsize::new(0));
+        let handle = shared.clone();
+        let counter = calls.clone();
+        shared.on_refresh(move |_| {
+            assert!(handle.load().airports().is_empty());
+            // store once more from the first call only
+            if counter.fetch_add(1, Ordering::SeqCst) == 0 {
+                handle.store(Dataset::default());
+            }
+        });
+        shared.store(Dataset::default());
+        assert_eq!(2, calls.load(Ordering::SeqCst));
+    }
+}
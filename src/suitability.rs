@@ -0,0 +1,381 @@
+//! Contains a check of which airports and runway ends are suitable for an aircraft, based on
+//! runway length, width, surface and lighting.
+//!
+//! Runway lengths required by an [`AircraftProfile`] are for sea level in standard conditions.
+//! They are increased by 7% per 1000 ft of density altitude at the airport, a rule of thumb
+//! close to the ICAO aerodrome design correction (7% per 300 m of elevation and 1% per °C above
+//! standard temperature). This is only an estimate; always use the aircraft flight manual for
+//! actual performance.
+//!
+//! # Examples
+//! ```no_run
+//! use ourairports::airports::*;
+//! use ourairports::geo::Coordinate;
+//! use ourairports::query::AirportIndex;
+//! use ourairports::runways::*;
+//! use ourairports::suitability::*;
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!     let airports = get_airports_csv()?;
+//!     let runways = get_runways_csv()?;
+//!     let index = AirportIndex::with_runways(&airports, &runways);
+//!
+//!     // diversion airports within 150 nm for a jet needing 6000 ft paved, at night, at 30°C
+//!     let profile = AircraftProfile::landplane(6000).min_width_ft(100).requires_lighting(true);
+//!     for (airport, distance) in index.within_radius(&Coordinate::new(51.47, -0.46), 150.0) {
+//!         let runways = index.runways(airport.id());
+//!         if let Some(suitable) = profile.check(airport, runways, Some(30.0)) {
+//!             for end in suitable.runway_ends() {
+//!                 println!("{} {:.0} nm: {}", airport.ident(), distance, end.end().ident());
+//!             }
+//!         }
+//!     }
+//! #    Ok(())
+//! # }
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+use crate::airports::{Airport, AirportType};
+use crate::query::AirportIndex;
+use crate::runways::{Runway, RunwayEnd};
+
+/// Increase in required runway length per 1000 ft of density altitude.
+const LENGTH_INCREASE_PER_1000_FT: f64 = 0.07;
+
+/// Class of runway surface, used to set separate length requirements.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum SurfaceClass {
+    /// Hard surface, see [`Runway::paved()`].
+    Paved,
+    /// Any other land runway, e.g. grass, gravel or dirt.
+    Unpaved,
+    /// Water runway, see [`Runway::water()`].
+    Water,
+    /// Helipad, see [`Runway::helipad()`].
+    Helipad,
+}
+
+impl SurfaceClass {
+    /// Returns the class of `runway`.
+    pub fn of(runway: &Runway) -> Self {
+        if runway.helipad() {
+            SurfaceClass::Helipad
+        } else if runway.water() {
+            SurfaceClass::Water
+        } else if runway.paved() {
+            SurfaceClass::Paved
+        } else {
+            SurfaceClass::Unpaved
+        }
+    }
+}
+
+/// Requirements of an aircraft for a runway.
+///
+/// A runway is only suitable if a minimum length is set for its [`SurfaceClass`]; e.g. a
+/// profile created with [`AircraftProfile::landplane()`] does not accept unpaved runways unless
+/// [`unpaved()`](Self::unpaved()) is also set.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Default)]
+pub struct AircraftProfile {
+    min_paved_length_ft: Option<i32>,
+    min_unpaved_length_ft: Option<i32>,
+    min_water_length_ft: Option<i32>,
+    min_helipad_length_ft: Option<i32>,
+    min_width_ft: Option<i32>,
+    requires_lighting: bool,
+}
+
+impl AircraftProfile {
+    /// Creates a profile which accepts no runways.
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Creates a profile for a landplane needing a paved runway of at least `min_length_ft`.
+    pub fn landplane(min_length_ft: i32) -> Self {
+        Self::new().surface(SurfaceClass::Paved, min_length_ft)
+    }
+    /// Creates a profile for a seaplane needing a water runway of at least `min_length_ft`.
+    pub fn seaplane(min_length_ft: i32) -> Self {
+        Self::new().surface(SurfaceClass::Water, min_length_ft)
+    }
+    /// Creates a profile for a helicopter, which can use any helipad or land runway.
+    pub fn helicopter() -> Self {
+        Self::new()
+            .surface(SurfaceClass::Helipad, 0)
+            .surface(SurfaceClass::Paved, 0)
+            .surface(SurfaceClass::Unpaved, 0)
+    }
+    /// Accepts runways of the given surface class which are at least `min_length_ft` long.
+    /// Runways with an unknown length are only accepted if `min_length_ft` is 0.
+    pub fn surface(mut self, class: SurfaceClass, min_length_ft: i32) -> Self {
+        *self.min_length_mut(class) = Some(min_length_ft);
+        self
+    }
+    /// Also accepts unpaved runways at least `min_length_ft` long.
+    pub fn unpaved(self, min_length_ft: i32) -> Self {
+        self.surface(SurfaceClass::Unpaved, min_length_ft)
+    }
+    /// Only accepts runways at least `width_ft` wide. Runways with an unknown width are not
+    /// accepted.
+    pub fn min_width_ft(mut self, width_ft: i32) -> Self {
+        self.min_width_ft = Some(width_ft);
+        self
+    }
+    /// Whether only lighted runways are accepted, e.g. for night operations.
+    pub fn requires_lighting(mut self, required: bool) -> Self {
+        self.requires_lighting = required;
+        self
+    }
+    /// Minimum length of runways of the given surface class in feet, at sea level in standard
+    /// conditions. `None` if the surface class is not accepted.
+    pub fn min_length_ft(&self, class: SurfaceClass) -> Option<i32> {
+        match class {
+            SurfaceClass::Paved => self.min_paved_length_ft,
+            SurfaceClass::Unpaved => self.min_unpaved_length_ft,
+            SurfaceClass::Water => self.min_water_length_ft,
+            SurfaceClass::Helipad => self.min_helipad_length_ft,
+        }
+    }
+    fn min_length_mut(&mut self, class: SurfaceClass) -> &mut Option<i32> {
+        match class {
+            SurfaceClass::Paved => &mut self.min_paved_length_ft,
+            SurfaceClass::Unpaved => &mut self.min_unpaved_length_ft,
+            SurfaceClass::Water => &mut self.min_water_length_ft,
+            SurfaceClass::Helipad => &mut self.min_helipad_length_ft,
+        }
+    }
+
+    /// Returns the runway ends of `airport` suitable for this aircraft, or `None` if there are
+    /// none or the airport is closed.
+    ///
+    /// `runways` are the runways of the airport, e.g. from [`AirportIndex::runways()`], and
+    /// `temperature_c` the outside air temperature; the standard temperature at the airport
+    /// elevation is used if `None`. Airports with an unknown elevation are treated as being at
+    /// sea level.
+    pub fn check<'a>(
+        &self,
+        airport: &'a Airport,
+        runways: &[&'a Runway],
+        temperature_c: Option<f64>,
+    ) -> Option<SuitableAirport<'a>> {
+        if airport.airport_type() == &AirportType::ClosedAirport {
+            return None;
+        }
+        let elevation_ft = f64::from(airport.elevation_ft().unwrap_or(0));
+        let density_altitude_ft = density_altitude_ft(
+            elevation_ft,
+            temperature_c.unwrap_or_else(|| isa_temperature_c(elevation_ft)),
+        );
+        let factor = length_correction_factor(density_altitude_ft);
+        let runway_ends = runways
+            .iter()
+            .filter(|runway| self.accepts(runway))
+            .filter_map(|runway| {
+                let required = self.min_length_ft(SurfaceClass::of(runway))?;
+                Some((runway, (f64::from(required) * factor).ceil() as i32))
+            })
+            .flat_map(|(runway, required_length_ft)| {
+                runway
+                    .ends()
+                    .into_iter()
+                    .filter(|end| !end.ident().trim().is_empty())
+                    .filter_map(move |end| {
+                        let available_length_ft = end
+                            .tora_ft()
+                            .zip(end.lda_ft())
+                            .map(|(tora, lda)| tora.min(lda));
+                        let long_enough = match available_length_ft {
+                            Some(available) => available >= required_length_ft,
+                            None => required_length_ft <= 0,
+                        };
+                        long_enough.then_some(SuitableRunwayEnd {
+                            end,
+                            available_length_ft,
+                            required_length_ft,
+                        })
+                    })
+            })
+            .collect::<Vec<_>>();
+        (!runway_ends.is_empty()).then_some(SuitableAirport {
+            airport,
+            density_altitude_ft,
+            runway_ends,
+        })
+    }
+
+    /// Returns every airport in `index` which has a runway end suitable for this aircraft, in
+    /// index order. See [`check()`](Self::check()).
+    pub fn suitable_airports<'a>(
+        &self,
+        index: &AirportIndex<'a>,
+        temperature_c: Option<f64>,
+    ) -> Vec<SuitableAirport<'a>> {
+        index
+            .airports()
+            .iter()
+            .filter_map(|airport| self.check(airport, index.runways(airport.id()), temperature_c))
+            .collect()
+    }
+
+    /// Whether `runway` meets the requirements other than length.
+    fn accepts(&self, runway: &Runway) -> bool {
+        !runway.closed()
+            && (!self.requires_lighting || runway.lighted())
+            && self
+                .min_width_ft
+                .is_none_or(|min| runway.width_ft().is_some_and(|w| w >= min))
+    }
+}
+
+/// An airport with the runway ends suitable for an aircraft.
+#[derive(Debug, Clone)]
+pub struct SuitableAirport<'a> {
+    airport: &'a Airport,
+    density_altitude_ft: f64,
+    runway_ends: Vec<SuitableRunwayEnd<'a>>,
+}
+
+impl<'a> SuitableAirport<'a> {
+    /// The airport.
+    pub fn airport(&self) -> &'a Airport {
+        self.airport
+    }
+    /// Density altitude at the airport in feet, used to correct the required runway lengths.
+    pub fn density_altitude_ft(&self) -> f64 {
+        self.density_altitude_ft
+    }
+    /// The suitable runway ends.
+    pub fn runway_ends(&self) -> &[SuitableRunwayEnd<'a>] {
+        &self.runway_ends
+    }
+}
+
+/// A runway end suitable for an aircraft.
+#[derive(Debug, Clone, Copy)]
+pub struct SuitableRunwayEnd<'a> {
+    end: RunwayEnd<'a>,
+    available_length_ft: Option<i32>,
+    required_length_ft: i32,
+}
+
+impl<'a> SuitableRunwayEnd<'a> {
+    /// The runway end.
+    pub fn end(&self) -> RunwayEnd<'a> {
+        self.end
+    }
+    /// Length available from this end in feet: the lower of the estimated
+    /// [TORA](RunwayEnd::tora_ft()) and [LDA](RunwayEnd::lda_ft()). Returns `None` if the
+    /// runway length is not available, which is only accepted when no minimum length is required.
+    pub fn available_length_ft(&self) -> Option<i32> {
+        self.available_length_ft
+    }
+    /// Length required by the aircraft in feet, after the density altitude correction.
+    pub fn required_length_ft(&self) -> i32 {
+        self.required_length_ft
+    }
+}
+
+/// Temperature in the International Standard Atmosphere at `altitude_ft`, in °C.
+pub fn isa_temperature_c(altitude_ft: f64) -> f64 {
+    15.0 - 1.98 * altitude_ft / 1000.0
+}
+
+/// Approximate density altitude in feet at `pressure_altitude_ft` with an outside air temperature
+/// of `temperature_c`.
+pub fn density_altitude_ft(pressure_altitude_ft: f64, temperature_c: f64) -> f64 {
+    pressure_altitude_ft + 118.8 * (temperature_c - isa_temperature_c(pressure_altitude_ft))
+}
+
+/// Factor by which the runway length required at sea level in standard conditions is multiplied
+/// at `density_altitude_ft`. Never less than 1.
+pub fn length_correction_factor(density_altitude_ft: f64) -> f64 {
+    1.0 + LENGTH_INCREASE_PER_1000_FT * density_altitude_ft.max(0.0) / 1000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{self, airport, runway};
+
+    #[test]
+    fn density_altitude_correction() {
+        assert_eq!(15.0, isa_temperature_c(0.0));
+        assert_eq!(0.0, density_altitude_ft(0.0, 15.0));
+        assert_eq!(1.0, length_correction_factor(0.0));
+        // never below the sea level requirement
+        assert_eq!(
+            1.0,
+            length_correction_factor(density_altitude_ft(0.0, -20.0))
+        );
+
+        // ISA at 5000 ft is 5.1 °C
+        let isa = isa_temperature_c(5000.0);
+        assert!((isa - 5.1).abs() < 1e-9);
+        let density_altitude = density_altitude_ft(5000.0, isa);
+        assert!((density_altitude - 5000.0).abs() < 1e-9);
+        assert!((length_correction_factor(density_altitude) - 1.35).abs() < 1e-9);
+
+        let density_altitude = density_altitude_ft(5000.0, isa + 20.0);
+        assert!((density_altitude - 7376.0).abs() < 1e-9);
+        assert!((length_correction_factor(density_altitude) - 1.51632).abs() < 1e-9);
+    }
+
+    #[test]
+    fn required_length_at_elevation() {
+        let airports = test_support::airports([
+            airport(1, "HIGH").set("elevation_ft", 5000),
+            airport(2, "GONE").set("type", "closed"),
+        ]);
+        let runways = test_support::runways([
+            runway(1, 1)
+                .set("length_ft", 7000)
+                .set("surface", "ASP")
+                .set("le_ident", "09")
+                .set("le_displaced_threshold_ft", 500)
+                .set("he_ident", "27"),
+            runway(2, 1)
+                .set("length_ft", 9000)
+                .set("surface", "GRASS")
+                .set("le_ident", "18")
+                .set("he_ident", "36"),
+            runway(3, 2).set("length_ft", 9000).set("surface", "ASP"),
+        ]);
+        let runway_refs = |id| {
+            runways
+                .values()
+                .filter(|r| r.airport_ref() == id)
+                .collect::<Vec<_>>()
+        };
+        let profile = AircraftProfile::landplane(5000);
+
+        // 6750 ft required in standard conditions, 09 only has 6500 ft available
+        let suitable = profile.check(&airports[&1], &runway_refs(1), None).unwrap();
+        assert!((suitable.density_altitude_ft() - 5000.0).abs() < 1e-9);
+        let ends = suitable.runway_ends();
+        assert_eq!(1, ends.len());
+        assert_eq!("27", ends[0].end().ident());
+        assert_eq!(Some(7000), ends[0].available_length_ft());
+        assert_eq!(6750, ends[0].required_length_ft());
+
+        // 7582 ft required at ISA+20
+        assert!(profile
+            .check(&airports[&1], &runway_refs(1), Some(25.1))
+            .is_none());
+        // the grass runway is long enough if accepted
+        let suitable = profile
+            .unpaved(5000)
+            .check(&airports[&1], &runway_refs(1), Some(25.1))
+            .unwrap();
+        let idents = suitable
+            .runway_ends()
+            .iter()
+            .map(|end| end.end().ident())
+            .collect::<Vec<_>>();
+        assert_eq!(vec!["18", "36"], idents);
+        assert!(AircraftProfile::helicopter()
+            .check(&airports[&2], &runway_refs(2), None)
+            .is_none());
+    }
+}
@@ -0,0 +1,255 @@
+//! Contains sunrise, sunset and twilight times, computed offline with the
+//! [NOAA solar calculator](https://gml.noaa.gov/grad/solcalc/calcdetails.html) algorithm, and
+//! whether night operations are possible at an airport.
+//!
+//! Times are in UTC and are for the solar day whose local noon is on the given (UTC) date, so the
+//! sunrise of airports far to the east or the sunset of airports far to the west may fall on a
+//! neighbouring UTC date. Times are for sea level and accurate to about a minute outside the
+//! polar regions. Near the poles the sun may not rise or set at all on a date, which is reported
+//! as [`SunEvents::AlwaysUp`] or [`SunEvents::AlwaysDown`].
+//!
+//! Night is the time between the end of evening civil twilight and the beginning of morning civil
+//! twilight, as used by ICAO and the FAA.
+//!
+//! # Examples
+//! ```
+//! use chrono::{NaiveDate, Timelike};
+//! use ourairports::geo::Coordinate;
+//! use ourairports::sun::*;
+//!
+//! // London on midsummer's day
+//! let date = NaiveDate::from_ymd_opt(2025, 6, 21).unwrap();
+//! let times = sun_times(&Coordinate::new(51.5, -0.1), date);
+//! let sunrise = times.sunrise().unwrap();
+//! assert_eq!((3, 43), (sunrise.hour(), sunrise.minute()));
+//! assert!(times.civil_twilight().set().unwrap() > times.sunset().unwrap());
+//!
+//! // the sun does not set in Tromsø in June, nor rise in December
+//! let tromso = Coordinate::new(69.68, 18.92);
+//! assert_eq!(SunEvents::AlwaysUp, sun_times(&tromso, date).sun());
+//! let december = NaiveDate::from_ymd_opt(2025, 12, 21).unwrap();
+//! assert_eq!(SunEvents::AlwaysDown, sun_times(&tromso, december).sun());
+//! ```
+
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::geo::Coordinate;
+use crate::runways::Runway;
+
+/// Julian date of the Unix epoch.
+const UNIX_EPOCH_JULIAN_DATE: f64 = 2_440_587.5;
+
+/// Julian date of the J2000.0 epoch.
+const J2000_JULIAN_DATE: f64 = 2_451_545.0;
+
+/// Solar elevation in degrees below which it is night.
+const NIGHT_ELEVATION_DEG: f64 = -6.0;
+
+/// The position of the sun marking the start or end of daylight or twilight.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum SunAngle {
+    /// Sunrise and sunset: the upper edge of the sun on the horizon, allowing for refraction.
+    Horizon,
+    /// Civil twilight: the centre of the sun 6° below the horizon.
+    Civil,
+    /// Nautical twilight: the centre of the sun 12° below the horizon.
+    Nautical,
+    /// Astronomical twilight: the centre of the sun 18° below the horizon.
+    Astronomical,
+}
+
+impl SunAngle {
+    /// Zenith angle of the centre of the sun in degrees.
+    pub fn zenith_deg(&self) -> f64 {
+        match self {
+            SunAngle::Horizon => 90.833,
+            SunAngle::Civil => 96.0,
+            SunAngle::Nautical => 102.0,
+            SunAngle::Astronomical => 108.0,
+        }
+    }
+}
+
+/// When the sun crosses a [`SunAngle`] on a day.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SunEvents {
+    /// The sun rises above the angle at `rise` and sets below it at `set`.
+    RisesAndSets {
+        rise: DateTime<Utc>,
+        set: DateTime<Utc>,
+    },
+    /// The sun stays above the angle all day, e.g. during polar day.
+    AlwaysUp,
+    /// The sun stays below the angle all day, e.g. during polar night.
+    AlwaysDown,
+}
+
+impl SunEvents {
+    /// Time the sun rises above the angle, or `None` if it does not cross it.
+    pub fn rise(&self) -> Option<DateTime<Utc>> {
+        match self {
+            SunEvents::RisesAndSets { rise, .. } => Some(*rise),
+            _ => None,
+        }
+    }
+    /// Time the sun sets below the angle, or `None` if it does not cross it.
+    pub fn set(&self) -> Option<DateTime<Utc>> {
+        match self {
+            SunEvents::RisesAndSets { set, .. } => Some(*set),
+            _ => None,
+        }
+    }
+}
+
+/// Sunrise, sunset and twilight times at a position on a date, as returned by [`sun_times()`].
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct SunTimes {
+    date: NaiveDate,
+    sun: SunEvents,
+    civil_twilight: SunEvents,
+    nautical_twilight: SunEvents,
+}
+
+impl SunTimes {
+    /// The date the times are for.
+    pub fn date(&self) -> NaiveDate {
+        self.date
+    }
+    /// Sunrise and sunset.
+    pub fn sun(&self) -> SunEvents {
+        self.sun
+    }
+    /// Beginning of morning and end of evening civil twilight.
+    pub fn civil_twilight(&self) -> SunEvents {
+        self.civil_twilight
+    }
+    /// Beginning of morning and end of evening nautical twilight.
+    pub fn nautical_twilight(&self) -> SunEvents {
+        self.nautical_twilight
+    }
+    /// Time of sunrise, or `None` if the sun does not rise or set.
+    pub fn sunrise(&self) -> Option<DateTime<Utc>> {
+        self.sun.rise()
+    }
+    /// Time of sunset, or `None` if the sun does not rise or set.
+    pub fn sunset(&self) -> Option<DateTime<Utc>> {
+        self.sun.set()
+    }
+    /// `true` if there is night on this date, i.e. the sun goes at least 6° below the horizon.
+    pub fn has_night(&self) -> bool {
+        self.civil_twilight != SunEvents::AlwaysUp
+    }
+}
+
+/// Returns the sunrise, sunset and twilight times at `coordinate` on `date`.
+pub fn sun_times(coordinate: &Coordinate, date: NaiveDate) -> SunTimes {
+    SunTimes {
+        date,
+        sun: sun_events(coordinate, date, SunAngle::Horizon),
+        civil_twilight: sun_events(coordinate, date, SunAngle::Civil),
+        nautical_twilight: sun_events(coordinate, date, SunAngle::Nautical),
+    }
+}
+
+/// Returns when the sun crosses `angle` at `coordinate` on `date`.
+pub fn sun_events(coordinate: &Coordinate, date: NaiveDate, angle: SunAngle) -> SunEvents {
+    let midnight = date.and_hms_opt(0, 0, 0).unwrap().and_utc();
+    // the first pass uses the position of the sun at local noon, the second its position at the
+    // time found in the first pass
+    let noon = midnight + minutes(720.0 - 4.0 * coordinate.longitude_deg());
+    let crossing = |time: DateTime<Utc>, rising: bool| -> Result<DateTime<Utc>, SunEvents> {
+        let (declination, equation_of_time) = solar_position(julian_date(time));
+        let hour_angle = hour_angle_deg(coordinate.latitude_deg(), declination, angle)?;
+        let solar_noon = 720.0 - 4.0 * coordinate.longitude_deg() - equation_of_time;
+        let offset = if rising { -hour_angle } else { hour_angle };
+        Ok(midnight + minutes(solar_noon + 4.0 * offset))
+    };
+    let refine = |rising: bool| crossing(noon, rising).and_then(|t| crossing(t, rising));
+    match (refine(true), refine(false)) {
+        (Ok(rise), Ok(set)) => SunEvents::RisesAndSets { rise, set },
+        (Err(events), _) | (_, Err(events)) => events,
+    }
+}
+
+/// Elevation of the centre of the sun above the horizon at `coordinate` at `time` in degrees,
+/// without refraction.
+pub fn solar_elevation_deg(coordinate: &Coordinate, time: DateTime<Utc>) -> f64 {
+    let (declination, equation_of_time) = solar_position(julian_date(time));
+    let minutes_of_day = (julian_date(time) + 0.5).rem_euclid(1.0) * 1440.0;
+    let true_solar_time = minutes_of_day + equation_of_time + 4.0 * coordinate.longitude_deg();
+    let hour_angle = (true_solar_time / 4.0 - 180.0).to_radians();
+    let latitude = coordinate.latitude_deg().to_radians();
+    let cos_zenith =
+        latitude.sin() * declination.sin() + latitude.cos() * declination.cos() * hour_angle.cos();
+    90.0 - cos_zenith.clamp(-1.0, 1.0).acos().to_degrees()
+}
+
+/// `true` if it is night at `coordinate` at `time`, i.e. the sun is more than 6° below the
+/// horizon.
+pub fn is_night(coordinate: &Coordinate, time: DateTime<Utc>) -> bool {
+    solar_elevation_deg(coordinate, time) < NIGHT_ELEVATION_DEG
+}
+
+/// `true` if any of `runways` can be used at night: it is lighted and not closed.
+pub fn night_operations_possible(runways: &[&Runway]) -> bool {
+    runways
+        .iter()
+        .any(|runway| runway.lighted() && !runway.closed())
+}
+
+/// Julian date of `time`.
+fn julian_date(time: DateTime<Utc>) -> f64 {
+    time.timestamp_millis() as f64 / 86_400_000.0 + UNIX_EPOCH_JULIAN_DATE
+}
+
+/// `minutes` (which may be fractional) as a duration, rounded to the second.
+fn minutes(minutes: f64) -> Duration {
+    Duration::seconds((minutes * 60.0).round() as i64)
+}
+
+/// Declination of the sun in radians and the equation of time in minutes at `julian_date`.
+fn solar_position(julian_date: f64) -> (f64, f64) {
+    let t = (julian_date - J2000_JULIAN_DATE) / 36525.0;
+    let mean_longitude = (280.46646 + t * (36000.76983 + t * 0.0003032)).rem_euclid(360.0);
+    let mean_anomaly = 357.52911 + t * (35999.05029 - 0.0001537 * t);
+    let eccentricity = 0.016708634 - t * (0.000042037 + 0.0000001267 * t);
+    let m = mean_anomaly.to_radians();
+    let centre = m.sin() * (1.914602 - t * (0.004817 + 0.000014 * t))
+        + (2.0 * m).sin() * (0.019993 - 0.000101 * t)
+        + (3.0 * m).sin() * 0.000289;
+    let omega = (125.04 - 1934.136 * t).to_radians();
+    let apparent_longitude =
+        (mean_longitude + centre - 0.00569 - 0.00478 * omega.sin()).to_radians();
+    let mean_obliquity =
+        23.0 + (26.0 + (21.448 - t * (46.815 + t * (0.00059 - t * 0.001813))) / 60.0) / 60.0;
+    let obliquity = (mean_obliquity + 0.00256 * omega.cos()).to_radians();
+    let declination = (obliquity.sin() * apparent_longitude.sin()).asin();
+
+    let y = (obliquity / 2.0).tan().powi(2);
+    let l0 = mean_longitude.to_radians();
+    let equation_of_time = 4.0
+        * (y * (2.0 * l0).sin() - 2.0 * eccentricity * m.sin()
+            + 4.0 * eccentricity * y * m.sin() * (2.0 * l0).cos()
+            - 0.5 * y * y * (4.0 * l0).sin()
+            - 1.25 * eccentricity * eccentricity * (2.0 * m).sin())
+        .to_degrees();
+    (declination, equation_of_time)
+}
+
+/// Hour angle in degrees at which the sun crosses `angle`, or the events if it does not.
+fn hour_angle_deg(latitude_deg: f64, declination: f64, angle: SunAngle) -> Result<f64, SunEvents> {
+    let latitude = latitude_deg.to_radians();
+    let cos_hour_angle = angle.zenith_deg().to_radians().cos()
+        / (latitude.cos() * declination.cos())
+        - latitude.tan() * declination.tan();
+    if cos_hour_angle > 1.0 {
+        Err(SunEvents::AlwaysDown)
+    } else if cos_hour_angle < -1.0 {
+        Err(SunEvents::AlwaysUp)
+    } else {
+        Ok(cos_hour_angle.acos().to_degrees())
+    }
+}
@@ -0,0 +1,250 @@
+//! Builders for small in-memory datasets used by the unit tests.
+//!
+//! Records are written as CSV with the column layout of the OurAirports files and read back with
+//! the regular readers, so tests exercise the same deserialization as real data.
+
+use std::collections::BTreeMap;
+
+use crate::airport_frequencies::{read_airport_frequencies_csv, AirportFrequency};
+use crate::airports::{read_airports_csv, Airport};
+use crate::countries::{read_countries_csv, Country};
+use crate::navaids::{read_navaids_csv, Navaid};
+use crate::regions::{read_regions_csv, Region};
+use crate::runways::{read_runways_csv, Runway};
+use crate::Id;
+
+const AIRPORTS_COLUMNS: &[&str] = &[
+    "id",
+    "ident",
+    "type",
+    "name",
+    "latitude_deg",
+    "longitude_deg",
+    "elevation_ft",
+    "continent",
+    "iso_country",
+    "iso_region",
+    "municipality",
+    "scheduled_service",
+    "gps_code",
+    "iata_code",
+    "local_code",
+    "home_link",
+    "wikipedia_link",
+    "keywords",
+];
+
+const RUNWAYS_COLUMNS: &[&str] = &[
+    "id",
+    "airport_ref",
+    "airport_ident",
+    "length_ft",
+    "width_ft",
+    "surface",
+    "lighted",
+    "closed",
+    "le_ident",
+    "le_latitude_deg",
+    "le_longitude_deg",
+    "le_elevation_ft",
+    "le_heading_degT",
+    "le_displaced_threshold_ft",
+    "he_ident",
+    "he_latitude_deg",
+    "he_longitude_deg",
+    "he_elevation_ft",
+    "he_heading_degT",
+    "he_displaced_threshold_ft",
+];
+
+const NAVAIDS_COLUMNS: &[&str] = &[
+    "id",
+    "filename",
+    "ident",
+    "name",
+    "type",
+    "frequency_khz",
+    "latitude_deg",
+    "longitude_deg",
+    "elevation_ft",
+    "iso_country",
+    "dme_frequency_khz",
+    "dme_channel",
+    "dme_latitude_deg",
+    "dme_longitude_deg",
+    "dme_elevation_ft",
+    "slaved_variation_deg",
+    "magnetic_variation_deg",
+    "usageType",
+    "power",
+    "associated_airport",
+];
+
+const AIRPORT_FREQUENCIES_COLUMNS: &[&str] = &[
+    "id",
+    "airport_ref",
+    "airport_ident",
+    "type",
+    "description",
+    "frequency_mhz",
+];
+
+const COUNTRIES_COLUMNS: &[&str] = &[
+    "id",
+    "code",
+    "name",
+    "continent",
+    "wikipedia_link",
+    "keywords",
+];
+
+const REGIONS_COLUMNS: &[&str] = &[
+    "id",
+    "code",
+    "local_code",
+    "name",
+    "continent",
+    "iso_country",
+    "wikipedia_link",
+    "keywords",
+];
+
+/// A single CSV record, with empty values for the columns which are not set.
+#[derive(Debug, Clone)]
+pub(crate) struct Row {
+    columns: &'static [&'static str],
+    values: Vec<String>,
+}
+
+impl Row {
+    fn new(columns: &'static [&'static str]) -> Self {
+        Row {
+            columns,
+            values: vec![String::new(); columns.len()],
+        }
+    }
+
+    /// Sets `column` to `value`. Panics if the record has no such column.
+    pub(crate) fn set(mut self, column: &str, value: impl ToString) -> Self {
+        let i = self
+            .columns
+            .iter()
+            .position(|c| *c == column)
+            .unwrap_or_else(|| panic!("no column \"{}\"", column));
+        self.values[i] = value.to_string();
+        self
+    }
+
+    /// Sets `latitude_deg` and `longitude_deg`.
+    pub(crate) fn position(self, latitude_deg: f64, longitude_deg: f64) -> Self {
+        self.set("latitude_deg", latitude_deg)
+            .set("longitude_deg", longitude_deg)
+    }
+}
+
+/// A small airport at 0°N 0°E in region "XX-01" on the European continent, without scheduled
+/// service.
+pub(crate) fn airport(id: Id, ident: &str) -> Row {
+    Row::new(AIRPORTS_COLUMNS)
+        .set("id", id)
+        .set("ident", ident)
+        .set("type", "small_airport")
+        .set("name", format!("{} Airport", ident))
+        .position(0.0, 0.0)
+        .set("continent", "EU")
+        .set("iso_country", "XX")
+        .set("iso_region", "XX-01")
+        .set("scheduled_service", "no")
+}
+
+/// An open, unlighted runway of the airport with ID `airport_ref`, without any other data.
+pub(crate) fn runway(id: Id, airport_ref: Id) -> Row {
+    Row::new(RUNWAYS_COLUMNS)
+        .set("id", id)
+        .set("airport_ref", airport_ref)
+        .set("lighted", 0)
+        .set("closed", 0)
+}
+
+/// A navaid of type `navaid_type` (e.g. "VOR-DME") at 0°N 0°E.
+pub(crate) fn navaid(id: Id, ident: &str, navaid_type: &str) -> Row {
+    Row::new(NAVAIDS_COLUMNS)
+        .set("id", id)
+        .set("ident", ident)
+        .set("name", ident)
+        .set("type", navaid_type)
+        .position(0.0, 0.0)
+        .set("iso_country", "XX")
+}
+
+/// A frequency of type `frequency_type` (e.g. "TWR") of the airport with ID `airport_ref`.
+pub(crate) fn airport_frequency(
+    id: Id,
+    airport_ref: Id,
+    frequency_type: &str,
+    frequency_mhz: &str,
+) -> Row {
+    Row::new(AIRPORT_FREQUENCIES_COLUMNS)
+        .set("id", id)
+        .set("airport_ref", airport_ref)
+        .set("type", frequency_type)
+        .set("frequency_mhz", frequency_mhz)
+}
+
+/// A country on `continent`, named after its code.
+pub(crate) fn country(id: Id, code: &str, continent: &str) -> Row {
+    Row::new(COUNTRIES_COLUMNS)
+        .set("id", id)
+        .set("code", code)
+        .set("name", code)
+        .set("continent", continent)
+}
+
+/// A region on `continent`, named after its code, in the country given by the code prefix.
+pub(crate) fn region(id: Id, code: &str, continent: &str) -> Row {
+    let (iso_country, local_code) = code.split_once('-').expect("region codes contain a dash");
+    Row::new(REGIONS_COLUMNS)
+        .set("id", id)
+        .set("code", code)
+        .set("local_code", local_code)
+        .set("name", code)
+        .set("continent", continent)
+        .set("iso_country", iso_country)
+}
+
+/// Writes `rows` as CSV, with a header line.
+fn to_csv(columns: &[&str], rows: impl IntoIterator<Item = Row>) -> Vec<u8> {
+    let mut writer = csv::Writer::from_writer(vec![]);
+    writer.write_record(columns).unwrap();
+    for row in rows {
+        assert_eq!(columns, row.columns, "record of another kind");
+        writer.write_record(&row.values).unwrap();
+    }
+    writer.into_inner().unwrap()
+}
+
+pub(crate) fn airports(rows: impl IntoIterator<Item = Row>) -> BTreeMap<Id, Airport> {
+    read_airports_csv(&to_csv(AIRPORTS_COLUMNS, rows)[..]).unwrap()
+}
+
+pub(crate) fn runways(rows: impl IntoIterator<Item = Row>) -> BTreeMap<Id, Runway> {
+    read_runways_csv(&to_csv(RUNWAYS_COLUMNS, rows)[..]).unwrap()
+}
+
+pub(crate) fn navaids(rows: impl IntoIterator<Item = Row>) -> BTreeMap<Id, Navaid> {
+    read_navaids_csv(&to_csv(NAVAIDS_COLUMNS, rows)[..]).unwrap()
+}
+
+pub(crate) fn airport_frequencies(
+    rows: impl IntoIterator<Item = Row>,
+) -> BTreeMap<Id, AirportFrequency> {
+    read_airport_frequencies_csv(&to_csv(AIRPORT_FREQUENCIES_COLUMNS, rows)[..]).unwrap()
+}
+
+pub(crate) fn countries(rows: impl IntoIterator<Item = Row>) -> BTreeMap<Id, Country> {
+    read_countries_csv(&to_csv(COUNTRIES_COLUMNS, rows)[..]).unwrap()
+}
+
+pub(crate) fn regions(rows: impl IntoIterator<Item = Row>) -> BTreeMap<Id, Region> {
+    read_regions_csv(&to_csv(REGIONS_COLUMNS, rows)[..]).unwrap()
+}
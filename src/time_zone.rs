@@ -0,0 +1,725 @@
+//! Contains an offline lookup of the [IANA time zone](https://www.iana.org/time-zones) of an
+//! airport, which OurAirports does not provide. Requires the `tz` feature.
+//!
+//! Zones are resolved from embedded, simplified boundary data: a rough extent and main zone for
+//! each country, and boxes around the zones of countries with more than one. Airports close to a
+//! zone boundary inside a country may be given the zone on the other side of it, and zones which
+//! only differ from their neighbour in historical rules are usually reported as that neighbour.
+//!
+//! An airport whose coordinates lie well outside the extent of its `iso_country` is flagged with
+//! [`TimeZoneMatch::conflicts_with_country()`], which usually indicates a mistake in either field.
+//! Its zone is then resolved from the coordinates alone.
+//!
+//! # Examples
+//! ```
+//! use chrono::{TimeZone, Timelike, Utc};
+//! use ourairports::geo::Coordinate;
+//! use ourairports::time_zone::*;
+//!
+//! // Los Angeles International Airport
+//! let lax = time_zone(&Coordinate::new(33.94, -118.41), "US").unwrap();
+//! assert_eq!("America/Los_Angeles", lax.name());
+//! assert!(!lax.conflicts_with_country());
+//! let noon = Utc.with_ymd_and_hms(2025, 1, 15, 20, 0, 0).unwrap();
+//! assert_eq!(12, lax.local_time(noon).hour());
+//!
+//! // coordinates in France for an airport said to be in Germany
+//! let paris = time_zone(&Coordinate::new(49.01, 2.55), "DE").unwrap();
+//! assert_eq!("Europe/Paris", paris.name());
+//! assert_eq!("FR", paris.country_code());
+//! assert!(paris.conflicts_with_country());
+//! ```
+
+use chrono::{DateTime, FixedOffset, Offset, Utc};
+use chrono_tz::Tz;
+use serde::Serialize;
+
+use crate::geo::{BoundingBox, Coordinate};
+
+/// How far in degrees coordinates may lie outside the extent of their country before they are
+/// flagged as a conflict.
+const CONFLICT_MARGIN_DEG: f64 = 1.0;
+
+/// An area of the embedded boundary data: country code, IANA zone name and the southern, western,
+/// northern and eastern edges in degrees.
+type Area = (&'static str, &'static str, f64, f64, f64, f64);
+
+/// How a [`TimeZoneMatch`] was resolved.
+#[derive(Serialize, Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum TimeZoneSource {
+    /// The coordinates lie within the boundary of the zone.
+    Coordinates,
+    /// The main zone of the country, used when the coordinates lie in none of its zone
+    /// boundaries.
+    Country,
+}
+
+/// The time zone of a position, as returned by [`time_zone()`].
+#[derive(Serialize, Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct TimeZoneMatch {
+    name: &'static str,
+    country_code: &'static str,
+    source: TimeZoneSource,
+    country_conflict: bool,
+}
+
+impl TimeZoneMatch {
+    /// IANA name of the zone, e.g. `Europe/London`.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+    /// The zone as a [`chrono_tz::Tz`].
+    pub fn tz(&self) -> Tz {
+        self.name
+            .parse()
+            .expect("embedded time zone names are valid")
+    }
+    /// ISO 3166:1-alpha2 code of the country the zone belongs to.
+    pub fn country_code(&self) -> &'static str {
+        self.country_code
+    }
+    /// How the zone was resolved.
+    pub fn source(&self) -> TimeZoneSource {
+        self.source
+    }
+    /// `true` if the coordinates lie outside the country they were given with, in which case the
+    /// zone is resolved from the coordinates.
+    ///
+    /// Countries are only known by rough boxes, so coordinates in a neighbouring country whose box
+    /// overlaps that of the given one, such as Chile and Argentina, the United States and Canada,
+    /// or Norway, Sweden and Finland, are never flagged. The zone of such a position is then
+    /// resolved within the given country.
+    pub fn conflicts_with_country(&self) -> bool {
+        self.country_conflict
+    }
+    /// Local time in the zone at `time`.
+    pub fn local_time(&self, time: DateTime<Utc>) -> DateTime<Tz> {
+        time.with_timezone(&self.tz())
+    }
+    /// Offset of local time from UTC at `time`.
+    pub fn utc_offset(&self, time: DateTime<Utc>) -> FixedOffset {
+        self.local_time(time).offset().fix()
+    }
+}
+
+/// Returns the time zone at `coordinate` in the country with ISO 3166:1-alpha2 code
+/// `iso_country`.
+///
+/// If the country is unknown the zone is resolved from `coordinate` alone, see
+/// [`time_zone_at()`]. Returns `None` if no zone is found.
+pub fn time_zone(coordinate: &Coordinate, iso_country: &str) -> Option<TimeZoneMatch> {
+    let mut extents = COUNTRY_EXTENTS
+        .iter()
+        .filter(|area| area.0 == iso_country)
+        .peekable();
+    let main_zone = match extents.peek() {
+        Some(area) => TimeZoneMatch {
+            source: TimeZoneSource::Country,
+            ..zone_match(area)
+        },
+        None => return time_zone_at(coordinate),
+    };
+    if extents.any(|area| bounding_box(area, CONFLICT_MARGIN_DEG).contains(coordinate)) {
+        Some(
+            ZONE_REGIONS
+                .iter()
+                .find(|area| area.0 == iso_country && bounding_box(area, 0.0).contains(coordinate))
+                .map_or(main_zone, zone_match),
+        )
+    } else {
+        Some(TimeZoneMatch {
+            country_conflict: true,
+            ..time_zone_at(coordinate).unwrap_or(main_zone)
+        })
+    }
+}
+
+/// Returns the time zone at `coordinate` without knowing its country, or `None` if it lies in
+/// no country, e.g. at sea.
+///
+/// Where country extents overlap the smallest country is used.
+pub fn time_zone_at(coordinate: &Coordinate) -> Option<TimeZoneMatch> {
+    let contains = |area: &&Area| bounding_box(area, 0.0).contains(coordinate);
+    ZONE_REGIONS
+        .iter()
+        .find(contains)
+        .map(zone_match)
+        .or_else(|| {
+            COUNTRY_EXTENTS
+                .iter()
+                .filter(contains)
+                .min_by(|a, b| area_size(a).total_cmp(&area_size(b)))
+                .map(zone_match)
+        })
+}
+
+/// Returns the IANA names of the time zones known for the country with ISO 3166:1-alpha2 code
+/// `iso_country`, starting with its main zone. Empty if the country is unknown.
+pub fn country_time_zones(iso_country: &str) -> Vec<&'static str> {
+    let mut zones: Vec<&'static str> = Vec::new();
+    for area in COUNTRY_EXTENTS.iter().chain(ZONE_REGIONS) {
+        if area.0 == iso_country && !zones.contains(&area.1) {
+            zones.push(area.1);
+        }
+    }
+    zones
+}
+
+/// The match for the zone of `area`, resolved from coordinates.
+fn zone_match(area: &Area) -> TimeZoneMatch {
+    TimeZoneMatch {
+        name: area.1,
+        country_code: area.0,
+        source: TimeZoneSource::Coordinates,
+        country_conflict: false,
+    }
+}
+
+/// The bounding box of `area`, widened by `margin_deg` on every side.
+fn bounding_box(area: &Area, margin_deg: f64) -> BoundingBox {
+    let &(_, _, south, west, north, east) = area;
+    BoundingBox::new(
+        (south - margin_deg).max(-90.0),
+        west - margin_deg,
+        (north + margin_deg).min(90.0),
+        east + margin_deg,
+    )
+}
+
+/// Size of `area` in square degrees.
+fn area_size(area: &Area) -> f64 {
+    let &(_, _, south, west, north, east) = area;
+    (north - south) * (east - west).rem_euclid(360.0)
+}
+
+/// Rough extent and main time zone of each country. A country may have several extents, the
+/// first of which gives its main zone.
+#[rustfmt::skip]
+const COUNTRY_EXTENTS: &[Area] = &[
+    ("AD", "Europe/Andorra", 42.4, 1.4, 42.7, 1.8),
+    ("AE", "Asia/Dubai", 22.6, 51.5, 26.1, 56.4),
+    ("AF", "Asia/Kabul", 29.4, 60.5, 38.5, 74.9),
+    ("AG", "America/Antigua", 16.9, -62.4, 17.8, -61.6),
+    ("AI", "America/Anguilla", 18.1, -63.5, 18.6, -62.9),
+    ("AL", "Europe/Tirane", 39.6, 19.2, 42.7, 21.1),
+    ("AM", "Asia/Yerevan", 38.8, 43.4, 41.3, 46.7),
+    ("AO", "Africa/Luanda", -18.1, 11.6, -4.3, 24.1),
+    ("AR", "America/Argentina/Buenos_Aires", -55.1, -73.6, -21.8, -53.6),
+    ("AS", "Pacific/Pago_Pago", -14.6, -171.1, -11.0, -168.1),
+    ("AT", "Europe/Vienna", 46.4, 9.5, 49.0, 17.2),
+    ("AU", "Australia/Sydney", -55.2, 112.9, -9.1, 159.2),
+    ("AW", "America/Aruba", 12.4, -70.1, 12.7, -69.8),
+    ("AX", "Europe/Mariehamn", 59.7, 19.5, 60.7, 21.3),
+    ("AZ", "Asia/Baku", 38.4, 44.8, 41.9, 50.4),
+    ("BA", "Europe/Sarajevo", 42.5, 15.7, 45.3, 19.7),
+    ("BB", "America/Barbados", 13.0, -59.7, 13.4, -59.4),
+    ("BD", "Asia/Dhaka", 20.6, 88.0, 26.7, 92.7),
+    ("BE", "Europe/Brussels", 49.5, 2.5, 51.5, 6.4),
+    ("BF", "Africa/Ouagadougou", 9.4, -5.5, 15.1, 2.4),
+    ("BG", "Europe/Sofia", 41.2, 22.3, 44.3, 28.7),
+    ("BH", "Asia/Bahrain", 25.5, 50.3, 26.4, 50.9),
+    ("BI", "Africa/Bujumbura", -4.5, 29.0, -2.3, 30.9),
+    ("BJ", "Africa/Porto-Novo", 6.2, 0.7, 12.5, 3.9),
+    ("BL", "America/St_Barthelemy", 17.8, -63.0, 18.0, -62.7),
+    ("BM", "Atlantic/Bermuda", 32.2, -65.0, 32.5, -64.6),
+    ("BN", "Asia/Brunei", 4.0, 114.0, 5.1, 115.4),
+    ("BO", "America/La_Paz", -22.9, -69.7, -9.7, -57.4),
+    ("BQ", "America/Kralendijk", 12.0, -68.5, 17.7, -62.9),
+    ("BR", "America/Sao_Paulo", -33.8, -74.0, 5.3, -28.8),
+    ("BS", "America/Nassau", 20.9, -79.6, 27.3, -72.7),
+    ("BT", "Asia/Thimphu", 26.7, 88.7, 28.4, 92.2),
+    ("BW", "Africa/Gaborone", -26.9, 19.9, -17.8, 29.4),
+    ("BY", "Europe/Minsk", 51.2, 23.1, 56.2, 32.8),
+    ("BZ", "America/Belize", 15.8, -89.3, 18.5, -87.4),
+    ("CA", "America/Toronto", 41.6, -141.1, 83.2, -52.6),
+    ("CC", "Indian/Cocos", -12.3, 96.8, -11.8, 97.0),
+    ("CD", "Africa/Lubumbashi", -13.5, 12.2, 5.4, 31.4),
+    ("CF", "Africa/Bangui", 2.2, 14.4, 11.0, 27.5),
+    ("CG", "Africa/Brazzaville", -5.1, 11.1, 3.7, 18.7),
+    ("CH", "Europe/Zurich", 45.8, 5.9, 47.8, 10.5),
+    ("CI", "Africa/Abidjan", 4.3, -8.6, 10.8, -2.5),
+    ("CK", "Pacific/Rarotonga", -22.0, -166.0, -8.9, -157.3),
+    ("CL", "America/Santiago", -56.0, -109.5, -17.5, -66.4),
+    ("CM", "Africa/Douala", 1.6, 8.4, 13.1, 16.2),
+    ("CN", "Asia/Shanghai", 18.1, 73.5, 53.6, 134.8),
+    ("CO", "America/Bogota", -4.3, -81.8, 13.4, -66.8),
+    ("CR", "America/Costa_Rica", 5.5, -87.1, 11.3, -82.5),
+    ("CU", "America/Havana", 19.8, -85.0, 23.3, -74.1),
+    ("CV", "Atlantic/Cape_Verde", 14.8, -25.4, 17.2, -22.6),
+    ("CW", "America/Curacao", 12.0, -69.2, 12.4, -68.7),
+    ("CX", "Indian/Christmas", -10.6, 105.5, -10.4, 105.8),
+    ("CY", "Asia/Nicosia", 34.5, 32.2, 35.7, 34.6),
+    ("CZ", "Europe/Prague", 48.5, 12.0, 51.1, 18.9),
+    ("DE", "Europe/Berlin", 47.2, 5.8, 55.1, 15.1),
+    ("DJ", "Africa/Djibouti", 10.9, 41.7, 12.8, 43.5),
+    ("DK", "Europe/Copenhagen", 54.5, 8.0, 57.8, 15.2),
+    ("DM", "America/Dominica", 15.2, -61.5, 15.7, -61.2),
+    ("DO", "America/Santo_Domingo", 17.5, -72.1, 20.0, -68.3),
+    ("DZ", "Africa/Algiers", 18.9, -8.7, 37.1, 12.0),
+    ("EC", "America/Guayaquil", -5.1, -92.1, 1.7, -75.2),
+    ("EE", "Europe/Tallinn", 57.5, 21.7, 59.7, 28.3),
+    ("EG", "Africa/Cairo", 21.9, 24.7, 31.7, 36.9),
+    ("EH", "Africa/El_Aaiun", 20.7, -17.2, 27.7, -8.6),
+    ("ER", "Africa/Asmara", 12.3, 36.4, 18.0, 43.2),
+    ("ES", "Europe/Madrid", 27.6, -18.2, 43.8, 4.4),
+    ("ET", "Africa/Addis_Ababa", 3.4, 33.0, 15.0, 48.0),
+    ("FI", "Europe/Helsinki", 59.7, 20.5, 70.1, 31.6),
+    ("FJ", "Pacific/Fiji", -21.0, 176.8, -12.4, -178.2),
+    ("FK", "Atlantic/Stanley", -52.5, -61.4, -51.2, -57.7),
+    ("FM", "Pacific/Pohnpei", 0.9, 137.3, 10.1, 163.1),
+    ("FO", "Atlantic/Faroe", 61.4, -7.7, 62.4, -6.3),
+    ("FR", "Europe/Paris", 41.3, -5.2, 51.1, 9.6),
+    ("GA", "Africa/Libreville", -4.0, 8.7, 2.3, 14.5),
+    ("GB", "Europe/London", 49.8, -8.7, 60.9, 1.8),
+    ("GD", "America/Grenada", 11.9, -61.8, 12.6, -61.4),
+    ("GE", "Asia/Tbilisi", 41.0, 40.0, 43.6, 46.7),
+    ("GF", "America/Cayenne", 2.1, -54.6, 5.8, -51.6),
+    ("GG", "Europe/Guernsey", 49.4, -2.7, 49.8, -2.2),
+    ("GH", "Africa/Accra", 4.7, -3.3, 11.2, 1.2),
+    ("GI", "Europe/Gibraltar", 36.1, -5.4, 36.2, -5.3),
+    ("GL", "America/Nuuk", 59.7, -73.1, 83.7, -11.3),
+    ("GM", "Africa/Banjul", 13.0, -16.9, 13.9, -13.8),
+    ("GN", "Africa/Conakry", 7.2, -15.1, 12.7, -7.6),
+    ("GP", "America/Guadeloupe", 15.8, -61.9, 16.6, -61.0),
+    ("GQ", "Africa/Malabo", -1.5, 5.6, 3.8, 11.4),
+    ("GR", "Europe/Athens", 34.8, 19.3, 41.8, 29.7),
+    ("GS", "Atlantic/South_Georgia", -59.5, -38.1, -53.9, -26.2),
+    ("GT", "America/Guatemala", 13.7, -92.3, 17.9, -88.2),
+    ("GU", "Pacific/Guam", 13.2, 144.6, 13.7, 145.0),
+    ("GW", "Africa/Bissau", 10.9, -16.8, 12.7, -13.6),
+    ("GY", "America/Guyana", 1.2, -61.4, 8.6, -56.5),
+    ("HK", "Asia/Hong_Kong", 22.1, 113.8, 22.6, 114.5),
+    ("HN", "America/Tegucigalpa", 12.9, -89.4, 17.5, -83.1),
+    ("HR", "Europe/Zagreb", 42.4, 13.4, 46.6, 19.5),
+    ("HT", "America/Port-au-Prince", 18.0, -74.5, 20.1, -71.6),
+    ("HU", "Europe/Budapest", 45.7, 16.1, 48.6, 22.9),
+    ("ID", "Asia/Jakarta", -11.0, 95.0, 6.1, 141.1),
+    ("IE", "Europe/Dublin", 51.4, -10.7, 55.4, -5.9),
+    ("IL", "Asia/Jerusalem", 29.5, 34.2, 33.4, 35.9),
+    ("IM", "Europe/Isle_of_Man", 54.0, -4.8, 54.4, -4.3),
+    ("IN", "Asia/Kolkata", 6.7, 68.1, 35.7, 97.4),
+    ("IO", "Indian/Chagos", -7.5, 71.2, -5.2, 72.5),
+    ("IQ", "Asia/Baghdad", 29.0, 38.8, 37.4, 48.6),
+    ("IR", "Asia/Tehran", 25.0, 44.0, 39.8, 63.3),
+    ("IS", "Atlantic/Reykjavik", 63.3, -24.6, 66.6, -13.5),
+    ("IT", "Europe/Rome", 35.5, 6.6, 47.1, 18.5),
+    ("JE", "Europe/Jersey", 49.1, -2.3, 49.3, -2.0),
+    ("JM", "America/Jamaica", 17.7, -78.4, 18.6, -76.2),
+    ("JO", "Asia/Amman", 29.2, 34.9, 33.4, 39.3),
+    ("JP", "Asia/Tokyo", 20.4, 122.9, 45.6, 154.0),
+    ("KE", "Africa/Nairobi", -4.7, 33.9, 5.0, 41.9),
+    ("KG", "Asia/Bishkek", 39.2, 69.2, 43.3, 80.3),
+    ("KH", "Asia/Phnom_Penh", 10.4, 102.3, 14.7, 107.7),
+    ("KI", "Pacific/Tarawa", -11.5, 169.5, 4.7, -150.2),
+    ("KM", "Indian/Comoro", -12.5, 43.2, -11.3, 44.6),
+    ("KN", "America/St_Kitts", 17.1, -62.9, 17.5, -62.5),
+    ("KP", "Asia/Pyongyang", 37.7, 124.2, 43.0, 130.7),
+    ("KR", "Asia/Seoul", 33.1, 124.6, 38.7, 131.9),
+    ("KW", "Asia/Kuwait", 28.5, 46.5, 30.1, 48.5),
+    ("KY", "America/Cayman", 19.2, -81.5, 19.8, -79.7),
+    ("KZ", "Asia/Almaty", 40.6, 46.5, 55.5, 87.4),
+    ("LA", "Asia/Vientiane", 13.9, 100.1, 22.5, 107.7),
+    ("LB", "Asia/Beirut", 33.0, 35.1, 34.7, 36.7),
+    ("LC", "America/St_Lucia", 13.7, -61.1, 14.2, -60.8),
+    ("LI", "Europe/Vaduz", 47.0, 9.4, 47.3, 9.7),
+    ("LK", "Asia/Colombo", 5.9, 79.5, 9.9, 81.9),
+    ("LR", "Africa/Monrovia", 4.3, -11.5, 8.6, -7.4),
+    ("LS", "Africa/Maseru", -30.7, 27.0, -28.5, 29.5),
+    ("LT", "Europe/Vilnius", 53.9, 20.9, 56.5, 26.9),
+    ("LU", "Europe/Luxembourg", 49.4, 5.7, 50.2, 6.6),
+    ("LV", "Europe/Riga", 55.7, 20.9, 58.1, 28.3),
+    ("LY", "Africa/Tripoli", 19.5, 9.3, 33.2, 25.2),
+    ("MA", "Africa/Casablanca", 27.6, -13.2, 35.9, -1.0),
+    ("MC", "Europe/Monaco", 43.7, 7.4, 43.8, 7.5),
+    ("MD", "Europe/Chisinau", 45.4, 26.6, 48.5, 30.2),
+    ("ME", "Europe/Podgorica", 41.8, 18.4, 43.6, 20.4),
+    ("MF", "America/Marigot", 18.0, -63.2, 18.2, -63.0),
+    ("MG", "Indian/Antananarivo", -25.7, 43.2, -11.9, 50.5),
+    ("MH", "Pacific/Majuro", 4.5, 160.8, 14.7, 172.2),
+    ("MK", "Europe/Skopje", 40.8, 20.4, 42.4, 23.1),
+    ("ML", "Africa/Bamako", 10.1, -12.3, 25.0, 4.3),
+    ("MM", "Asia/Yangon", 9.6, 92.1, 28.6, 101.2),
+    ("MN", "Asia/Ulaanbaatar", 41.5, 87.7, 52.2, 119.9),
+    ("MO", "Asia/Macau", 22.1, 113.5, 22.3, 113.6),
+    ("MP", "Pacific/Saipan", 14.1, 145.1, 20.6, 146.1),
+    ("MQ", "America/Martinique", 14.3, -61.3, 14.9, -60.8),
+    ("MR", "Africa/Nouakchott", 14.7, -17.1, 27.3, -4.8),
+    ("MS", "America/Montserrat", 16.6, -62.3, 16.9, -62.1),
+    ("MT", "Europe/Malta", 35.8, 14.1, 36.1, 14.6),
+    ("MU", "Indian/Mauritius", -20.6, 56.5, -10.3, 63.6),
+    ("MV", "Indian/Maldives", -0.7, 72.6, 7.1, 73.8),
+    ("MW", "Africa/Blantyre", -17.2, 32.6, -9.3, 35.9),
+    ("MX", "America/Mexico_City", 14.5, -118.4, 32.8, -86.7),
+    ("MY", "Asia/Kuala_Lumpur", 0.8, 99.6, 7.4, 119.3),
+    ("MZ", "Africa/Maputo", -26.9, 30.2, -10.4, 40.9),
+    ("NA", "Africa/Windhoek", -29.0, 11.7, -16.9, 25.3),
+    ("NC", "Pacific/Noumea", -22.7, 163.5, -19.5, 168.2),
+    ("NE", "Africa/Niamey", 11.6, 0.1, 23.6, 16.0),
+    ("NF", "Pacific/Norfolk", -29.2, 167.9, -28.9, 168.1),
+    ("NG", "Africa/Lagos", 4.2, 2.6, 13.9, 14.7),
+    ("NI", "America/Managua", 10.7, -87.7, 15.1, -82.6),
+    ("NL", "Europe/Amsterdam", 50.7, 3.3, 53.6, 7.3),
+    ("NO", "Europe/Oslo", 57.9, 4.5, 71.2, 31.2),
+    ("NP", "Asia/Kathmandu", 26.3, 80.0, 30.5, 88.3),
+    ("NR", "Pacific/Nauru", -0.6, 166.9, -0.5, 167.0),
+    ("NU", "Pacific/Niue", -19.2, -170.0, -18.9, -169.7),
+    ("NZ", "Pacific/Auckland", -52.7, 165.8, -29.2, -176.1),
+    ("OM", "Asia/Muscat", 16.6, 52.0, 26.5, 59.9),
+    ("PA", "America/Panama", 7.2, -83.1, 9.7, -77.1),
+    ("PE", "America/Lima", -18.4, -81.4, 0.0, -68.6),
+    ("PF", "Pacific/Tahiti", -27.7, -154.8, -7.8, -134.4),
+    ("PG", "Pacific/Port_Moresby", -11.7, 140.8, -0.8, 159.5),
+    ("PH", "Asia/Manila", 4.5, 116.9, 21.2, 126.7),
+    ("PK", "Asia/Karachi", 23.6, 60.8, 37.1, 77.9),
+    ("PL", "Europe/Warsaw", 49.0, 14.1, 54.9, 24.2),
+    ("PM", "America/Miquelon", 46.7, -56.5, 47.2, -56.1),
+    ("PN", "Pacific/Pitcairn", -25.1, -130.8, -23.9, -124.7),
+    ("PR", "America/Puerto_Rico", 17.8, -68.0, 18.6, -65.2),
+    ("PS", "Asia/Gaza", 31.2, 34.2, 32.6, 35.6),
+    ("PT", "Europe/Lisbon", 30.0, -31.3, 42.2, -6.2),
+    ("PW", "Pacific/Palau", 2.8, 131.1, 8.2, 134.8),
+    ("PY", "America/Asuncion", -27.6, -62.7, -19.3, -54.3),
+    ("QA", "Asia/Qatar", 24.5, 50.7, 26.2, 51.7),
+    ("RE", "Indian/Reunion", -21.4, 55.2, -20.9, 55.9),
+    ("RO", "Europe/Bucharest", 43.6, 20.2, 48.3, 29.7),
+    ("RS", "Europe/Belgrade", 42.2, 18.8, 46.2, 23.0),
+    ("RU", "Europe/Moscow", 41.2, 19.6, 81.9, -169.0),
+    ("RW", "Africa/Kigali", -2.9, 28.8, -1.0, 30.9),
+    ("SA", "Asia/Riyadh", 16.3, 34.5, 32.2, 55.7),
+    ("SB", "Pacific/Guadalcanal", -12.4, 155.5, -5.0, 170.2),
+    ("SC", "Indian/Mahe", -10.3, 46.2, -3.7, 56.3),
+    ("SD", "Africa/Khartoum", 8.6, 21.8, 22.3, 38.6),
+    ("SE", "Europe/Stockholm", 55.3, 11.0, 69.1, 24.2),
+    ("SG", "Asia/Singapore", 1.2, 103.6, 1.5, 104.1),
+    ("SH", "Atlantic/St_Helena", -40.4, -14.5, -7.8, -5.6),
+    ("SI", "Europe/Ljubljana", 45.4, 13.3, 46.9, 16.6),
+    ("SJ", "Arctic/Longyearbyen", 70.8, -9.1, 80.9, 33.6),
+    ("SK", "Europe/Bratislava", 47.7, 16.8, 49.6, 22.6),
+    ("SL", "Africa/Freetown", 6.9, -13.4, 10.0, -10.2),
+    ("SM", "Europe/San_Marino", 43.9, 12.4, 44.0, 12.5),
+    ("SN", "Africa/Dakar", 12.3, -17.6, 16.7, -11.3),
+    ("SO", "Africa/Mogadishu", -1.7, 40.9, 12.0, 51.5),
+    ("SR", "America/Paramaribo", 1.8, -58.1, 6.0, -53.9),
+    ("SS", "Africa/Juba", 3.4, 23.4, 12.3, 36.0),
+    ("ST", "Africa/Sao_Tome", 0.0, 6.4, 1.8, 7.5),
+    ("SV", "America/El_Salvador", 13.1, -90.2, 14.5, -87.6),
+    ("SX", "America/Lower_Princes", 18.0, -63.2, 18.1, -63.0),
+    ("SY", "Asia/Damascus", 32.3, 35.7, 37.4, 42.4),
+    ("SZ", "Africa/Mbabane", -27.4, 30.8, -25.7, 32.2),
+    ("TC", "America/Grand_Turk", 21.1, -72.5, 22.0, -71.1),
+    ("TD", "Africa/Ndjamena", 7.4, 13.4, 23.5, 24.0),
+    ("TF", "Indian/Kerguelen", -49.8, 39.7, -11.5, 77.6),
+    ("TG", "Africa/Lome", 6.1, -0.2, 11.2, 1.9),
+    ("TH", "Asia/Bangkok", 5.6, 97.3, 20.5, 105.7),
+    ("TJ", "Asia/Dushanbe", 36.7, 67.3, 41.1, 75.2),
+    ("TK", "Pacific/Fakaofo", -9.5, -172.6, -8.5, -171.1),
+    ("TL", "Asia/Dili", -9.5, 124.0, -8.1, 127.4),
+    ("TM", "Asia/Ashgabat", 35.1, 52.4, 42.8, 66.7),
+    ("TN", "Africa/Tunis", 30.2, 7.5, 37.6, 11.6),
+    ("TO", "Pacific/Tongatapu", -22.4, -176.3, -15.5, -173.7),
+    ("TR", "Europe/Istanbul", 35.8, 25.6, 42.1, 44.8),
+    ("TT", "America/Port_of_Spain", 10.0, -61.9, 11.4, -60.5),
+    ("TV", "Pacific/Funafuti", -10.8, 176.0, -5.6, 179.9),
+    ("TW", "Asia/Taipei", 21.9, 118.2, 26.4, 122.1),
+    ("TZ", "Africa/Dar_es_Salaam", -11.8, 29.3, -0.9, 40.5),
+    ("UA", "Europe/Kyiv", 44.3, 22.1, 52.4, 40.2),
+    ("UG", "Africa/Kampala", -1.5, 29.5, 4.3, 35.0),
+    ("UM", "Pacific/Wake", -0.5, 166.0, 28.5, -160.0),
+    ("UM", "America/Port-au-Prince", 18.3, -75.1, 18.5, -74.9),
+    ("US", "America/New_York", 24.4, -125.0, 49.5, -66.9),
+    ("US", "America/New_York", 51.2, 172.4, 71.5, -129.9),
+    ("US", "America/New_York", 18.9, -178.4, 28.5, -154.8),
+    ("UY", "America/Montevideo", -35.0, -58.5, -30.0, -53.1),
+    ("UZ", "Asia/Tashkent", 37.1, 55.9, 45.6, 73.2),
+    ("VA", "Europe/Vatican", 41.9, 12.4, 41.9, 12.5),
+    ("VC", "America/St_Vincent", 12.5, -61.5, 13.4, -61.1),
+    ("VE", "America/Caracas", 0.6, -73.4, 15.7, -59.8),
+    ("VG", "America/Tortola", 18.3, -64.9, 18.8, -64.2),
+    ("VI", "America/St_Thomas", 17.6, -65.1, 18.5, -64.5),
+    ("VN", "Asia/Ho_Chi_Minh", 8.4, 102.1, 23.4, 109.5),
+    ("VU", "Pacific/Efate", -20.3, 166.5, -13.0, 170.3),
+    ("WF", "Pacific/Wallis", -14.4, -178.2, -13.2, -176.1),
+    ("WS", "Pacific/Apia", -14.1, -172.8, -13.4, -171.4),
+    ("XK", "Europe/Belgrade", 41.8, 20.0, 43.3, 21.8),
+    ("YE", "Asia/Aden", 12.1, 42.5, 19.0, 54.6),
+    ("YT", "Indian/Mayotte", -13.1, 45.0, -12.6, 45.3),
+    ("ZA", "Africa/Johannesburg", -47.0, 16.4, -22.1, 38.0),
+    ("ZM", "Africa/Lusaka", -18.1, 21.9, -8.2, 33.7),
+    ("ZW", "Africa/Harare", -22.5, 25.2, -15.6, 33.1),
+];
+
+/// Boxes around the zones of countries with more than one zone. Within a country the first box
+/// containing a position gives its zone; positions in none of them use the main zone of the
+/// country.
+#[rustfmt::skip]
+const ZONE_REGIONS: &[Area] = &[
+    ("AU", "Australia/Lord_Howe", -31.7, 158.9, -31.4, 159.3),
+    ("AU", "Australia/Hobart", -43.7, 143.8, -39.5, 148.5),
+    ("AU", "Australia/Melbourne", -39.2, 140.9, -35.9, 150.0),
+    ("AU", "Australia/Melbourne", -35.9, 140.9, -33.9, 143.5),
+    ("AU", "Australia/Brisbane", -28.2, 138.0, -9.0, 154.0),
+    ("AU", "Australia/Brisbane", -29.0, 138.0, -28.2, 152.0),
+    ("AU", "Australia/Broken_Hill", -32.5, 141.0, -31.0, 142.0),
+    ("AU", "Australia/Adelaide", -38.1, 129.0, -26.0, 141.0),
+    ("AU", "Australia/Darwin", -26.0, 129.0, -10.9, 138.0),
+    ("AU", "Australia/Perth", -35.2, 112.9, -13.7, 129.0),
+    ("BR", "America/Noronha", -4.0, -32.6, -3.7, -32.3),
+    ("BR", "America/Rio_Branco", -11.2, -74.0, -7.1, -66.6),
+    ("BR", "America/Boa_Vista", -1.6, -64.9, 5.3, -58.8),
+    ("BR", "America/Porto_Velho", -13.7, -66.8, -7.9, -59.8),
+    ("BR", "America/Manaus", -9.9, -73.9, 2.3, -56.6),
+    ("BR", "America/Campo_Grande", -24.1, -58.2, -17.2, -50.9),
+    ("BR", "America/Cuiaba", -18.1, -61.7, -7.3, -50.2),
+    ("CA", "America/St_Johns", 46.5, -59.5, 51.8, -52.5),
+    ("CA", "America/Goose_Bay", 51.5, -67.5, 60.5, -55.6),
+    ("CA", "America/Halifax", 43.3, -69.1, 48.1, -59.6),
+    ("CA", "America/Toronto", 41.6, -90.0, 63.0, -57.0),
+    ("CA", "America/Winnipeg", 48.9, -101.4, 60.0, -90.0),
+    ("CA", "America/Regina", 49.0, -110.0, 60.0, -101.4),
+    ("CA", "America/Cambridge_Bay", 65.5, -120.0, 79.0, -102.0),
+    ("CA", "America/Rankin_Inlet", 56.0, -102.0, 70.0, -89.0),
+    ("CA", "America/Iqaluit", 60.0, -90.0, 83.5, -60.0),
+    ("CA", "America/Whitehorse", 60.0, -141.1, 69.7, -134.5),
+    ("CA", "America/Whitehorse", 60.0, -134.5, 63.0, -124.0),
+    ("CA", "America/Edmonton", 49.0, -118.5, 53.0, -110.0),
+    ("CA", "America/Edmonton", 53.0, -120.0, 60.0, -110.0),
+    ("CA", "America/Edmonton", 60.0, -136.5, 79.0, -102.0),
+    ("CA", "America/Vancouver", 48.2, -139.1, 60.0, -114.0),
+    ("CD", "Africa/Kinshasa", -7.5, 12.2, 5.4, 20.5),
+    ("CL", "Pacific/Easter", -27.3, -109.6, -26.9, -109.1),
+    ("CL", "America/Punta_Arenas", -56.0, -76.0, -48.6, -66.0),
+    ("EC", "Pacific/Galapagos", -1.5, -92.1, 1.7, -89.0),
+    ("ES", "Atlantic/Canary", 27.5, -18.3, 29.5, -13.3),
+    ("FM", "Pacific/Chuuk", 0.9, 137.0, 12.0, 153.0),
+    ("FM", "Pacific/Kosrae", 4.5, 162.5, 6.0, 163.5),
+    ("GL", "America/Danmarkshavn", 76.0, -19.5, 77.5, -17.5),
+    ("GL", "America/Scoresbysund", 70.0, -23.5, 71.0, -21.0),
+    ("GL", "America/Thule", 76.0, -70.0, 77.8, -66.0),
+    ("ID", "Asia/Pontianak", -3.1, 108.5, 2.1, 114.3),
+    ("ID", "Asia/Makassar", -11.0, 114.45, 7.5, 125.8),
+    ("ID", "Asia/Jayapura", -11.0, 125.8, 5.0, 141.1),
+    ("KI", "Pacific/Kanton", -5.0, -175.0, -2.5, -170.0),
+    ("KI", "Pacific/Kiritimati", -12.0, -162.0, 5.0, -150.0),
+    ("MN", "Asia/Hovd", 44.5, 87.7, 52.2, 96.5),
+    ("MX", "America/Tijuana", 28.0, -117.2, 32.8, -112.6),
+    ("MX", "America/Mazatlan", 22.8, -115.0, 26.5, -109.4),
+    ("MX", "America/Mazatlan", 26.5, -115.0, 28.0, -111.5),
+    ("MX", "America/Hermosillo", 26.3, -115.0, 32.5, -108.4),
+    ("MX", "America/Ciudad_Juarez", 30.5, -109.0, 31.8, -104.5),
+    ("MX", "America/Chihuahua", 25.5, -109.0, 31.8, -103.3),
+    ("MX", "America/Mazatlan", 21.0, -109.4, 27.0, -105.4),
+    ("MX", "America/Mazatlan", 21.0, -105.4, 22.8, -104.3),
+    ("MX", "America/Cancun", 18.4, -89.5, 21.7, -86.7),
+    ("MY", "Asia/Kuching", 0.8, 109.5, 7.5, 119.5),
+    ("NZ", "Pacific/Chatham", -44.5, -177.0, -43.5, -176.0),
+    ("PF", "Pacific/Marquesas", -11.0, -141.0, -7.5, -138.0),
+    ("PF", "Pacific/Gambier", -23.5, -135.5, -22.5, -134.4),
+    ("PG", "Pacific/Bougainville", -7.0, 154.0, -4.5, 156.5),
+    ("PS", "Asia/Hebron", 31.3, 34.8, 32.6, 35.6),
+    ("PT", "Atlantic/Azores", 36.8, -31.5, 39.8, -24.9),
+    ("PT", "Atlantic/Madeira", 30.0, -17.4, 33.2, -15.8),
+    ("RU", "Europe/Kaliningrad", 54.3, 19.6, 55.3, 22.9),
+    ("RU", "Europe/Samara", 51.8, 49.5, 54.7, 52.6),
+    ("RU", "Europe/Samara", 56.0, 51.2, 58.6, 54.5),
+    ("RU", "Europe/Ulyanovsk", 53.3, 45.8, 55.0, 49.5),
+    ("RU", "Europe/Saratov", 49.8, 42.5, 53.2, 50.9),
+    ("RU", "Europe/Astrakhan", 45.5, 44.9, 48.9, 49.5),
+    ("RU", "Asia/Omsk", 53.3, 70.3, 58.6, 76.3),
+    ("RU", "Asia/Novosibirsk", 51.0, 75.0, 60.0, 89.5),
+    ("RU", "Asia/Yekaterinburg", 51.0, 51.5, 54.5, 61.5),
+    ("RU", "Asia/Yekaterinburg", 54.5, 53.7, 56.0, 61.5),
+    ("RU", "Asia/Yekaterinburg", 56.0, 54.5, 61.6, 61.5),
+    ("RU", "Asia/Yekaterinburg", 55.0, 61.5, 65.0, 86.0),
+    ("RU", "Asia/Yekaterinburg", 65.0, 66.0, 82.0, 86.0),
+    ("RU", "Asia/Chita", 49.8, 108.5, 56.5, 122.2),
+    ("RU", "Asia/Irkutsk", 50.5, 96.0, 57.0, 119.0),
+    ("RU", "Asia/Irkutsk", 57.0, 100.5, 59.0, 119.0),
+    ("RU", "Asia/Irkutsk", 59.0, 104.0, 64.0, 110.0),
+    ("RU", "Asia/Krasnoyarsk", 50.5, 84.0, 82.0, 106.0),
+    ("RU", "Asia/Sakhalin", 45.8, 141.6, 54.5, 144.8),
+    ("RU", "Asia/Sakhalin", 43.5, 145.3, 51.0, 156.7),
+    ("RU", "Asia/Magadan", 58.5, 145.0, 66.0, 158.5),
+    ("RU", "Asia/Kamchatka", 50.5, 155.0, 62.5, 174.0),
+    ("RU", "Asia/Anadyr", 62.0, 158.5, 72.0, 180.0),
+    ("RU", "Asia/Anadyr", 64.0, -180.0, 70.0, -168.9),
+    ("RU", "Asia/Srednekolymsk", 64.0, 141.0, 77.0, 162.0),
+    ("RU", "Asia/Vladivostok", 42.3, 130.7, 45.0, 140.0),
+    ("RU", "Asia/Vladivostok", 45.0, 133.3, 48.3, 140.0),
+    ("RU", "Asia/Vladivostok", 48.3, 134.5, 62.5, 147.5),
+    ("RU", "Asia/Vladivostok", 48.3, 130.5, 49.5, 134.5),
+    ("RU", "Asia/Yakutsk", 55.5, 105.0, 77.0, 141.0),
+    ("RU", "Asia/Yakutsk", 53.4, 119.6, 55.5, 125.5),
+    ("RU", "Asia/Yakutsk", 50.3, 125.5, 55.5, 130.5),
+    ("UM", "Pacific/Midway", 28.1, -177.5, 28.3, -177.3),
+    ("UM", "Pacific/Honolulu", 16.6, -169.7, 16.8, -169.4),
+    ("UM", "America/Port-au-Prince", 18.3, -75.1, 18.5, -74.9),
+    ("US", "America/Adak", 51.0, 172.0, 53.0, -169.0),
+    ("US", "America/Anchorage", 51.0, -169.0, 71.5, -129.9),
+    ("US", "Pacific/Honolulu", 18.5, -178.5, 28.5, -154.5),
+    ("US", "America/Phoenix", 31.3, -114.8, 37.0, -109.05),
+    ("US", "America/Boise", 42.0, -117.3, 45.5, -111.0),
+    ("US", "America/New_York", 24.4, -85.0, 31.0, -79.9),
+    ("US", "America/Indiana/Indianapolis", 38.2, -87.2, 41.77, -84.8),
+    ("US", "America/Detroit", 41.7, -86.6, 45.8, -82.4),
+    ("US", "America/Detroit", 45.8, -87.8, 47.5, -82.4),
+    ("US", "America/Kentucky/Louisville", 36.5, -86.0, 39.2, -82.0),
+    ("US", "America/New_York", 31.0, -85.6, 47.5, -66.9),
+    ("US", "America/Chicago", 25.8, -103.05, 37.0, -100.9),
+    ("US", "America/Chicago", 28.9, -105.0, 32.0, -103.05),
+    ("US", "America/Denver", 44.3, -116.05, 49.1, -104.0),
+    ("US", "America/Los_Angeles", 32.5, -124.9, 49.1, -114.05),
+    ("US", "America/Denver", 31.3, -117.1, 49.1, -101.0),
+    ("US", "America/Chicago", 25.8, -104.1, 49.5, -84.8),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn navassa_island_uses_haitian_time() {
+        let navassa = Coordinate::new(18.4, -75.0);
+        let zone = time_zone(&navassa, "UM").unwrap();
+        assert_eq!("America/Port-au-Prince", zone.name());
+        assert!(!zone.conflicts_with_country());
+        assert_eq!(
+            Some("America/Port-au-Prince"),
+            time_zone_at(&navassa).map(|z| z.name())
+        );
+    }
+
+    /// Asserts that `airport` at `latitude`, `longitude` in `iso_country` is in `zone`, resolved
+    /// from `source`, without a conflict.
+    fn assert_zone(
+        airport: &str,
+        latitude: f64,
+        longitude: f64,
+        iso_country: &str,
+        zone: &str,
+        source: TimeZoneSource,
+    ) {
+        let found = time_zone(&Coordinate::new(latitude, longitude), iso_country).unwrap();
+        assert_eq!(zone, found.name(), "{}", airport);
+        assert_eq!(iso_country, found.country_code(), "{}", airport);
+        assert_eq!(source, found.source(), "{}", airport);
+        assert!(!found.conflicts_with_country(), "{}", airport);
+    }
+
+    #[test]
+    fn zones_within_countries() {
+        use TimeZoneSource::*;
+        let airports = [
+            ("KATL", 33.64, -84.43, "US", "America/New_York", Coordinates),
+            ("KORD", 41.98, -87.90, "US", "America/Chicago", Coordinates),
+            ("KDEN", 39.86, -104.67, "US", "America/Denver", Coordinates),
+            ("KPHX", 33.43, -112.01, "US", "America/Phoenix", Coordinates),
+            (
+                "KLAX",
+                33.94,
+                -118.41,
+                "US",
+                "America/Los_Angeles",
+                Coordinates,
+            ),
+            (
+                "PANC",
+                61.17,
+                -150.00,
+                "US",
+                "America/Anchorage",
+                Coordinates,
+            ),
+            (
+                "PHNL",
+                21.32,
+                -157.92,
+                "US",
+                "Pacific/Honolulu",
+                Coordinates,
+            ),
+            ("YSSY", -33.95, 151.18, "AU", "Australia/Sydney", Country),
+            (
+                "YBBN",
+                -27.38,
+                153.12,
+                "AU",
+                "Australia/Brisbane",
+                Coordinates,
+            ),
+            (
+                "YMHB",
+                -42.84,
+                147.51,
+                "AU",
+                "Australia/Hobart",
+                Coordinates,
+            ),
+            (
+                "YPAD",
+                -34.95,
+                138.53,
+                "AU",
+                "Australia/Adelaide",
+                Coordinates,
+            ),
+            (
+                "YPDN",
+                -12.41,
+                130.88,
+                "AU",
+                "Australia/Darwin",
+                Coordinates,
+            ),
+            ("YPPH", -31.94, 115.97, "AU", "Australia/Perth", Coordinates),
+            ("UUEE", 55.97, 37.41, "RU", "Europe/Moscow", Country),
+            (
+                "UMKK",
+                54.89,
+                20.59,
+                "RU",
+                "Europe/Kaliningrad",
+                Coordinates,
+            ),
+            ("UNNT", 55.01, 82.65, "RU", "Asia/Novosibirsk", Coordinates),
+            ("UEEE", 62.09, 129.77, "RU", "Asia/Yakutsk", Coordinates),
+            ("UHWW", 43.40, 132.15, "RU", "Asia/Vladivostok", Coordinates),
+            ("UHPP", 53.17, 158.45, "RU", "Asia/Kamchatka", Coordinates),
+            ("SBGR", -23.43, -46.47, "BR", "America/Sao_Paulo", Country),
+            ("SBFN", -3.85, -32.42, "BR", "America/Noronha", Coordinates),
+            ("SBEG", -3.04, -60.05, "BR", "America/Manaus", Coordinates),
+            (
+                "SBRB",
+                -9.87,
+                -67.90,
+                "BR",
+                "America/Rio_Branco",
+                Coordinates,
+            ),
+            ("SBCY", -15.65, -56.12, "BR", "America/Cuiaba", Coordinates),
+        ];
+        for (airport, latitude, longitude, iso_country, zone, source) in airports {
+            assert_zone(airport, latitude, longitude, iso_country, zone, source);
+        }
+    }
+
+    #[test]
+    fn conflicting_countries() {
+        // Mexico City International Airport said to be in the United States
+        let mmmx = time_zone(&Coordinate::new(19.44, -99.07), "US").unwrap();
+        assert!(mmmx.conflicts_with_country());
+        assert_eq!("America/Mexico_City", mmmx.name());
+        assert_eq!("MX", mmmx.country_code());
+        assert_eq!(TimeZoneSource::Coordinates, mmmx.source());
+
+        // John F. Kennedy International Airport with the sign of its longitude lost
+        let kjfk = time_zone(&Coordinate::new(40.64, 73.78), "US").unwrap();
+        assert!(kjfk.conflicts_with_country());
+        assert_ne!("US", kjfk.country_code());
+
+        // Santiago in Chile lies within the box of Argentina, so it is not flagged
+        let scel = time_zone(&Coordinate::new(-33.39, -70.79), "AR").unwrap();
+        assert!(!scel.conflicts_with_country());
+        assert_eq!("America/Argentina/Buenos_Aires", scel.name());
+    }
+}
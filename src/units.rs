@@ -0,0 +1,289 @@
+//! Contains length and elevation types which carry their unit, so that values in feet, metres,
+//! nautical miles and kilometres cannot be mixed up.
+//!
+//! A [`Length`] or [`Elevation`] keeps its value in the unit it was created with, so values from
+//! the source data (which are whole feet) are exact, and is only converted when another unit is
+//! asked for. The conversion factors are the exact international definitions: 1 ft = 0.3048 m
+//! and 1 NM = 1852 m. Both types are serialized with their value and unit, e.g.
+//! `{"value":202.0,"unit":"ft"}`.
+//!
+//! # Examples
+//! ```
+//! use ourairports::units::*;
+//!
+//! let length = Length::feet(12001.0);
+//! assert_eq!(3658.0, length.to_metres().round());
+//! assert_eq!(12001.0, length.convert_to(LengthUnit::Metres).to_feet().round());
+//! assert_eq!("3658 m", format!("{:.0}", length.convert_to(LengthUnit::Metres)));
+//! assert!(Length::metres(1000.0) > Length::feet(3000.0));
+//!
+//! let elevation = Elevation::feet(202.0);
+//! assert_eq!(Length::feet(102.0), elevation - Elevation::feet(100.0));
+//! assert_eq!(
+//!     r#"{"value":202.0,"unit":"ft"}"#,
+//!     serde_json::to_string(&elevation).unwrap()
+//! );
+//! ```
+
+use std::cmp::Ordering;
+use std::fmt::{self, Display, Formatter};
+use std::ops::{Add, Div, Mul, Sub};
+
+use serde::{Deserialize, Serialize};
+
+/// Number of metres in a foot.
+pub const METRES_PER_FOOT: f64 = 0.3048;
+
+/// Number of metres in a nautical mile.
+pub const METRES_PER_NM: f64 = 1852.0;
+
+/// Number of metres in a kilometre.
+pub const METRES_PER_KM: f64 = 1000.0;
+
+/// Unit of a [`Length`] or [`Elevation`].
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum LengthUnit {
+    #[serde(rename = "ft")]
+    Feet,
+    #[serde(rename = "m")]
+    Metres,
+    #[serde(rename = "NM")]
+    NauticalMiles,
+    #[serde(rename = "km")]
+    Kilometres,
+}
+
+impl LengthUnit {
+    /// Number of metres in one of this unit.
+    pub fn metres_per_unit(&self) -> f64 {
+        match self {
+            LengthUnit::Feet => METRES_PER_FOOT,
+            LengthUnit::Metres => 1.0,
+            LengthUnit::NauticalMiles => METRES_PER_NM,
+            LengthUnit::Kilometres => METRES_PER_KM,
+        }
+    }
+    /// The symbol of the unit, e.g. "ft" or "NM".
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            LengthUnit::Feet => "ft",
+            LengthUnit::Metres => "m",
+            LengthUnit::NauticalMiles => "NM",
+            LengthUnit::Kilometres => "km",
+        }
+    }
+}
+
+impl Display for LengthUnit {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(self.symbol())
+    }
+}
+
+/// A length or distance with its unit.
+///
+/// Lengths in different units compare equal if they are the same length in metres.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy)]
+pub struct Length {
+    value: f64,
+    unit: LengthUnit,
+}
+
+impl Length {
+    /// A length of `value` in `unit`.
+    pub fn new(value: f64, unit: LengthUnit) -> Self {
+        Length { value, unit }
+    }
+    /// A length in feet.
+    pub fn feet(value: f64) -> Self {
+        Length::new(value, LengthUnit::Feet)
+    }
+    /// A length in metres.
+    pub fn metres(value: f64) -> Self {
+        Length::new(value, LengthUnit::Metres)
+    }
+    /// A length in nautical miles.
+    pub fn nautical_miles(value: f64) -> Self {
+        Length::new(value, LengthUnit::NauticalMiles)
+    }
+    /// A length in kilometres.
+    pub fn kilometres(value: f64) -> Self {
+        Length::new(value, LengthUnit::Kilometres)
+    }
+    /// The value in [`Self::unit()`].
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+    /// The unit the length was created with.
+    pub fn unit(&self) -> LengthUnit {
+        self.unit
+    }
+    /// The value in `unit`. Returns the value unchanged if it is already in `unit`.
+    pub fn in_unit(&self, unit: LengthUnit) -> f64 {
+        if unit == self.unit {
+            self.value
+        } else {
+            self.value * self.unit.metres_per_unit() / unit.metres_per_unit()
+        }
+    }
+    /// The same length in `unit`.
+    pub fn convert_to(&self, unit: LengthUnit) -> Length {
+        Length::new(self.in_unit(unit), unit)
+    }
+    /// The value in feet.
+    pub fn to_feet(&self) -> f64 {
+        self.in_unit(LengthUnit::Feet)
+    }
+    /// The value in metres.
+    pub fn to_metres(&self) -> f64 {
+        self.in_unit(LengthUnit::Metres)
+    }
+    /// The value in nautical miles.
+    pub fn to_nautical_miles(&self) -> f64 {
+        self.in_unit(LengthUnit::NauticalMiles)
+    }
+    /// The value in kilometres.
+    pub fn to_kilometres(&self) -> f64 {
+        self.in_unit(LengthUnit::Kilometres)
+    }
+}
+
+impl PartialEq for Length {
+    fn eq(&self, other: &Self) -> bool {
+        self.to_metres() == other.to_metres()
+    }
+}
+
+impl PartialOrd for Length {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.to_metres().partial_cmp(&other.to_metres())
+    }
+}
+
+/// Adds `rhs` in the unit of `self`.
+impl Add for Length {
+    type Output = Length;
+
+    fn add(self, rhs: Length) -> Length {
+        Length::new(self.value + rhs.in_unit(self.unit), self.unit)
+    }
+}
+
+/// Subtracts `rhs` in the unit of `self`.
+impl Sub for Length {
+    type Output = Length;
+
+    fn sub(self, rhs: Length) -> Length {
+        Length::new(self.value - rhs.in_unit(self.unit), self.unit)
+    }
+}
+
+impl Mul<f64> for Length {
+    type Output = Length;
+
+    fn mul(self, rhs: f64) -> Length {
+        Length::new(self.value * rhs, self.unit)
+    }
+}
+
+impl Div<f64> for Length {
+    type Output = Length;
+
+    fn div(self, rhs: f64) -> Length {
+        Length::new(self.value / rhs, self.unit)
+    }
+}
+
+/// Formats the length as its value and unit symbol, e.g. "202 ft". The precision, if given,
+/// applies to the value.
+impl Display for Length {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match f.precision() {
+            Some(precision) => write!(f, "{:.*} {}", precision, self.value, self.unit),
+            None => write!(f, "{} {}", self.value, self.unit),
+        }
+    }
+}
+
+/// An elevation above mean sea level (negative for below) with its unit.
+///
+/// Elevations in different units compare equal if they are the same elevation in metres.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, PartialOrd)]
+#[serde(transparent)]
+pub struct Elevation(Length);
+
+impl Elevation {
+    /// An elevation of `value` in `unit`.
+    pub fn new(value: f64, unit: LengthUnit) -> Self {
+        Elevation(Length::new(value, unit))
+    }
+    /// An elevation in feet.
+    pub fn feet(value: f64) -> Self {
+        Elevation(Length::feet(value))
+    }
+    /// An elevation in metres.
+    pub fn metres(value: f64) -> Self {
+        Elevation(Length::metres(value))
+    }
+    /// The value in [`Self::unit()`].
+    pub fn value(&self) -> f64 {
+        self.0.value()
+    }
+    /// The unit the elevation was created with.
+    pub fn unit(&self) -> LengthUnit {
+        self.0.unit()
+    }
+    /// The height above mean sea level (negative for below).
+    pub fn above_msl(&self) -> Length {
+        self.0
+    }
+    /// The value in `unit`. Returns the value unchanged if it is already in `unit`.
+    pub fn in_unit(&self, unit: LengthUnit) -> f64 {
+        self.0.in_unit(unit)
+    }
+    /// The same elevation in `unit`.
+    pub fn convert_to(&self, unit: LengthUnit) -> Elevation {
+        Elevation(self.0.convert_to(unit))
+    }
+    /// The value in feet.
+    pub fn to_feet(&self) -> f64 {
+        self.0.to_feet()
+    }
+    /// The value in metres.
+    pub fn to_metres(&self) -> f64 {
+        self.0.to_metres()
+    }
+}
+
+/// The height difference between two elevations, in the unit of `self`.
+impl Sub for Elevation {
+    type Output = Length;
+
+    fn sub(self, rhs: Elevation) -> Length {
+        self.0 - rhs.0
+    }
+}
+
+impl Add<Length> for Elevation {
+    type Output = Elevation;
+
+    fn add(self, rhs: Length) -> Elevation {
+        Elevation(self.0 + rhs)
+    }
+}
+
+impl Sub<Length> for Elevation {
+    type Output = Elevation;
+
+    fn sub(self, rhs: Length) -> Elevation {
+        Elevation(self.0 - rhs)
+    }
+}
+
+/// Formats the elevation as its value and unit symbol, e.g. "202 ft". The precision, if given,
+/// applies to the value.
+impl Display for Elevation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Display::fmt(&self.0, f)
+    }
+}
@@ -0,0 +1,267 @@
+//! Contains headwind and crosswind calculations for runways, and selection of the runway ends
+//! best aligned with the wind.
+//!
+//! Wind directions are the direction the wind is blowing *from*, in degrees true, and speeds are
+//! in knots. Runway ends without a known true heading are not considered.
+//!
+//! # Examples
+//! ```
+//! use ourairports::wind::*;
+//!
+//! // wind from 300° at 20 kt on a runway heading 270°
+//! let components = Wind::new(300.0, 20.0).components(270.0);
+//! assert_eq!(17.3, (components.headwind_kt() * 10.0).round() / 10.0);
+//! assert_eq!(10.0, (components.crosswind_kt() * 10.0).round() / 10.0);
+//! ```
+//!
+//! Choosing a runway at an airport:
+//! ```no_run
+//! use ourairports::runways::*;
+//! use ourairports::wind::*;
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!     let runways = get_runways_csv()?;
+//!     let limits = WindLimits::new().max_crosswind_kt(15.0).max_tailwind_kt(5.0);
+//!     let ranked = usable_runway_ends(
+//!         runways.values().filter(|r| r.airport_ident() == "EGLL"),
+//!         Wind::new(250.0, 12.0),
+//!         &limits,
+//!     );
+//!     // 09L/27R is the longer of the two runways
+//!     assert_eq!("27R", ranked[0].end().ident());
+//! #    Ok(())
+//! # }
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+use crate::runways::{Runway, RunwayEnd};
+
+/// Surface wind, blowing from `direction_deg_true` at `speed_kt`.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+pub struct Wind {
+    direction_deg_true: f64,
+    speed_kt: f64,
+}
+
+impl Wind {
+    /// Creates a wind blowing from `direction_deg_true` (degrees true) at `speed_kt` (knots).
+    pub fn new(direction_deg_true: f64, speed_kt: f64) -> Self {
+        Wind {
+            direction_deg_true,
+            speed_kt,
+        }
+    }
+    /// Direction the wind is blowing from, in degrees true.
+    pub fn direction_deg_true(&self) -> f64 {
+        self.direction_deg_true
+    }
+    /// Wind speed in knots.
+    pub fn speed_kt(&self) -> f64 {
+        self.speed_kt
+    }
+    /// Splits the wind into components relative to an aircraft heading `heading_deg_true`.
+    pub fn components(&self, heading_deg_true: f64) -> WindComponents {
+        let angle = (self.direction_deg_true - heading_deg_true).to_radians();
+        WindComponents {
+            headwind_kt: self.speed_kt * angle.cos(),
+            crosswind_kt: self.speed_kt * angle.sin(),
+        }
+    }
+}
+
+/// Wind components relative to a heading, in knots.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+pub struct WindComponents {
+    headwind_kt: f64,
+    crosswind_kt: f64,
+}
+
+impl WindComponents {
+    /// Headwind component. Negative for a tailwind.
+    pub fn headwind_kt(&self) -> f64 {
+        self.headwind_kt
+    }
+    /// Tailwind component, or 0 if there is a headwind.
+    pub fn tailwind_kt(&self) -> f64 {
+        (-self.headwind_kt).max(0.0)
+    }
+    /// Crosswind component. Positive for wind from the right, negative for wind from the left.
+    pub fn crosswind_kt(&self) -> f64 {
+        self.crosswind_kt
+    }
+}
+
+/// Maximum crosswind and tailwind components for a runway end to be usable.
+///
+/// No limits are set by default.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Default)]
+pub struct WindLimits {
+    max_crosswind_kt: Option<f64>,
+    max_tailwind_kt: Option<f64>,
+}
+
+impl WindLimits {
+    /// Creates limits which accept any wind.
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Sets the maximum crosswind component, from either side.
+    pub fn max_crosswind_kt(mut self, kt: f64) -> Self {
+        self.max_crosswind_kt = Some(kt);
+        self
+    }
+    /// Sets the maximum tailwind component. Use 0 to only accept runway ends without a tailwind.
+    pub fn max_tailwind_kt(mut self, kt: f64) -> Self {
+        self.max_tailwind_kt = Some(kt);
+        self
+    }
+    /// `true` if `components` are within these limits.
+    pub fn allows(&self, components: &WindComponents) -> bool {
+        self.max_crosswind_kt
+            .is_none_or(|max| components.crosswind_kt().abs() <= max)
+            && self
+                .max_tailwind_kt
+                .is_none_or(|max| components.tailwind_kt() <= max)
+    }
+}
+
+/// Wind components for one end of a runway.
+#[derive(Debug, Clone, Copy)]
+pub struct RunwayWind<'a> {
+    end: RunwayEnd<'a>,
+    components: WindComponents,
+}
+
+impl<'a> RunwayWind<'a> {
+    /// The runway end the components apply to.
+    pub fn end(&self) -> RunwayEnd<'a> {
+        self.end
+    }
+    /// Wind components for an aircraft using this runway end.
+    pub fn components(&self) -> WindComponents {
+        self.components
+    }
+}
+
+/// Returns the wind components for each end of the open (not [`closed`](Runway::closed())) runways
+/// in `runways` which has a known true heading, in the order given.
+pub fn runway_winds<'a>(
+    runways: impl IntoIterator<Item = &'a Runway>,
+    wind: Wind,
+) -> Vec<RunwayWind<'a>> {
+    runways
+        .into_iter()
+        .filter(|runway| !runway.closed())
+        .flat_map(Runway::ends)
+        .filter_map(|end| {
+            Some(RunwayWind {
+                end,
+                components: wind.components(end.heading_deg_true()?),
+            })
+        })
+        .collect()
+}
+
+/// Returns the runway ends from [`runway_winds`] which are within `limits`, best first.
+///
+/// Ends are ranked by headwind component (highest first), then by crosswind component (lowest
+/// first), then by runway length (longest first). Components are compared to the nearest knot,
+/// so parallel runways and calm wind prefer the longest runway.
+pub fn usable_runway_ends<'a>(
+    runways: impl IntoIterator<Item = &'a Runway>,
+    wind: Wind,
+    limits: &WindLimits,
+) -> Vec<RunwayWind<'a>> {
+    let mut ends = runway_winds(runways, wind);
+    ends.retain(|end| limits.allows(&end.components));
+    ends.sort_by(|a, b| {
+        let headwind = |end: &RunwayWind| end.components.headwind_kt.round() as i64;
+        let crosswind = |end: &RunwayWind| end.components.crosswind_kt.abs().round() as i64;
+        headwind(b)
+            .cmp(&headwind(a))
+            .then_with(|| crosswind(a).cmp(&crosswind(b)))
+            .then_with(|| b.end.runway().length_ft().cmp(&a.end.runway().length_ft()))
+    });
+    ends
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{self, runway};
+
+    #[test]
+    fn limits_are_inclusive() {
+        // exactly 10 kt from the right, and exactly 5 kt from behind
+        let crosswind = Wind::new(90.0, 10.0).components(0.0);
+        let tailwind = Wind::new(180.0, 5.0).components(0.0);
+        assert_eq!(10.0, crosswind.crosswind_kt());
+        assert_eq!(5.0, tailwind.tailwind_kt());
+        assert_eq!(0.0, crosswind.tailwind_kt());
+        let limits = WindLimits::new();
+        assert!(limits.allows(&crosswind) && limits.allows(&tailwind));
+        let limits = WindLimits::new()
+            .max_crosswind_kt(10.0)
+            .max_tailwind_kt(5.0);
+        assert!(limits.allows(&crosswind) && limits.allows(&tailwind));
+        let limits = WindLimits::new().max_crosswind_kt(9.9).max_tailwind_kt(4.9);
+        assert!(!limits.allows(&crosswind) && !limits.allows(&tailwind));
+        // wind from the left counts as well
+        assert!(!limits.allows(&Wind::new(270.0, 10.0).components(0.0)));
+    }
+
+    #[test]
+    fn ranking_and_exclusions() {
+        let runway = |id, low, high, length_ft, low_heading: f64| {
+            runway(id, 1)
+                .set("le_ident", low)
+                .set("he_ident", high)
+                .set("length_ft", length_ft)
+                .set("le_heading_degT", low_heading)
+                .set("he_heading_degT", low_heading + 180.0)
+        };
+        let runways = test_support::runways([
+            runway(1, "09L", "27R", 3000, 90.0),
+            runway(2, "04", "22", 5000, 40.0),
+            // closed, and without headings
+            runway(3, "09C", "27C", 9000, 90.0).set("closed", 1),
+            test_support::runway(4, 1)
+                .set("le_ident", "18")
+                .set("he_ident", "36"),
+            runway(5, "09R", "27L", 4000, 90.0),
+        ]);
+        let wind = Wind::new(250.0, 20.0);
+        let idents = |ends: Vec<RunwayWind>| {
+            ends.iter()
+                .map(|end| end.end().ident().to_string())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            vec!["09L", "27R", "04", "22", "09R", "27L"],
+            idents(runway_winds(runways.values(), wind))
+        );
+        // the parallel runways have the same components, so the longer one ranks first
+        assert_eq!(
+            vec!["27L", "27R", "22", "04", "09R", "09L"],
+            idents(usable_runway_ends(
+                runways.values(),
+                wind,
+                &WindLimits::new()
+            ))
+        );
+        // 22 has 10 kt crosswind, 27L/27R about 6.8 kt
+        let limits = WindLimits::new().max_tailwind_kt(0.0);
+        assert_eq!(
+            vec!["27L", "27R", "22"],
+            idents(usable_runway_ends(runways.values(), wind, &limits))
+        );
+        let limits = limits.max_crosswind_kt(8.0);
+        assert_eq!(
+            vec!["27L", "27R"],
+            idents(usable_runway_ends(runways.values(), wind, &limits))
+        );
+        let limits = limits.max_crosswind_kt(5.0);
+        assert!(usable_runway_ends(runways.values(), wind, &limits).is_empty());
+    }
+}